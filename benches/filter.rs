@@ -0,0 +1,134 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use openapiv3::OpenAPI;
+use openapiv3_filter::filter::openapi::{FilteringParameters, OpenAPIFilter};
+use serde_json::{Map, Value, json};
+use std::hint::black_box;
+
+/// Number of resources in the synthetic document, each resource has two paths and two schemas
+const SYNTHETIC_RESOURCES: usize = 250;
+
+/// Builds a document with `SYNTHETIC_RESOURCES` tagged resources whose schemas reference the schemas of the previous resource in their group of ten
+fn synthetic_document() -> OpenAPI {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+    for index in 0..SYNTHETIC_RESOURCES {
+        let resource = format!("resource{}", index);
+        let schema_ref = json!({"$ref": format!("#/components/schemas/Resource{}", index)});
+        paths.insert(
+            format!("/{}", resource),
+            json!({
+                "get": {
+                    "tags": [format!("group{}", index % 10)],
+                    "responses": {"200": {
+                        "description": "List",
+                        "content": {"application/json": {"schema": {"type": "array", "items": schema_ref}}}
+                    }}
+                },
+                "post": {
+                    "tags": [format!("group{}", index % 10)],
+                    "requestBody": {"content": {"application/json": {"schema": schema_ref}}},
+                    "responses": {"201": {"description": "Created"}}
+                }
+            }),
+        );
+        paths.insert(
+            format!("/{}/{{id}}", resource),
+            json!({
+                "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "string"}}],
+                "delete": {
+                    "tags": [format!("group{}", index % 10)],
+                    "responses": {"204": {"description": "Deleted"}}
+                }
+            }),
+        );
+        let mut properties = json!({
+            "id": {"type": "string"},
+            "details": {"$ref": format!("#/components/schemas/Resource{}Details", index)}
+        });
+        // Every group of ten resources forms its own reference chain
+        if index % 10 > 0 {
+            properties["previous"] =
+                json!({"$ref": format!("#/components/schemas/Resource{}", index - 1)});
+        }
+        schemas.insert(
+            format!("Resource{}", index),
+            json!({"type": "object", "properties": properties}),
+        );
+        schemas.insert(
+            format!("Resource{}Details", index),
+            json!({"type": "object", "properties": {"description": {"type": "string"}}}),
+        );
+    }
+    serde_json::from_value(json!({
+        "openapi": "3.0.3",
+        "info": {"title": "Synthetic", "version": "1.0.0"},
+        "paths": Value::Object(paths),
+        "components": {"schemas": Value::Object(schemas)}
+    }))
+    .expect("synthetic document is a valid OpenAPI document")
+}
+
+/// Representative filters shared by all the benchmarked documents
+fn filters(path: &str, tag: &str) -> Vec<(&'static str, FilteringParameters)> {
+    vec![
+        (
+            "path",
+            FilteringParameters {
+                paths: Some(vec![path.to_string()]),
+                ..Default::default()
+            },
+        ),
+        (
+            "tag",
+            FilteringParameters {
+                tags: Some(vec![tag.to_string()]),
+                ..Default::default()
+            },
+        ),
+        (
+            "combined",
+            FilteringParameters {
+                paths: Some(vec![path.to_string()]),
+                tags: Some(vec![tag.to_string()]),
+                methods: Some(vec![String::from("get")]),
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+fn bench_document(c: &mut Criterion, name: &str, document: &OpenAPI, path: &str, tag: &str) {
+    let mut group = c.benchmark_group(name);
+    for (filter_name, _) in filters(path, tag) {
+        group.bench_function(filter_name, |b| {
+            b.iter_batched(
+                || {
+                    filters(path, tag)
+                        .into_iter()
+                        .find(|(name, _)| *name == filter_name)
+                        .map(|(_, filter)| filter)
+                        .unwrap()
+                },
+                |filter| black_box(document.filter_by_parameters(filter).unwrap()),
+                criterion::BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_filter_by_parameters(c: &mut Criterion) {
+    let petstore: OpenAPI = serde_yaml::from_str(include_str!("../tests/resources/petstore.yaml"))
+        .expect("petstore fixture is a valid OpenAPI document");
+    bench_document(c, "petstore", &petstore, "/pet*", "pet");
+    bench_document(
+        c,
+        "synthetic",
+        &synthetic_document(),
+        "/resource1*",
+        "group3",
+    );
+}
+
+criterion_group!(benches, bench_filter_by_parameters);
+criterion_main!(benches);
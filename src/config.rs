@@ -0,0 +1,288 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File names that are looked up from the working directory when discovering default filtering parameters
+const DISCOVERED_CONFIG_FILES: [&str; 3] = [
+    "openapiv3-filter.toml",
+    "openapiv3-filter.yaml",
+    "openapiv3-filter.yml",
+];
+
+/// Filtering parameters read from a configuration file
+///
+/// Precedence of the parameters is command line arguments > environment variables > explicit `--filter-file` > discovered configuration file.
+/// Precedence is applied per parameter, so a configuration file can provide defaults for parameters not given on the command line.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    ///path name patterns to match
+    pub paths: Option<Vec<String>>,
+    ///http methods to match
+    pub methods: Option<Vec<String>>,
+    ///tag names to match
+    pub tags: Option<Vec<String>>,
+    ///tag group names to match
+    pub tag_groups: Option<Vec<String>>,
+    ///security scheme names to match
+    pub security: Option<Vec<String>>,
+}
+
+impl Config {
+    /// Combines two configurations so that parameters of this configuration take precedence over the parameters of `defaults`
+    ///
+    /// # Arguments
+    ///
+    /// * `defaults` - configuration with lower precedence
+    ///
+    /// # Returns
+    ///
+    /// * `Config` - configuration where missing parameters are filled from `defaults`
+    pub fn or(self, defaults: Config) -> Config {
+        Config {
+            paths: self.paths.or(defaults.paths),
+            methods: self.methods.or(defaults.methods),
+            tags: self.tags.or(defaults.tags),
+            tag_groups: self.tag_groups.or(defaults.tag_groups),
+            security: self.security.or(defaults.security),
+        }
+    }
+}
+
+/// Finds a configuration file with a well known name from the directory.
+///
+/// # Arguments
+///
+/// * `directory` - A reference to the directory where the configuration file is looked up from.
+///
+/// # Returns
+///
+/// * `Option<PathBuf>` - The path of the first found configuration file, or None if the directory does not have one.
+pub fn discover_config(directory: &Path) -> Option<PathBuf> {
+    DISCOVERED_CONFIG_FILES
+        .iter()
+        .map(|file_name| directory.join(file_name))
+        .find(|path| path.is_file())
+}
+
+/// Prefix of the environment variables providing filtering parameters, e.g. `OPENAPIV3_FILTER_TAG`
+const ENV_PREFIX: &str = "OPENAPIV3_FILTER_";
+
+/// Reads filtering parameters from the `OPENAPIV3_FILTER_PATH`, `_METHOD`, `_TAG` and `_SECURITY` environment variables.
+///
+/// Each variable holds a comma-separated list, e.g. `OPENAPIV3_FILTER_TAG=pet,store`. Values are trimmed and empty
+/// values are ignored, so a variable that is empty or only has separators leaves its parameter unset.
+///
+/// # Arguments
+///
+/// * `lookup` - Returns the value of an environment variable by its name, e.g. `|name| std::env::var(name).ok()`.
+///
+/// # Returns
+///
+/// * `Config` - The parameters given in the environment variables.
+pub fn env_config(lookup: impl Fn(&str) -> Option<String>) -> Config {
+    let list = |name: &str| {
+        lookup(&format!("{}{}", ENV_PREFIX, name)).and_then(|value| {
+            let values: Vec<String> = value
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+                .collect();
+            (!values.is_empty()).then_some(values)
+        })
+    };
+    Config {
+        paths: list("PATH"),
+        methods: list("METHOD"),
+        tags: list("TAG"),
+        tag_groups: None,
+        security: list("SECURITY"),
+    }
+}
+
+/// Keys of the `key:value` lines of plain text configuration files, named like the command line options
+const PLAIN_TEXT_KEYS: [&str; 5] = ["path", "method", "tag", "tag-group", "security"];
+
+/// Reads filtering parameters from a TOML, plain text or YAML configuration file.
+///
+/// Files with `.toml` extension are parsed as TOML, files with `.txt` extension as plain text and all other files as YAML.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the path of the configuration file.
+///
+/// # Returns
+///
+/// * `Result<Config, Box<dyn std::error::Error>>` - A Result containing the configuration, or an error if the file cannot be read or parsed.
+pub fn load_config(path: &Path) -> Result<Config, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("Could not read filter file {}: {}", path.display(), error))?;
+    let has_extension = |expected: &str| {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case(expected))
+    };
+    let config = if has_extension("toml") {
+        toml::from_str(&contents).map_err(|error| error.to_string())
+    } else if has_extension("txt") {
+        parse_plain_text(&contents)
+    } else {
+        serde_yaml::from_str(&contents).map_err(|error| error.to_string())
+    };
+    config.map_err(|error| {
+        format!("Could not parse filter file {}: {}", path.display(), error).into()
+    })
+}
+
+/// Parses a plain text configuration with one `key:value` filter per line.
+///
+/// Keys are the names of the command line options, e.g. `path:/pets/*` or `tag:users`. Repeated keys add values to the same parameter.
+/// Blank lines and lines starting with `#` are ignored.
+///
+/// # Arguments
+///
+/// * `contents` - Contents of the configuration file.
+///
+/// # Returns
+///
+/// * `Result<Config, String>` - The configuration, or an error naming the number of the first malformed line.
+fn parse_plain_text(contents: &str) -> Result<Config, String> {
+    let mut config = Config::default();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_number = index + 1;
+        let Some((key, value)) = line.split_once(':') else {
+            return Err(format!(
+                "line {}: expected a 'key:value' filter, found '{}'",
+                line_number, line
+            ));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if value.is_empty() {
+            return Err(format!("line {}: missing value for '{}'", line_number, key));
+        }
+        let parameter = match key {
+            "path" => &mut config.paths,
+            "method" => &mut config.methods,
+            "tag" => &mut config.tags,
+            "tag-group" => &mut config.tag_groups,
+            "security" => &mut config.security,
+            _ => {
+                return Err(format!(
+                    "line {}: unknown filter '{}', expected one of {}",
+                    line_number,
+                    key,
+                    PLAIN_TEXT_KEYS.join(", ")
+                ));
+            }
+        };
+        parameter
+            .get_or_insert_with(Vec::new)
+            .push(value.to_string());
+    }
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_prefers_own_parameters_over_defaults() {
+        let config = Config {
+            tags: Some(vec![String::from("user")]),
+            ..Default::default()
+        };
+        let defaults = Config {
+            tags: Some(vec![String::from("item")]),
+            methods: Some(vec![String::from("get")]),
+            ..Default::default()
+        };
+
+        let merged = config.or(defaults);
+
+        assert_eq!(merged.tags, Some(vec![String::from("user")]));
+        assert_eq!(merged.methods, Some(vec![String::from("get")]));
+    }
+
+    #[test]
+    fn it_reads_comma_separated_parameters_from_environment() {
+        let config = env_config(|name| match name {
+            "OPENAPIV3_FILTER_PATH" => Some(String::from("/pet/*, /store/*")),
+            "OPENAPIV3_FILTER_METHOD" => Some(String::from("get")),
+            "OPENAPIV3_FILTER_TAG" => Some(String::from(" , ")),
+            "OPENAPIV3_FILTER_SECURITY" => Some(String::from("api_key,")),
+            _ => None,
+        });
+
+        assert_eq!(
+            config,
+            Config {
+                paths: Some(vec![String::from("/pet/*"), String::from("/store/*")]),
+                methods: Some(vec![String::from("get")]),
+                security: Some(vec![String::from("api_key")]),
+                ..Default::default()
+            }
+        );
+        assert_eq!(env_config(|_| None), Config::default());
+    }
+
+    #[test]
+    fn it_loads_toml_and_yaml_configurations() {
+        let toml_config = load_config(Path::new("tests/resources/config/get-filter.toml")).unwrap();
+        let yaml_config =
+            load_config(Path::new("tests/resources/config/openapiv3-filter.yaml")).unwrap();
+
+        assert_eq!(toml_config.methods, Some(vec![String::from("get")]));
+        assert_eq!(yaml_config.methods, Some(vec![String::from("post")]));
+    }
+
+    #[test]
+    fn it_loads_plain_text_configuration_with_comments() {
+        let config = load_config(Path::new("tests/resources/config/filters.txt")).unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                paths: Some(vec![String::from("/pet/*"), String::from("/store/*")]),
+                tags: Some(vec![String::from("pet")]),
+                methods: Some(vec![String::from("get")]),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn it_reports_malformed_plain_text_lines() {
+        assert_eq!(
+            parse_plain_text("# comment\n\npath /pets"),
+            Err(String::from(
+                "line 3: expected a 'key:value' filter, found 'path /pets'"
+            ))
+        );
+        assert_eq!(
+            parse_plain_text("tag:"),
+            Err(String::from("line 1: missing value for 'tag'"))
+        );
+        assert_eq!(
+            parse_plain_text("path:/pets\ncolor:red"),
+            Err(String::from(
+                "line 2: unknown filter 'color', expected one of path, method, tag, tag-group, security"
+            ))
+        );
+    }
+
+    #[test]
+    fn it_discovers_configuration_from_directory() {
+        assert_eq!(
+            discover_config(Path::new("tests/resources/config")),
+            Some(PathBuf::from(
+                "tests/resources/config/openapiv3-filter.yaml"
+            ))
+        );
+        assert_eq!(discover_config(Path::new("tests/snapshots")), None);
+    }
+}
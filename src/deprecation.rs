@@ -0,0 +1,202 @@
+use clap::ValueEnum;
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, ReferenceOr};
+use openapiv3_filter::filter::content::openapi31_fields::resolve_path_item;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Extension holding the date after which a deprecated operation or schema is removed
+const SUNSET_EXTENSION: &str = "x-sunset";
+
+/// Output format of the deprecation report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// JSON object with `operations` and `schemas` lists
+    Json,
+    /// Plain text table with one deprecated operation or schema per row
+    Table,
+}
+
+/// Deprecated operation of the document
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct DeprecatedOperation {
+    path: String,
+    method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operation_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunset: Option<String>,
+}
+
+/// Deprecated schema of the document components
+#[derive(Debug, Serialize, PartialEq)]
+struct DeprecatedSchema {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sunset: Option<String>,
+}
+
+/// Deprecated operations and schemas of the document
+#[derive(Debug, Default, Serialize, PartialEq)]
+struct DeprecationReport {
+    operations: Vec<DeprecatedOperation>,
+    schemas: Vec<DeprecatedSchema>,
+}
+
+/// Writes a report of the deprecated operations and component schemas of the document.
+///
+/// The report lists the path, method and `operationId` of every deprecated operation, the name of every deprecated schema
+/// and the value of their `x-sunset` extension.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the document, usually already filtered.
+/// * `format` - Output format of the report.
+///
+/// # Returns
+///
+/// * `String` - The report in the requested format.
+pub fn deprecation_report(openapi: &OpenAPI, format: ReportFormat) -> String {
+    let report = collect_deprecations(openapi);
+    match format {
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(&report).expect("Reports are always serializable")
+        }
+        ReportFormat::Table => report_table(&report),
+    }
+}
+
+fn collect_deprecations(openapi: &OpenAPI) -> DeprecationReport {
+    let mut report = DeprecationReport::default();
+    for (path_name, path_item) in openapi.paths.iter() {
+        let Some(path_item) = resolve_path_item(path_item, openapi.components.as_ref()) else {
+            continue;
+        };
+        for (method, operation) in path_item
+            .iter()
+            .filter(|(_, operation)| operation.deprecated)
+        {
+            report.operations.push(DeprecatedOperation {
+                path: path_name.clone(),
+                method: method.to_string(),
+                operation_id: operation.operation_id.clone(),
+                sunset: sunset(&operation.extensions),
+            });
+        }
+    }
+    let schemas = openapi
+        .components
+        .iter()
+        .flat_map(|components| &components.schemas);
+    for (name, schema) in schemas {
+        if let ReferenceOr::Item(schema) = schema
+            && schema.schema_data.deprecated
+        {
+            report.schemas.push(DeprecatedSchema {
+                name: name.clone(),
+                sunset: sunset(&schema.schema_data.extensions),
+            });
+        }
+    }
+    report
+}
+
+/// Reads the sunset date from the extensions, writing values other than strings as JSON
+fn sunset(extensions: &IndexMap<String, Value>) -> Option<String> {
+    extensions.get(SUNSET_EXTENSION).map(|sunset| match sunset {
+        Value::String(sunset) => sunset.clone(),
+        sunset => sunset.to_string(),
+    })
+}
+
+/// Writes the report as a table with columns aligned by padding with spaces
+fn report_table(report: &DeprecationReport) -> String {
+    let missing = || String::from("-");
+    let mut rows: Vec<[String; 5]> = vec![[
+        String::from("TYPE"),
+        String::from("PATH OR NAME"),
+        String::from("METHOD"),
+        String::from("OPERATION ID"),
+        String::from("SUNSET"),
+    ]];
+    rows.extend(report.operations.iter().map(|operation| {
+        [
+            String::from("operation"),
+            operation.path.clone(),
+            operation.method.clone(),
+            operation.operation_id.clone().unwrap_or_else(missing),
+            operation.sunset.clone().unwrap_or_else(missing),
+        ]
+    }));
+    rows.extend(report.schemas.iter().map(|schema| {
+        [
+            String::from("schema"),
+            schema.name.clone(),
+            missing(),
+            missing(),
+            schema.sunset.clone().unwrap_or_else(missing),
+        ]
+    }));
+    let widths: Vec<usize> = (0..5)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_reports_deprecated_operations_and_schemas() {
+        let openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Deprecations", "version": "1.0.0"},
+            "paths": {
+                "/pets": {
+                    "get": {"operationId": "listPets", "deprecated": true, "x-sunset": "2026-12-31", "responses": {}},
+                    "post": {"responses": {}}
+                },
+                "/pets/{petId}": {"delete": {"deprecated": true, "responses": {}}}
+            },
+            "components": {"schemas": {
+                "Pet": {"type": "object"},
+                "LegacyPet": {"type": "object", "deprecated": true, "x-sunset": "2026-06-30"}
+            }}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            deprecation_report(&openapi, ReportFormat::Table),
+            "TYPE       PATH OR NAME   METHOD  OPERATION ID  SUNSET\n\
+             operation  /pets          get     listPets      2026-12-31\n\
+             operation  /pets/{petId}  delete  -             -\n\
+             schema     LegacyPet      -       -             2026-06-30"
+        );
+        let report: Value =
+            serde_json::from_str(&deprecation_report(&openapi, ReportFormat::Json)).unwrap();
+        assert_eq!(
+            report,
+            json!({
+                "operations": [
+                    {"path": "/pets", "method": "get", "operationId": "listPets", "sunset": "2026-12-31"},
+                    {"path": "/pets/{petId}", "method": "delete"}
+                ],
+                "schemas": [{"name": "LegacyPet", "sunset": "2026-06-30"}]
+            })
+        );
+    }
+}
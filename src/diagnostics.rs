@@ -0,0 +1,40 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether warnings and informational messages are suppressed, set once from the `--quiet` argument
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether warnings and informational messages are suppressed
+///
+/// # Arguments
+///
+/// * `quiet` - True to suppress all the warnings written to stderr
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Writes a warning to stderr unless warnings are suppressed with `--quiet`
+///
+/// Warnings never affect the exit code. Errors are reported separately and are printed even when quiet.
+///
+/// # Arguments
+///
+/// * `message` - The warning message
+pub fn warn(message: impl Display) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("warning: {}", message);
+    }
+}
+
+/// Writes an informational message to stderr unless messages are suppressed with `--quiet`
+///
+/// Unlike warnings, informational messages are written without a prefix, e.g. the summary of the filtered document.
+///
+/// # Arguments
+///
+/// * `message` - The message
+pub fn info(message: impl Display) {
+    if !QUIET.load(Ordering::Relaxed) {
+        eprintln!("{}", message);
+    }
+}
@@ -0,0 +1,75 @@
+use clap::ValueEnum;
+use openapiv3_filter::filter::content::schema_transformer;
+use serde_json::Value;
+
+/// JSON Schema dialect the schemas of the output document are rewritten to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaDialect {
+    /// JSON Schema 2020-12, the dialect of OpenAPI 3.1
+    #[value(name = "2020-12")]
+    Draft2020_12,
+}
+
+/// Rewrites the component schemas and the inline schemas of the document to the dialect.
+///
+/// Inline schemas are the schemas of parameters, headers and media types, wherever they are declared. The `openapi`
+/// version of the document is left as it is.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the JSON value of the whole document.
+/// * `dialect` - The dialect the schemas are rewritten to.
+pub fn convert_schemas(document: &mut Value, dialect: SchemaDialect) {
+    let convert = match dialect {
+        SchemaDialect::Draft2020_12 => schema_transformer::convert_to_json_schema_2020_12,
+    };
+    // Component schemas are keyed by name, so they are taken out to not be mistaken for inline schemas
+    let schemas = document
+        .get_mut("components")
+        .and_then(|components| components.as_object_mut())
+        .and_then(|components| components.get_mut("schemas"))
+        .map(Value::take);
+    schema_transformer::transform_inline_schemas(document, convert);
+    if let Some(Value::Object(mut schemas)) = schemas {
+        schemas.values_mut().for_each(convert);
+        document["components"]["schemas"] = Value::Object(schemas);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_converts_component_and_inline_schemas() {
+        let mut document = json!({
+            "openapi": "3.0.3",
+            "paths": {"/pets": {"get": {
+                "parameters": [{"name": "limit", "in": "query", "schema": {"type": "integer", "nullable": true}}],
+                "responses": {"200": {"description": "Pets", "content": {"application/json": {
+                    "schema": {"$ref": "#/components/schemas/Pet"},
+                    "example": {"name": "Rex"}
+                }}}}
+            }}},
+            "components": {"schemas": {
+                "Pet": {"type": "object", "properties": {"schema": {"type": "string", "example": "Rex"}}}
+            }}
+        });
+
+        convert_schemas(&mut document, SchemaDialect::Draft2020_12);
+
+        assert_eq!(
+            document["paths"]["/pets"]["get"]["parameters"][0]["schema"],
+            json!({"type": ["integer", "null"]})
+        );
+        assert_eq!(
+            document["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]["example"],
+            json!({"name": "Rex"})
+        );
+        assert_eq!(
+            document["components"]["schemas"]["Pet"],
+            json!({"type": "object", "properties": {"schema": {"type": "string", "examples": ["Rex"]}}})
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use crate::filter::content::reference_collector::RecursionLimitExceeded;
+use crate::parser::{InvalidDocumentError, InvalidEncodingError, UndefinedEnvVariableError};
+use std::io;
+use thiserror::Error;
+
+/// Error returned when the input document cannot be read or parsed
+#[derive(Debug, Error)]
+pub enum ParseError {
+    /// The document could not be read from the file, URL or stdin
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The document is not valid YAML, or does not have the structure of an OpenAPI document
+    #[error(transparent)]
+    YamlParse(#[from] serde_yaml::Error),
+    /// The document is not valid JSON, or does not have the structure of an OpenAPI document
+    #[error(transparent)]
+    JsonParse(#[from] serde_json::Error),
+    /// The document is empty or null
+    #[error("Input document is empty or null")]
+    EmptyDocument,
+    /// The document is valid YAML or JSON but is missing the required fields of an OpenAPI document
+    #[error(transparent)]
+    InvalidDocument(#[from] InvalidDocumentError),
+    /// The document is not valid UTF-8
+    #[error(transparent)]
+    InvalidEncoding(#[from] InvalidEncodingError),
+    /// The document references an undefined environment variable and substitution was requested
+    #[error(transparent)]
+    UndefinedEnvVariable(#[from] UndefinedEnvVariableError),
+    /// The document declares a version other than OpenAPI 3, e.g. a Swagger 2.0 document
+    #[error("Unsupported OpenAPI version '{0}', only OpenAPI 3 documents are supported")]
+    UnsupportedVersion(String),
+}
+
+/// Error returned when a document cannot be filtered
+#[derive(Debug, Error)]
+pub enum FilterError {
+    /// The input document could not be parsed before filtering
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// A pattern given as a filtering parameter is invalid
+    #[error("Invalid {kind} '{pattern}': {reason}")]
+    InvalidPattern {
+        /// Kind of the pattern, e.g. `JSONPath expression`
+        kind: &'static str,
+        /// The pattern as it was given
+        pattern: String,
+        /// Why the pattern is invalid
+        reason: String,
+    },
+    /// References of the input document do not resolve to any content, found when checking the input
+    #[error("{}", describe_dangling_refs(references))]
+    DanglingRefs {
+        /// Pairs of the location of the `$ref` and the unresolved reference
+        references: Vec<(String, String)>,
+    },
+    /// An exact path given as a filtering parameter has none of the requested methods
+    #[error("Path '{path}' has no {methods} operation")]
+    MissingOperation {
+        /// The exact path as it was given
+        path: String,
+        /// The requested methods, e.g. `get or post`
+        methods: String,
+    },
+    /// A path key cannot be merged into the path that has the same key without its trailing slash
+    #[error(
+        "Path '{path}' collides with '{normalized}' after removing the trailing slash: {reason}"
+    )]
+    PathCollision {
+        /// The path key as it is in the document, e.g. `/pets/`
+        path: String,
+        /// The path key without the trailing slash, e.g. `/pets`
+        normalized: String,
+        /// Why the path items cannot be merged
+        reason: String,
+    },
+    /// The base path of the servers cannot be moved to the path keys
+    #[error("Cannot embed the base path of the servers: {0}")]
+    BasePath(String),
+    /// The document is nested deeper than the maximum recursion depth
+    #[error(transparent)]
+    RecursionLimitExceeded(#[from] RecursionLimitExceeded),
+    /// The filtered document could not be converted or written
+    #[error("Failed to write the document: {0}")]
+    Serialization(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// How many dangling references are listed in the error message
+const LISTED_DANGLING_REFS: usize = 5;
+
+/// Describes the dangling references, listing the first ones with their locations
+fn describe_dangling_refs(references: &[(String, String)]) -> String {
+    let mut description = format!(
+        "Input document has {} reference(s) that do not resolve to any content:",
+        references.len()
+    );
+    for (location, reference) in references.iter().take(LISTED_DANGLING_REFS) {
+        description.push_str(&format!("\n  '{}' at {}", reference, location));
+    }
+    if references.len() > LISTED_DANGLING_REFS {
+        description.push_str(&format!(
+            "\n  and {} more",
+            references.len() - LISTED_DANGLING_REFS
+        ));
+    }
+    description
+}
+
+impl From<serde_json::Error> for FilterError {
+    fn from(error: serde_json::Error) -> Self {
+        FilterError::Serialization(Box::new(error))
+    }
+}
+
+impl From<serde_yaml::Error> for FilterError {
+    fn from(error: serde_yaml::Error) -> Self {
+        FilterError::Serialization(Box::new(error))
+    }
+}
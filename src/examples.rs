@@ -0,0 +1,278 @@
+use indexmap::IndexMap;
+use openapiv3::{Components, MediaType, OpenAPI, ReferenceOr};
+use openapiv3_filter::ParseError;
+use openapiv3_filter::filter::content::openapi31_fields::resolve_path_item;
+use openapiv3_filter::filter::content::reference_collector::pointer_segments;
+use openapiv3_filter::parser;
+use serde_json::{Map, Value, json};
+use std::time::Duration;
+
+/// Extracts the response examples of the operations into a document for mock servers.
+///
+/// The examples are keyed by path, lowercase method, status code and media type. A media type with an `example` is written
+/// as `{"example": ...}` and a media type with `examples` as `{"examples": {"name": ...}}` holding only the example values.
+/// References to the examples and responses of the components are resolved, while examples given only as an
+/// `externalValue` are skipped. Operations without response examples are left out.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the document, usually already filtered.
+///
+/// # Returns
+///
+/// * `String` - The examples as a pretty printed JSON object.
+pub fn extract_examples(openapi: &OpenAPI) -> String {
+    let components = openapi.components.as_ref();
+    let mut paths = Map::new();
+    for (path_name, path_item) in openapi.paths.iter() {
+        let Some(path_item) = resolve_path_item(path_item, components) else {
+            continue;
+        };
+        let mut operations = Map::new();
+        for (method, operation) in path_item.iter() {
+            let responses = operation
+                .responses
+                .responses
+                .iter()
+                .map(|(status, response)| (status.to_string(), response))
+                .chain(
+                    operation
+                        .responses
+                        .default
+                        .iter()
+                        .map(|response| (String::from("default"), response)),
+                );
+            let mut statuses = Map::new();
+            for (status, response) in responses {
+                let Some(response) = resolve(response, components.map(|c| &c.responses)) else {
+                    continue;
+                };
+                let media_types: Map<String, Value> = response
+                    .content
+                    .iter()
+                    .filter_map(|(media_type_name, media_type)| {
+                        media_type_examples(media_type, components)
+                            .map(|examples| (media_type_name.clone(), examples))
+                    })
+                    .collect();
+                if !media_types.is_empty() {
+                    statuses.insert(status, Value::Object(media_types));
+                }
+            }
+            if !statuses.is_empty() {
+                operations.insert(method.to_lowercase(), Value::Object(statuses));
+            }
+        }
+        if !operations.is_empty() {
+            paths.insert(path_name.clone(), Value::Object(operations));
+        }
+    }
+    serde_json::to_string_pretty(&Value::Object(paths)).expect("Examples are always serializable")
+}
+
+/// Fetches the examples given as an `externalValue` and inlines them as the `value` of the example.
+///
+/// Examples of the components, media types, parameters and headers are inlined, while examples that already have a
+/// `value` are kept as they are. Relative locations are resolved against the location of the document.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the document, usually already filtered.
+/// * `document` - The file name or URL of the input document, or "-" for stdin.
+/// * `timeout` - The maximum duration of fetching each example from a URL, or None for no limit.
+///
+/// # Returns
+///
+/// * `Result<OpenAPI, Box<dyn std::error::Error>>` - The document with the inlined examples, or the error of the first example that could not be fetched.
+pub fn inline_external_values(
+    openapi: &OpenAPI,
+    document: &str,
+    timeout: Option<Duration>,
+) -> Result<OpenAPI, Box<dyn std::error::Error>> {
+    let mut value = serde_json::to_value(openapi)?;
+    inline_external_examples(&mut value, document, timeout)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Inlines the external examples of the `examples` maps under the value
+fn inline_external_examples(
+    value: &mut Value,
+    document: &str,
+    timeout: Option<Duration>,
+) -> Result<(), ParseError> {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(examples)) = map.get_mut("examples") {
+                for example in examples.values_mut() {
+                    let Value::Object(example) = example else {
+                        continue;
+                    };
+                    if example.contains_key("value") {
+                        continue;
+                    }
+                    if let Some(Value::String(location)) = example.get("externalValue") {
+                        let fetched = parser::read_external_value(location, document, timeout)?;
+                        example.shift_remove("externalValue");
+                        example.insert(String::from("value"), fetched);
+                    }
+                }
+            }
+            for value in map.values_mut() {
+                inline_external_examples(value, document, timeout)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                inline_external_examples(item, document, timeout)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns the `example` or the resolved `examples` values of a media type, or None when it has neither
+fn media_type_examples(media_type: &MediaType, components: Option<&Components>) -> Option<Value> {
+    if let Some(example) = &media_type.example {
+        return Some(json!({ "example": example }));
+    }
+    let examples: Map<String, Value> = media_type
+        .examples
+        .iter()
+        .filter_map(|(name, example)| {
+            resolve(example, components.map(|c| &c.examples))
+                .and_then(|example| example.value.clone())
+                .map(|value| (name.clone(), value))
+        })
+        .collect();
+    (!examples.is_empty()).then(|| json!({ "examples": examples }))
+}
+
+/// Resolves a local reference to a component of the given category, following references between the components.
+/// External references are not resolved.
+fn resolve<'a, T>(
+    item: &'a ReferenceOr<T>,
+    category: Option<&'a IndexMap<String, ReferenceOr<T>>>,
+) -> Option<&'a T> {
+    let mut item = item;
+    // Each component is visited at most once, which stops reference cycles
+    for _ in 0..=category.map_or(0, IndexMap::len) {
+        match item {
+            ReferenceOr::Item(item) => return Some(item),
+            ReferenceOr::Reference { reference } => {
+                // Names are escaped in the pointer, e.g. `Not~1Found` for `Not/Found`
+                let segments = pointer_segments(reference.strip_prefix("#/components/")?);
+                let [_, name] = segments.as_slice() else {
+                    return None;
+                };
+                item = category?.get(name)?;
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapiv3_filter::filter::openapi::{FilteringParameters, OpenAPIFilter};
+    use openapiv3_filter::parser::ParseOptions;
+
+    #[test]
+    fn it_extracts_response_examples_by_path_method_status_and_media_type() {
+        let openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Examples", "version": "1.0.0"},
+            "paths": {
+                "/pets": {
+                    "get": {"responses": {
+                        "200": {"description": "Pets", "content": {
+                            "application/json": {"example": [{"name": "Rex"}]},
+                            "text/plain": {"schema": {"type": "string"}}
+                        }},
+                        "default": {"$ref": "#/components/responses/Error"}
+                    }},
+                    "post": {"responses": {"201": {"description": "Created"}}}
+                },
+                "/health": {"get": {"responses": {"200": {"description": "Healthy"}}}}
+            },
+            "components": {
+                "responses": {"Error": {"description": "Error", "content": {"application/json": {"examples": {
+                    "notFound": {"$ref": "#/components/examples/NotFound"},
+                    "gone": {"$ref": "#/components/examples/Errors~1Gone"},
+                    "remote": {"$ref": "errors.yaml#/components/examples/NotFound"},
+                    "external": {"externalValue": "https://example.com/error.json"}
+                }}}}},
+                "examples": {
+                    "NotFound": {"summary": "Not found", "value": {"code": 404}},
+                    "Errors/Gone": {"value": {"code": 410}}
+                }
+            }
+        }))
+        .unwrap();
+
+        let examples: Value = serde_json::from_str(&extract_examples(&openapi)).unwrap();
+
+        assert_eq!(
+            examples,
+            json!({
+                "/pets": {"get": {
+                    "200": {"application/json": {"example": [{"name": "Rex"}]}},
+                    "default": {"application/json": {"examples": {
+                        "notFound": {"code": 404},
+                        "gone": {"code": 410}
+                    }}}
+                }}
+            })
+        );
+    }
+
+    #[test]
+    fn it_resolves_examples_from_the_components() {
+        let openapi = parser::parse_document::<OpenAPI>(
+            "tests/resources/example-references.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        let examples: Value = serde_json::from_str(&extract_examples(openapi.content())).unwrap();
+
+        assert_eq!(
+            examples,
+            json!({"/pets": {"get": {"200": {"application/json": {"examples": {
+                "pets": [{"$ref": "#/components/schemas/Pet"}]
+            }}}}}})
+        );
+    }
+
+    #[test]
+    fn it_inlines_external_values_of_examples() {
+        let openapi = parser::parse_document::<OpenAPI>(
+            "tests/resources/external-examples.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap()
+        .content()
+        .clone();
+        let openapi = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let inlined =
+            inline_external_values(&openapi, "tests/resources/external-examples.yaml", None)
+                .unwrap();
+        let examples: Value = serde_json::from_str(&extract_examples(&inlined)).unwrap();
+
+        assert_eq!(
+            examples,
+            json!({"/pets": {"get": {"200": {"application/json": {"examples": {
+                "pets": [{"name": "Rex"}, {"name": "Tom"}],
+                "notes": "Pets are listed in the order they were added\n"
+            }}}}}})
+        );
+        let pets = &inlined.components.unwrap().examples["Pets"];
+        assert!(pets.as_item().unwrap().external_value.is_none());
+    }
+}
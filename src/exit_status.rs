@@ -0,0 +1,112 @@
+use openapiv3_filter::{FilterError, ParseError};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::process::ExitCode;
+
+/// Exit statuses of the program
+///
+/// The numeric values are stable so that scripts can distinguish between the failure classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Document was filtered successfully
+    Success = 0,
+    /// Any failure not covered by the other statuses, e.g. invalid arguments or filter files
+    Error = 1,
+    /// Input document could not be parsed
+    ParseError = 2,
+    /// Input document could not be read
+    IoError = 3,
+    /// Filtering removed all the paths and `--fail-on-empty` was given
+    EmptyResult = 4,
+}
+
+impl ExitStatus {
+    /// Resolves the exit status matching the class of the error
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - A reference to the error that stopped the processing.
+    ///
+    /// # Returns
+    ///
+    /// * `ExitStatus` - The exit status for the error.
+    pub fn from_error(error: &(dyn Error + 'static)) -> Self {
+        let parse_error = error.downcast_ref::<ParseError>().or_else(|| {
+            match error.downcast_ref::<FilterError>() {
+                Some(FilterError::Parse(parse_error)) => Some(parse_error),
+                _ => None,
+            }
+        });
+        if let Some(parse_error) = parse_error {
+            return match parse_error {
+                ParseError::Io(_) => ExitStatus::IoError,
+                ParseError::UndefinedEnvVariable(_) => ExitStatus::Error,
+                _ => ExitStatus::ParseError,
+            };
+        }
+        if let Some(FilterError::DanglingRefs { .. }) = error.downcast_ref::<FilterError>() {
+            return ExitStatus::ParseError;
+        }
+        if error.is::<EmptyResultError>() {
+            ExitStatus::EmptyResult
+        } else if error.is::<io::Error>() {
+            ExitStatus::IoError
+        } else if error.is::<serde_json::Error>() || error.is::<serde_yaml::Error>() {
+            ExitStatus::ParseError
+        } else {
+            ExitStatus::Error
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// Error returned when filtering removed all the paths and `--fail-on-empty` was given
+#[derive(Debug)]
+pub struct EmptyResultError;
+
+impl fmt::Display for EmptyResultError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Filtering removed all the paths from the document")
+    }
+}
+
+impl Error for EmptyResultError {}
+
+/// How many mismatching examples are listed in the error message
+const LISTED_MISMATCHES: usize = 5;
+
+/// Error returned when `--check-examples` with `--strict` finds examples that do not conform to their schemas
+#[derive(Debug)]
+pub struct InvalidExamplesError {
+    /// Pairs of the location of the example and the reason of the mismatch
+    pub mismatches: Vec<(String, String)>,
+}
+
+impl fmt::Display for InvalidExamplesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Filtered document has {} example(s) that do not match their schema:",
+            self.mismatches.len()
+        )?;
+        for (location, reason) in self.mismatches.iter().take(LISTED_MISMATCHES) {
+            write!(f, "\n  {}: {}", location, reason)?;
+        }
+        if self.mismatches.len() > LISTED_MISMATCHES {
+            write!(
+                f,
+                "\n  and {} more",
+                self.mismatches.len() - LISTED_MISMATCHES
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InvalidExamplesError {}
@@ -0,0 +1,335 @@
+use crate::filter::content::reference_collector::{escape_segment, pointer_segments};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Prefix of the references to the component schemas
+pub(crate) const SCHEMA_REFERENCE_PREFIX: &str = "#/components/schemas/";
+
+/// Prefixes the names of the component schemas and rewrites the references to them throughout the document.
+///
+/// References are rewritten in `$ref` values, including references to locations inside a schema, and in the values of
+/// discriminator mappings, which may also be plain schema names. Other component categories are not renamed.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the JSON value of the whole document.
+/// * `prefix` - The prefix added to the schema names.
+pub fn prefix_schema_names(document: &mut Value, prefix: &str) {
+    let Some(Value::Object(schemas)) = document.pointer_mut("/components/schemas") else {
+        return;
+    };
+    let renamed_schemas: HashMap<String, String> = schemas
+        .keys()
+        .map(|name| (name.clone(), format!("{}{}", prefix, name)))
+        .collect();
+    *schemas = std::mem::take(schemas)
+        .into_iter()
+        .map(|(name, schema)| (renamed_schemas[&name].clone(), schema))
+        .collect::<Map<String, Value>>();
+    // Names are escaped in the references like any other JSON pointer segment
+    let renamed_segments: HashMap<String, String> = renamed_schemas
+        .iter()
+        .map(|(name, new_name)| (escape_segment(name), escape_segment(new_name)))
+        .collect();
+    rewrite_references(document, &renamed_schemas, &renamed_segments);
+}
+
+/// Replaces the component schemas that are structurally identical to an earlier schema with references to that schema.
+///
+/// Schemas are compared without their `description` keywords, at any depth, so the first of the identical schemas in
+/// document order is kept under its name and the others are removed. References pointing to the removed schemas are
+/// rewritten to point at the kept one. Schemas named in a discriminator mapping and schemas extending another component
+/// schema with `allOf` are never replaced, since their names tell the polymorphic types apart even when their
+/// structures are identical.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the JSON value of the whole document.
+///
+/// # Returns
+///
+/// * `Vec<(String, String)>` - Pairs of the removed schema name and the name of the schema it was replaced by.
+pub fn dedupe_schemas(document: &mut Value) -> Vec<(String, String)> {
+    let polymorphic_schemas = collect_polymorphic_schemas(document);
+    let Some(Value::Object(schemas)) = document.pointer_mut("/components/schemas") else {
+        return Vec::new();
+    };
+    let mut canonical_schemas: Vec<(String, Value)> = Vec::new();
+    let mut duplicates: Vec<(String, String)> = Vec::new();
+    for (name, schema) in schemas
+        .iter()
+        .filter(|(name, _)| !polymorphic_schemas.contains(name.as_str()))
+    {
+        let structure = without_descriptions(schema);
+        match canonical_schemas
+            .iter()
+            .find(|(_, canonical)| *canonical == structure)
+        {
+            Some((canonical_name, _)) => duplicates.push((name.clone(), canonical_name.clone())),
+            None => canonical_schemas.push((name.clone(), structure)),
+        }
+    }
+    if duplicates.is_empty() {
+        return duplicates;
+    }
+    for (name, _) in &duplicates {
+        schemas.shift_remove(name);
+    }
+    let renamed_schemas: HashMap<String, String> = duplicates.iter().cloned().collect();
+    let renamed_segments: HashMap<String, String> = renamed_schemas
+        .iter()
+        .map(|(name, new_name)| (escape_segment(name), escape_segment(new_name)))
+        .collect();
+    rewrite_references(document, &renamed_schemas, &renamed_segments);
+    duplicates
+}
+
+/// Collects the names of the component schemas that are discriminator mapping targets or extend another component
+/// schema with `allOf`
+fn collect_polymorphic_schemas(document: &Value) -> HashSet<String> {
+    let mut names = HashSet::new();
+    collect_mapping_targets(document, &mut names);
+    for (name, schema) in document
+        .pointer("/components/schemas")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let extends_schema = schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|member| member.get("$ref").and_then(Value::as_str))
+            .any(|reference| reference.starts_with(SCHEMA_REFERENCE_PREFIX));
+        if extends_schema {
+            names.insert(name.clone());
+        }
+    }
+    names
+}
+
+/// Collects the schema names the discriminator mappings under the value point to, either by name or by reference
+fn collect_mapping_targets(value: &Value, names: &mut HashSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for target in map
+                .get("discriminator")
+                .and_then(|discriminator| discriminator.get("mapping"))
+                .and_then(Value::as_object)
+                .into_iter()
+                .flat_map(Map::values)
+                .filter_map(Value::as_str)
+            {
+                match target.strip_prefix(SCHEMA_REFERENCE_PREFIX) {
+                    Some(pointer) => names.extend(pointer_segments(pointer).into_iter().next()),
+                    None => {
+                        names.insert(target.to_string());
+                    }
+                }
+            }
+            map.values()
+                .for_each(|value| collect_mapping_targets(value, names));
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_mapping_targets(item, names)),
+        _ => {}
+    }
+}
+
+/// Returns a copy of the schema without the `description` keywords, which do not change what the schema accepts
+fn without_descriptions(schema: &Value) -> Value {
+    match schema {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(key, value)| !(*key == "description" && value.is_string()))
+                .map(|(key, value)| (key.clone(), without_descriptions(value)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(without_descriptions).collect()),
+        value => value.clone(),
+    }
+}
+
+fn rewrite_references(
+    value: &mut Value,
+    renamed_schemas: &HashMap<String, String>,
+    renamed_segments: &HashMap<String, String>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::String(reference)) = map.get_mut("$ref") {
+                rewrite_reference(reference, renamed_segments);
+            }
+            if let Some(Value::Object(mapping)) = map
+                .get_mut("discriminator")
+                .and_then(|discriminator| discriminator.get_mut("mapping"))
+            {
+                for target in mapping.values_mut() {
+                    match target {
+                        Value::String(name) if renamed_schemas.contains_key(name.as_str()) => {
+                            *name = renamed_schemas[name.as_str()].clone();
+                        }
+                        Value::String(reference) => {
+                            rewrite_reference(reference, renamed_segments);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            map.values_mut()
+                .for_each(|value| rewrite_references(value, renamed_schemas, renamed_segments));
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| rewrite_references(item, renamed_schemas, renamed_segments)),
+        _ => {}
+    }
+}
+
+/// Rewrites a local reference to a renamed schema, keeping the rest of the pointer after the schema name
+fn rewrite_reference(reference: &mut String, renamed_segments: &HashMap<String, String>) {
+    let Some(pointer) = reference.strip_prefix(SCHEMA_REFERENCE_PREFIX) else {
+        return;
+    };
+    let (name, rest) = pointer
+        .split_once('/')
+        .map_or((pointer, None), |(name, rest)| (name, Some(rest)));
+    if let Some(new_name) = renamed_segments.get(name) {
+        *reference = match rest {
+            Some(rest) => format!("{}{}/{}", SCHEMA_REFERENCE_PREFIX, new_name, rest),
+            None => format!("{}{}", SCHEMA_REFERENCE_PREFIX, new_name),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_prefixes_schema_names_and_their_references() {
+        let mut document = json!({
+            "paths": {"/pets": {"get": {"responses": {"200": {
+                "description": "Pets",
+                "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}
+            }}}}},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "oneOf": [{"$ref": "#/components/schemas/Dog"}],
+                        "discriminator": {"propertyName": "kind", "mapping": {"dog": "Dog", "cat": "#/components/schemas/Pet"}}
+                    },
+                    "Dog": {"properties": {"id": {"$ref": "#/components/schemas/Pet/properties/id"}}}
+                },
+                "responses": {"Error": {"description": "Error"}},
+                "x-refs": {"$ref": "#/components/responses/Error"}
+            }
+        });
+
+        prefix_schema_names(&mut document, "Petstore");
+
+        assert_eq!(
+            document,
+            json!({
+                "paths": {"/pets": {"get": {"responses": {"200": {
+                    "description": "Pets",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/PetstorePet"}}}
+                }}}}},
+                "components": {
+                    "schemas": {
+                        "PetstorePet": {
+                            "oneOf": [{"$ref": "#/components/schemas/PetstoreDog"}],
+                            "discriminator": {"propertyName": "kind", "mapping": {"dog": "PetstoreDog", "cat": "#/components/schemas/PetstorePet"}}
+                        },
+                        "PetstoreDog": {"properties": {"id": {"$ref": "#/components/schemas/PetstorePet/properties/id"}}}
+                    },
+                    "responses": {"Error": {"description": "Error"}},
+                    "x-refs": {"$ref": "#/components/responses/Error"}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_replaces_identical_schemas_with_the_first_one() {
+        let mut document = json!({
+            "paths": {"/pets": {"get": {"responses": {"200": {
+                "description": "Pets",
+                "content": {"application/json": {"schema": {"$ref": "#/components/schemas/PetList"}}}
+            }}}}},
+            "components": {
+                "schemas": {
+                    "Pet": {"description": "A pet", "properties": {"name": {"type": "string"}}},
+                    "Animal": {"properties": {"name": {"type": "string", "description": "Name"}}},
+                    "Owner": {"properties": {"name": {"type": "integer"}}},
+                    "PetList": {"type": "array", "items": {"$ref": "#/components/schemas/Animal"}},
+                    "Pets": {"oneOf": [{"$ref": "#/components/schemas/Animal"}]}
+                }
+            }
+        });
+
+        let duplicates = dedupe_schemas(&mut document);
+
+        assert_eq!(
+            duplicates,
+            vec![(String::from("Animal"), String::from("Pet"))]
+        );
+        assert_eq!(
+            document,
+            json!({
+                "paths": {"/pets": {"get": {"responses": {"200": {
+                    "description": "Pets",
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/PetList"}}}
+                }}}}},
+                "components": {
+                    "schemas": {
+                        "Pet": {"description": "A pet", "properties": {"name": {"type": "string"}}},
+                        "Owner": {"properties": {"name": {"type": "integer"}}},
+                        "PetList": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}},
+                        "Pets": {"oneOf": [{"$ref": "#/components/schemas/Pet"}]}
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_keeps_identical_schemas_of_polymorphic_types() {
+        let mut document = json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "properties": {"kind": {"type": "string"}},
+                        "discriminator": {"propertyName": "kind", "mapping": {"dog": "Dog", "cat": "#/components/schemas/Cat"}}
+                    },
+                    "Dog": {"allOf": [{"$ref": "#/components/schemas/Pet"}]},
+                    "Cat": {"allOf": [{"$ref": "#/components/schemas/Pet"}]},
+                    "Hamster": {"allOf": [{"$ref": "#/components/schemas/Pet"}]},
+                    "Rodent": {"allOf": [{"$ref": "#/components/schemas/Pet"}]},
+                    "Kind": {"type": "string"},
+                    "Name": {"type": "string"}
+                }
+            }
+        });
+
+        let duplicates = dedupe_schemas(&mut document);
+
+        assert_eq!(
+            duplicates,
+            vec![(String::from("Name"), String::from("Kind"))]
+        );
+        let names: Vec<&String> = document["components"]["schemas"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Pet", "Dog", "Cat", "Hamster", "Rodent", "Kind"]
+        );
+    }
+}
@@ -0,0 +1,696 @@
+use crate::filter::content::reference_collector::{DEFAULT_MAX_RECURSION_DEPTH, escape_segment};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// Keywords of a schema whose values are subschemas
+const SUBSCHEMA_KEYWORDS: [&str; 3] = ["items", "additionalProperties", "not"];
+
+/// Keywords of a schema whose values are lists of subschemas
+const SUBSCHEMA_LIST_KEYWORDS: [&str; 3] = ["allOf", "anyOf", "oneOf"];
+
+/// Direction an example is sent in, which decides whether required `readOnly` and `writeOnly` properties may be missing
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Direction {
+    /// Request bodies and parameters, which leave out `readOnly` properties
+    Request,
+    /// Responses and their headers, which leave out `writeOnly` properties
+    Response,
+    /// Schema examples, which may leave out either
+    Any,
+}
+
+/// Checks that the examples of the document conform to their schemas.
+///
+/// The `example` and `examples` of media types, parameters and headers are checked against their `schema`, resolving
+/// references to `#/components/examples`, and the `example` of every schema, including nested property schemas, is
+/// checked against the schema itself. Only the validation keywords of the OpenAPI schema object are supported: `type`,
+/// `nullable`, `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`, `uniqueItems`, the length,
+/// size and range limits, `multipleOf`, `pattern`, `allOf`, `anyOf`, `oneOf` and `not`. Formats are not checked, and a
+/// `pattern` that is not a valid regular expression is reported as a mismatch. Required `readOnly` properties may be
+/// missing from request examples and required `writeOnly` properties from response examples, while schema examples
+/// may leave out either.
+///
+/// # Arguments
+///
+/// * `document` - A reference to the JSON value of the whole document.
+///
+/// # Returns
+///
+/// * `Vec<(String, String)>` - Pairs of the location of the mismatching example as a `#/...` pointer and the reason, in document order.
+pub fn check_examples(document: &Value) -> Vec<(String, String)> {
+    let mut mismatches = Vec::new();
+    check_value(document, document, "#", Direction::Any, &mut mismatches);
+    mismatches
+}
+
+/// Checks the examples of the media types, parameters and headers under the value and the schemas they contain
+fn check_value(
+    document: &Value,
+    value: &Value,
+    pointer: &str,
+    direction: Direction,
+    mismatches: &mut Vec<(String, String)>,
+) {
+    match value {
+        Value::Object(map) => {
+            if let Some(schema) = map.get("schema").filter(|schema| schema.is_object()) {
+                check_media_type_examples(document, map, schema, pointer, direction, mismatches);
+            }
+            for (key, value) in map {
+                let pointer = format!("{}/{}", pointer, escape_segment(key));
+                let direction = match key.as_str() {
+                    "requestBody" | "requestBodies" | "parameters" => Direction::Request,
+                    "responses" => Direction::Response,
+                    // Headers belong to the request only inside the encoding of a request body
+                    "headers" if direction == Direction::Any => Direction::Response,
+                    _ => direction,
+                };
+                match key.as_str() {
+                    "example" | "examples" => {}
+                    "schema" => check_schema(document, value, &pointer, mismatches),
+                    "schemas" if pointer == "#/components/schemas" => {
+                        for (name, schema) in value.as_object().into_iter().flatten() {
+                            let pointer = format!("{}/{}", pointer, escape_segment(name));
+                            check_schema(document, schema, &pointer, mismatches);
+                        }
+                    }
+                    _ => check_value(document, value, &pointer, direction, mismatches),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                check_value(
+                    document,
+                    item,
+                    &format!("{}/{}", pointer, index),
+                    direction,
+                    mismatches,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks the `example` and the `examples` of a media type, a parameter or a header against its schema
+fn check_media_type_examples(
+    document: &Value,
+    media_type: &Map<String, Value>,
+    schema: &Value,
+    pointer: &str,
+    direction: Direction,
+    mismatches: &mut Vec<(String, String)>,
+) {
+    let mut examples: Vec<(String, &Value)> = Vec::new();
+    if let Some(example) = media_type.get("example") {
+        examples.push((format!("{}/example", pointer), example));
+    }
+    for (name, example) in media_type
+        .get("examples")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let example = resolve(document, example, 0);
+        if let Some(value) = example.and_then(|example| example.get("value")) {
+            examples.push((
+                format!("{}/examples/{}", pointer, escape_segment(name)),
+                value,
+            ));
+        }
+    }
+    for (location, example) in examples {
+        if let Err(reason) = validate(document, example, schema, "$", direction, 0) {
+            mismatches.push((location, reason));
+        }
+    }
+}
+
+/// Checks the `example` of a schema against the schema and continues to its subschemas
+fn check_schema(
+    document: &Value,
+    schema: &Value,
+    pointer: &str,
+    mismatches: &mut Vec<(String, String)>,
+) {
+    let Value::Object(map) = schema else {
+        return;
+    };
+    if map.contains_key("$ref") {
+        // Referenced schemas are checked at their definition
+        return;
+    }
+    let mut examples: Vec<(String, &Value)> = Vec::new();
+    if let Some(example) = map.get("example") {
+        examples.push((format!("{}/example", pointer), example));
+    }
+    // OpenAPI 3.1 schemas list their examples in an array
+    if let Some(Value::Array(items)) = map.get("examples") {
+        examples.extend(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| (format!("{}/examples/{}", pointer, index), item)),
+        );
+    }
+    for (location, example) in examples {
+        if let Err(reason) = validate(document, example, schema, "$", Direction::Any, 0) {
+            mismatches.push((location, reason));
+        }
+    }
+    for (name, property) in map
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let pointer = format!("{}/properties/{}", pointer, escape_segment(name));
+        check_schema(document, property, &pointer, mismatches);
+    }
+    for keyword in SUBSCHEMA_KEYWORDS {
+        if let Some(subschema) = map.get(keyword) {
+            check_schema(
+                document,
+                subschema,
+                &format!("{}/{}", pointer, keyword),
+                mismatches,
+            );
+        }
+    }
+    for keyword in SUBSCHEMA_LIST_KEYWORDS {
+        for (index, subschema) in map
+            .get(keyword)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let pointer = format!("{}/{}/{}", pointer, keyword, index);
+            check_schema(document, subschema, &pointer, mismatches);
+        }
+    }
+}
+
+/// Validates a value against a schema, returning the reason of the first mismatch
+///
+/// # Arguments
+///
+/// * `document` - The whole document, used to resolve references
+/// * `value` - The validated value
+/// * `schema` - The schema the value should conform to
+/// * `path` - Location of the value inside the example, e.g. `$.tags[0]`
+/// * `direction` - Whether the example is sent in a request or a response
+/// * `depth` - How many references and subschemas have been followed, which stops recursive schemas
+fn validate(
+    document: &Value,
+    value: &Value,
+    schema: &Value,
+    path: &str,
+    direction: Direction,
+    depth: usize,
+) -> Result<(), String> {
+    if depth > DEFAULT_MAX_RECURSION_DEPTH {
+        return Ok(());
+    }
+    let Some(schema) = resolve(document, schema, depth) else {
+        return Ok(());
+    };
+    let Value::Object(schema) = schema else {
+        return match schema {
+            Value::Bool(false) => Err(format!("{} is not allowed", path)),
+            _ => Ok(()),
+        };
+    };
+    if value.is_null() && schema.get("nullable") == Some(&Value::Bool(true)) {
+        return Ok(());
+    }
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(expected) => vec![expected.as_str()],
+            Value::Array(expected) => expected.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|expected| has_type(value, expected)) {
+            return Err(format!("{} is not of type {}", path, types.join(" or ")));
+        }
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum")
+        && !allowed.contains(value)
+    {
+        return Err(format!("{} is not one of the enum values", path));
+    }
+    if let Some(constant) = schema.get("const")
+        && constant != value
+    {
+        return Err(format!("{} is not the constant value", path));
+    }
+    match value {
+        Value::Object(object) => validate_object(document, object, schema, path, direction, depth)?,
+        Value::Array(items) => validate_array(document, items, schema, path, direction, depth)?,
+        Value::String(string) => validate_string(string, schema, path)?,
+        Value::Number(number) => {
+            validate_number(number.as_f64().unwrap_or_default(), schema, path)?
+        }
+        _ => {}
+    }
+    for subschema in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        validate(document, value, subschema, path, direction, depth + 1)?;
+    }
+    if let Some(Value::Array(subschemas)) = schema.get("anyOf")
+        && !subschemas.iter().any(|subschema| {
+            validate(document, value, subschema, path, direction, depth + 1).is_ok()
+        })
+    {
+        return Err(format!("{} does not match any of the anyOf schemas", path));
+    }
+    if let Some(Value::Array(subschemas)) = schema.get("oneOf") {
+        let matching = subschemas
+            .iter()
+            .filter(|subschema| {
+                validate(document, value, subschema, path, direction, depth + 1).is_ok()
+            })
+            .count();
+        if matching != 1 {
+            return Err(format!(
+                "{} matches {} of the oneOf schemas instead of exactly one",
+                path, matching
+            ));
+        }
+    }
+    if let Some(subschema) = schema.get("not")
+        && validate(document, value, subschema, path, direction, depth + 1).is_ok()
+    {
+        return Err(format!("{} matches the schema it must not match", path));
+    }
+    Ok(())
+}
+
+fn validate_object(
+    document: &Value,
+    object: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    path: &str,
+    direction: Direction,
+    depth: usize,
+) -> Result<(), String> {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for required in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        if let Some(required) = required.as_str()
+            && !object.contains_key(required)
+            && !may_be_missing(document, properties, required, direction, depth)
+        {
+            return Err(format!(
+                "{} is missing the required property '{}'",
+                path, required
+            ));
+        }
+    }
+    for (name, property) in object {
+        let property_path = format!("{}.{}", path, name);
+        match properties.and_then(|properties| properties.get(name)) {
+            Some(property_schema) => validate(
+                document,
+                property,
+                property_schema,
+                &property_path,
+                direction,
+                depth + 1,
+            )?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    return Err(format!("{} is not an allowed property", property_path));
+                }
+                Some(additional @ Value::Object(_)) => validate(
+                    document,
+                    property,
+                    additional,
+                    &property_path,
+                    direction,
+                    depth + 1,
+                )?,
+                _ => {}
+            },
+        }
+    }
+    check_size(
+        object.len(),
+        schema,
+        "minProperties",
+        "maxProperties",
+        "properties",
+        path,
+    )
+}
+
+fn validate_array(
+    document: &Value,
+    items: &[Value],
+    schema: &Map<String, Value>,
+    path: &str,
+    direction: Direction,
+    depth: usize,
+) -> Result<(), String> {
+    if let Some(item_schema) = schema.get("items") {
+        for (index, item) in items.iter().enumerate() {
+            validate(
+                document,
+                item,
+                item_schema,
+                &format!("{}[{}]", path, index),
+                direction,
+                depth + 1,
+            )?;
+        }
+    }
+    if schema.get("uniqueItems") == Some(&Value::Bool(true))
+        && let Some(index) = (1..items.len()).find(|&index| items[..index].contains(&items[index]))
+    {
+        return Err(format!("{}[{}] is a duplicate item", path, index));
+    }
+    check_size(items.len(), schema, "minItems", "maxItems", "items", path)
+}
+
+fn validate_string(string: &str, schema: &Map<String, Value>, path: &str) -> Result<(), String> {
+    check_size(
+        string.chars().count(),
+        schema,
+        "minLength",
+        "maxLength",
+        "characters",
+        path,
+    )?;
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        let regex = Regex::new(pattern).map_err(|_| {
+            format!(
+                "{} cannot be checked against the invalid pattern '{}'",
+                path, pattern
+            )
+        })?;
+        if !regex.is_match(string) {
+            return Err(format!("{} does not match the pattern '{}'", path, pattern));
+        }
+    }
+    Ok(())
+}
+
+fn validate_number(number: f64, schema: &Map<String, Value>, path: &str) -> Result<(), String> {
+    let limit = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    // OpenAPI 3.0 marks the limits exclusive with booleans, 3.1 gives the exclusive limits as numbers
+    let is_exclusive = |keyword: &str| schema.get(keyword) == Some(&Value::Bool(true));
+    if let Some(minimum) = limit("minimum")
+        && (number < minimum || (number == minimum && is_exclusive("exclusiveMinimum")))
+    {
+        return Err(format!("{} is less than the minimum {}", path, minimum));
+    }
+    if let Some(maximum) = limit("maximum")
+        && (number > maximum || (number == maximum && is_exclusive("exclusiveMaximum")))
+    {
+        return Err(format!("{} is greater than the maximum {}", path, maximum));
+    }
+    if let Some(minimum) = limit("exclusiveMinimum")
+        && number <= minimum
+    {
+        return Err(format!("{} is not greater than {}", path, minimum));
+    }
+    if let Some(maximum) = limit("exclusiveMaximum")
+        && number >= maximum
+    {
+        return Err(format!("{} is not less than {}", path, maximum));
+    }
+    if let Some(divisor) = limit("multipleOf").filter(|divisor| *divisor > 0.0) {
+        let quotient = number / divisor;
+        // Tolerates the rounding of decimal divisors such as 0.01
+        if (quotient - quotient.round()).abs() > 1e-9 * quotient.abs().max(1.0) {
+            return Err(format!("{} is not a multiple of {}", path, divisor));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the number of properties, items or characters against the limits of the keywords
+fn check_size(
+    size: usize,
+    schema: &Map<String, Value>,
+    min_keyword: &str,
+    max_keyword: &str,
+    unit: &str,
+    path: &str,
+) -> Result<(), String> {
+    let limit = |keyword: &str| schema.get(keyword).and_then(Value::as_u64);
+    if let Some(minimum) = limit(min_keyword)
+        && (size as u64) < minimum
+    {
+        return Err(format!("{} has fewer than {} {}", path, minimum, unit));
+    }
+    if let Some(maximum) = limit(max_keyword)
+        && (size as u64) > maximum
+    {
+        return Err(format!("{} has more than {} {}", path, maximum, unit));
+    }
+    Ok(())
+}
+
+/// Returns true when a missing required property is left out because of the direction of the example, i.e. a `readOnly`
+/// property of a request or a `writeOnly` property of a response
+fn may_be_missing(
+    document: &Value,
+    properties: Option<&Map<String, Value>>,
+    name: &str,
+    direction: Direction,
+    depth: usize,
+) -> bool {
+    let Some(property) = properties
+        .and_then(|properties| properties.get(name))
+        .and_then(|property| resolve(document, property, depth))
+    else {
+        return false;
+    };
+    let is_set = |keyword: &str| property.get(keyword) == Some(&Value::Bool(true));
+    match direction {
+        Direction::Request => is_set("readOnly"),
+        Direction::Response => is_set("writeOnly"),
+        Direction::Any => is_set("readOnly") || is_set("writeOnly"),
+    }
+}
+
+/// Returns true when the value is of the JSON Schema type
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value
+            .as_f64()
+            .is_some_and(|number| value.is_i64() || value.is_u64() || number.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Follows local references until a value without `$ref`, or None when a reference does not resolve
+fn resolve<'a>(document: &'a Value, value: &'a Value, depth: usize) -> Option<&'a Value> {
+    let mut value = value;
+    for _ in depth..=DEFAULT_MAX_RECURSION_DEPTH {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(reference) => value = document.pointer(reference.strip_prefix('#')?)?,
+            None => return Some(value),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_reports_examples_not_matching_their_schemas() {
+        let document = json!({
+            "paths": {"/pets": {"get": {
+                "parameters": [{"name": "limit", "in": "query", "schema": {"type": "integer", "maximum": 100}, "example": 500}],
+                "responses": {"200": {"description": "Pets", "content": {"application/json": {
+                    "schema": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}},
+                    "examples": {
+                        "valid": {"$ref": "#/components/examples/Pets"},
+                        "invalid": {"value": [{"name": "Rex", "status": "lost"}]}
+                    }
+                }}}}
+            }}},
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": {"type": "string", "example": 7},
+                            "status": {"type": "string", "enum": ["available", "sold"]},
+                            "tag": {"type": "string", "nullable": true}
+                        },
+                        "example": {"status": "sold"}
+                    }
+                },
+                "examples": {"Pets": {"value": [{"name": "Rex", "tag": null}]}}
+            }
+        });
+
+        assert_eq!(
+            check_examples(&document),
+            vec![
+                (
+                    String::from("#/paths/~1pets/get/parameters/0/example"),
+                    String::from("$ is greater than the maximum 100")
+                ),
+                (
+                    String::from(
+                        "#/paths/~1pets/get/responses/200/content/application~1json/examples/invalid"
+                    ),
+                    String::from("$[0].status is not one of the enum values")
+                ),
+                (
+                    String::from("#/components/schemas/Pet/example"),
+                    String::from("$ is missing the required property 'name'")
+                ),
+                (
+                    String::from("#/components/schemas/Pet/properties/name/example"),
+                    String::from("$ is not of type string")
+                )
+            ]
+        );
+    }
+
+    #[test]
+    fn it_validates_compositions() {
+        let schema = json!({
+            "oneOf": [
+                {"type": "object", "properties": {"bark": {"type": "boolean"}}, "required": ["bark"]},
+                {"type": "object", "properties": {"meow": {"type": "boolean"}}, "required": ["meow"]}
+            ],
+            "allOf": [{"type": "object", "additionalProperties": {"type": "boolean"}}]
+        });
+        let document = json!({});
+
+        assert_eq!(
+            validate(
+                &document,
+                &json!({"bark": true}),
+                &schema,
+                "$",
+                Direction::Any,
+                0
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            validate(
+                &document,
+                &json!({"bark": true, "meow": true}),
+                &schema,
+                "$",
+                Direction::Any,
+                0
+            ),
+            Err(String::from(
+                "$ matches 2 of the oneOf schemas instead of exactly one"
+            ))
+        );
+        assert_eq!(
+            validate(
+                &document,
+                &json!({"purr": true}),
+                &schema,
+                "$",
+                Direction::Any,
+                0
+            ),
+            Err(String::from(
+                "$ matches 0 of the oneOf schemas instead of exactly one"
+            ))
+        );
+        assert_eq!(
+            validate(
+                &document,
+                &json!({"bark": true, "purr": "yes"}),
+                &schema,
+                "$",
+                Direction::Any,
+                0
+            ),
+            Err(String::from("$.purr is not of type boolean"))
+        );
+    }
+
+    #[test]
+    fn it_leaves_out_read_only_properties_of_requests_and_write_only_properties_of_responses() {
+        let pet = json!({"$ref": "#/components/schemas/Pet"});
+        let document = json!({
+            "paths": {"/pets": {"post": {
+                "requestBody": {"content": {"application/json": {"schema": pet, "example": {"name": "Rex", "password": "secret"}}}},
+                "responses": {"201": {"description": "Created", "content": {"application/json": {
+                    "schema": pet,
+                    "examples": {
+                        "created": {"value": {"id": 1, "name": "Rex"}},
+                        "anonymous": {"value": {"name": "Rex"}}
+                    }
+                }}}}
+            }}},
+            "components": {"schemas": {"Pet": {
+                "type": "object",
+                "required": ["id", "name", "password"],
+                "properties": {
+                    "id": {"type": "integer", "readOnly": true},
+                    "name": {"type": "string"},
+                    "password": {"type": "string", "writeOnly": true}
+                },
+                "example": {"name": "Rex"}
+            }}}
+        });
+
+        assert_eq!(
+            check_examples(&document),
+            vec![(
+                String::from(
+                    "#/paths/~1pets/post/responses/201/content/application~1json/examples/anonymous"
+                ),
+                String::from("$ is missing the required property 'id'")
+            )]
+        );
+    }
+
+    #[test]
+    fn it_checks_multiples_unique_items_and_invalid_patterns() {
+        let document = json!({});
+        let check = |value: Value, schema: Value| {
+            validate(&document, &value, &schema, "$", Direction::Any, 0)
+        };
+
+        assert_eq!(check(json!(0.3), json!({"multipleOf": 0.1})), Ok(()));
+        assert_eq!(
+            check(json!(7), json!({"multipleOf": 5})),
+            Err(String::from("$ is not a multiple of 5"))
+        );
+        assert_eq!(
+            check(json!([1, 2, 1]), json!({"uniqueItems": true})),
+            Err(String::from("$[2] is a duplicate item"))
+        );
+        assert_eq!(
+            check(json!("rex"), json!({"pattern": "[a-z"})),
+            Err(String::from(
+                "$ cannot be checked against the invalid pattern '[a-z'"
+            ))
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Keys whose values are literal example or default data, which is not part of the document structure
+const LITERAL_KEYS: [&str; 4] = ["example", "default", "enum", "value"];
+
+/// Renames the extension keys of the document.
+///
+/// Keys are renamed in every object of the document, e.g. in the root, operations and schemas, keeping their position.
+/// Names of schema properties and literal example, default and enum values are left as they are.
+/// Each key is renamed once, so renames are not applied to the result of another rename.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the JSON value of the whole document.
+/// * `renames` - Pairs of the current and the new extension key.
+pub fn rename_extensions(document: &mut Value, renames: &[(String, String)]) {
+    let renames: HashMap<&str, &str> = renames
+        .iter()
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .collect();
+    rename_keys(document, &renames);
+}
+
+fn rename_keys(value: &mut Value, renames: &HashMap<&str, &str>) {
+    match value {
+        Value::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    match key.as_str() {
+                        "properties" => {
+                            if let Value::Object(properties) = &mut value {
+                                properties
+                                    .values_mut()
+                                    .for_each(|property| rename_keys(property, renames));
+                            }
+                        }
+                        key if LITERAL_KEYS.contains(&key) => {}
+                        _ => rename_keys(&mut value, renames),
+                    }
+                    let key = renames
+                        .get(key.as_str())
+                        .map_or(key, |renamed| renamed.to_string());
+                    (key, value)
+                })
+                .collect::<Map<String, Value>>();
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| rename_keys(item, renames)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_renames_extension_keys_outside_property_names_and_literals() {
+        let mut document = json!({
+            "x-enum": "root",
+            "paths": {"/pets": {"get": {"x-enum": ["a"], "x-other": true, "responses": {}}}},
+            "components": {"schemas": {"Kind": {
+                "type": "object",
+                "x-enum": {"values": [{"x-enum": "nested"}]},
+                "properties": {"x-enum": {"type": "string", "x-enum": "property"}},
+                "example": {"x-enum": "literal"}
+            }}}
+        });
+
+        rename_extensions(
+            &mut document,
+            &[
+                (String::from("x-enum"), String::from("x-ms-enum")),
+                (String::from("x-ms-enum"), String::from("x-unused")),
+            ],
+        );
+
+        assert_eq!(
+            document,
+            json!({
+                "x-ms-enum": "root",
+                "paths": {"/pets": {"get": {"x-ms-enum": ["a"], "x-other": true, "responses": {}}}},
+                "components": {"schemas": {"Kind": {
+                    "type": "object",
+                    "x-ms-enum": {"values": [{"x-ms-enum": "nested"}]},
+                    "properties": {"x-enum": {"type": "string", "x-ms-enum": "property"}},
+                    "example": {"x-enum": "literal"}
+                }}}
+            })
+        );
+    }
+}
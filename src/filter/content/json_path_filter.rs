@@ -2,22 +2,20 @@ use serde_json::{Map, Value};
 
 /// Finds paths in a JSON value that match the provided paths.
 ///
-/// This function takes a JSON value and a slice of path strings as input.
+/// This function takes a JSON value and a slice of paths as input.
 /// It filters the JSON value to include only the parts that match the provided paths.
+/// Paths are given as already split key segments, so keys containing separators such as dots (e.g. `com.example.Pet`) are matched as is.
 ///
 /// # Arguments
 ///
 /// * `value` - A reference to the JSON value to filter.
-/// * `paths` - A slice of string slices representing the paths to filter by. Each path is a dot-separated string.
+/// * `paths` - A slice of paths to filter by. Each path is a list of keys from the root of the value.
 ///
 /// # Returns
 ///
 /// * `Option<Value>` - An Option containing the filtered JSON value, or None if no paths match.
-pub fn filter_json(value: &Value, paths: &[&str]) -> Option<Value> {
-    // Convert paths into a Vec of Vec<&str> for efficient processing
-    let path_parts: Vec<Vec<&str>> = paths.iter().map(|path| path.split('.').collect()).collect();
-
-    filter_value(value, &path_parts, &[])
+pub fn filter_json(value: &Value, paths: &[Vec<&str>]) -> Option<Value> {
+    filter_value(value, paths, &[])
 }
 
 /// Recursively filters a JSON value based on the provided paths.
@@ -154,7 +152,7 @@ mod tests {
             "user.orders.item",
         ];
 
-        let filtered = filter_json(&json, &paths).unwrap();
+        let filtered = filter_json(&json, &segments(&paths)).unwrap();
 
         // Expected filtered structure
         let expected = json!({
@@ -179,6 +177,27 @@ mod tests {
         assert_eq!(filtered, expected);
     }
 
+    #[test]
+    fn it_filters_keys_containing_dots() {
+        let json = json!({
+            "schemas": {
+                "com.example.Pet": {"type": "object"},
+                "com.example.Order": {"type": "object"}
+            }
+        });
+
+        let paths = vec![vec!["schemas", "com.example.Pet"]];
+        let filtered = filter_json(&json, &paths).unwrap();
+
+        let expected = json!({
+            "schemas": {
+                "com.example.Pet": {"type": "object"}
+            }
+        });
+
+        assert_eq!(filtered, expected);
+    }
+
     #[test]
     fn it_works_with_empty_paths() {
         let json = json!({
@@ -187,7 +206,7 @@ mod tests {
         });
 
         let paths: Vec<&str> = vec![];
-        let filtered = filter_json(&json, &paths);
+        let filtered = filter_json(&json, &segments(&paths));
         assert!(filtered.is_none());
     }
 
@@ -199,7 +218,7 @@ mod tests {
         });
 
         let paths = vec!["c.d", "e.f"];
-        let filtered = filter_json(&json, &paths);
+        let filtered = filter_json(&json, &segments(&paths));
         assert!(filtered.is_none());
     }
 
@@ -213,7 +232,7 @@ mod tests {
         });
 
         let paths = vec!["items.id", "items.name"];
-        let filtered = filter_json(&json, &paths).unwrap();
+        let filtered = filter_json(&json, &segments(&paths)).unwrap();
 
         let expected = json!({
             "items": [
@@ -239,7 +258,7 @@ mod tests {
         });
 
         let paths = vec!["level1.level2.level3.value"];
-        let filtered = filter_json(&json, &paths).unwrap();
+        let filtered = filter_json(&json, &segments(&paths)).unwrap();
 
         let expected = json!({
             "level1": {
@@ -269,7 +288,7 @@ mod tests {
 
         let paths = vec!["level1.level2.level3"];
 
-        let filtered = filter_json(&json, &paths).unwrap();
+        let filtered = filter_json(&json, &segments(&paths)).unwrap();
 
         let expected = json!({
             "level1": {
@@ -284,4 +303,8 @@ mod tests {
 
         assert_eq!(filtered, expected);
     }
+
+    fn segments<'a>(paths: &[&'a str]) -> Vec<Vec<&'a str>> {
+        paths.iter().map(|path| path.split('.').collect()).collect()
+    }
 }
@@ -0,0 +1,151 @@
+use crate::error::FilterError;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// Selects JSON values with JSONPath expressions.
+///
+/// A value is selected when any of the expressions yields a non-empty result for it.
+/// Expressions follow RFC 9535, except that dot notation also accepts member names starting with a digit,
+/// so that response codes can be written as `$.responses.200` instead of `$.responses['200']`.
+#[derive(Debug, Clone)]
+pub struct JsonPathSelector {
+    paths: Vec<JsonPath>,
+}
+
+impl JsonPathSelector {
+    /// Creates a selector from JSONPath expressions.
+    ///
+    /// # Arguments
+    ///
+    /// * `expressions` - JSONPath expressions evaluated against the selected values.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<JsonPathSelector, FilterError>` - The selector, or an error naming the first invalid expression.
+    pub fn new(expressions: &[String]) -> Result<Self, FilterError> {
+        let paths = expressions
+            .iter()
+            .map(|expression| {
+                JsonPath::parse(&normalize_expression(expression)).map_err(|error| {
+                    FilterError::InvalidPattern {
+                        kind: "JSONPath expression",
+                        pattern: expression.clone(),
+                        reason: error.to_string(),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(JsonPathSelector { paths })
+    }
+
+    /// Returns true when any of the expressions yields a non-empty result for the value
+    pub fn matches(&self, value: &Value) -> bool {
+        self.paths.iter().any(|path| !path.query(value).is_empty())
+    }
+}
+
+/// Rewrites dot notation member names starting with a digit (e.g. `.200`) to bracket notation (`['200']`), also after
+/// the descendant segment `..`.
+///
+/// Only segments outside of brackets are rewritten, so numbers in filter expressions are left as they are.
+fn normalize_expression(expression: &str) -> String {
+    let mut normalized = String::with_capacity(expression.len());
+    let mut characters = expression.chars().peekable();
+    let mut bracket_depth = 0;
+    let mut quote: Option<char> = None;
+    while let Some(character) = characters.next() {
+        match (character, quote) {
+            (_, Some(open_quote)) => {
+                if character == open_quote {
+                    quote = None;
+                }
+                normalized.push(character);
+            }
+            ('\'' | '"', None) => {
+                quote = Some(character);
+                normalized.push(character);
+            }
+            ('[', None) => {
+                bracket_depth += 1;
+                normalized.push(character);
+            }
+            (']', None) => {
+                bracket_depth -= 1;
+                normalized.push(character);
+            }
+            ('.', None) if bracket_depth == 0 => {
+                // A descendant segment keeps both dots, e.g. `$..200` becomes `$..['200']`
+                let is_descendant = characters.next_if_eq(&'.').is_some();
+                if is_descendant {
+                    normalized.push('.');
+                }
+                if characters.peek().is_some_and(|next| next.is_ascii_digit()) {
+                    let mut member = String::new();
+                    while let Some(next) =
+                        characters.next_if(|next| next.is_ascii_alphanumeric() || *next == '_')
+                    {
+                        member.push(next);
+                    }
+                    if is_descendant {
+                        normalized.push('.');
+                    }
+                    normalized.push_str(&format!("['{}']", member));
+                } else {
+                    normalized.push('.');
+                }
+            }
+            _ => normalized.push(character),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_selects_values_with_numeric_member_names() {
+        let selector =
+            JsonPathSelector::new(&[String::from("$.responses.200.content['application/json']")])
+                .unwrap();
+
+        assert!(selector.matches(&json!({
+            "responses": {"200": {"content": {"application/json": {}}}}
+        })));
+        assert!(!selector.matches(&json!({
+            "responses": {"200": {"content": {"application/xml": {}}}}
+        })));
+    }
+
+    #[test]
+    fn it_leaves_filter_expressions_untouched() {
+        assert_eq!(
+            normalize_expression("$.responses.2XX[?@.x == 1.5]..schema"),
+            "$.responses['2XX'][?@.x == 1.5]..schema"
+        );
+    }
+
+    #[test]
+    fn it_selects_descendants_with_numeric_member_names() {
+        assert_eq!(
+            normalize_expression("$..200.description"),
+            "$..['200'].description"
+        );
+        let selector = JsonPathSelector::new(&[String::from("$..200")]).unwrap();
+
+        assert!(selector.matches(&json!({"get": {"responses": {"200": {}}}})));
+        assert!(!selector.matches(&json!({"get": {"responses": {"404": {}}}})));
+    }
+
+    #[test]
+    fn it_reports_invalid_expressions() {
+        let error = JsonPathSelector::new(&[String::from("$.[")]).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Invalid JSONPath expression '$.['")
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use crate::error::FilterError;
+use crate::filter::content::link_transformer;
+use crate::filter::content::operation_transformer;
+use indexmap::IndexMap;
+use openapiv3::{Components, OpenAPI, Operation, PathItem, ReferenceOr, SecurityRequirement};
+use std::collections::HashMap;
+
+/// Trims leading and trailing whitespace from the names filters are matched against.
+///
+/// Path keys, tag names of the root and the operations, security scheme names of the components and
+/// the scheme names used in security requirements are trimmed, so the output uses the trimmed names as well.
+/// When two keys are equal after trimming, the position of the first and the value of the last one are kept.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+pub fn normalize_keys(openapi: &mut OpenAPI) {
+    openapi.paths.paths = trim_map_keys(std::mem::take(&mut openapi.paths.paths));
+    openapi
+        .tags
+        .iter_mut()
+        .for_each(|tag| tag.name = tag.name.trim().to_string());
+    trim_security_requirements(&mut openapi.security);
+    if let Some(components) = openapi.components.as_mut() {
+        components.security_schemes =
+            trim_map_keys(std::mem::take(&mut components.security_schemes));
+    }
+    for path in openapi.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path) = path {
+            [
+                &mut path.get,
+                &mut path.put,
+                &mut path.post,
+                &mut path.delete,
+                &mut path.options,
+                &mut path.head,
+                &mut path.patch,
+                &mut path.trace,
+            ]
+            .into_iter()
+            .flatten()
+            .for_each(normalize_operation);
+        }
+    }
+}
+
+fn normalize_operation(operation: &mut Operation) {
+    operation
+        .tags
+        .iter_mut()
+        .for_each(|tag| *tag = tag.trim().to_string());
+    trim_security_requirements(&mut operation.security);
+}
+
+fn trim_security_requirements(security: &mut Option<Vec<SecurityRequirement>>) {
+    security
+        .iter_mut()
+        .flatten()
+        .for_each(|requirement| *requirement = trim_map_keys(std::mem::take(requirement)));
+}
+
+fn trim_map_keys<T>(map: IndexMap<String, T>) -> IndexMap<String, T> {
+    map.into_iter()
+        .map(|(key, value)| (key.trim().to_string(), value))
+        .collect()
+}
+
+/// Removes the trailing slashes of the path keys, e.g. `/pets/` becomes `/pets`. The root path `/` is kept as it is.
+///
+/// Path items whose keys are equal without the trailing slashes are merged at the position of the first one. Their
+/// operations are combined, and the summary and description of the first path item are kept when it has them.
+/// Path level parameters and servers apply to every operation of their path item, so when the merged path items declare
+/// different ones, each path item moves them into its own operations first. Links whose `operationRef` points to an
+/// operation of a renamed path are rewritten to the new path.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+///
+/// # Returns
+///
+/// * `Result<(), FilterError>` - An error when merged path items declare the same operation or either of them is a reference.
+pub fn strip_trailing_slashes(openapi: &mut OpenAPI) -> Result<(), FilterError> {
+    let mut paths: IndexMap<String, ReferenceOr<PathItem>> = IndexMap::new();
+    let mut renames: HashMap<String, String> = HashMap::new();
+    for (path, item) in std::mem::take(&mut openapi.paths.paths) {
+        let normalized = strip_trailing_slash(&path);
+        if normalized != path {
+            renames.insert(path.clone(), normalized.to_string());
+        }
+        match paths.get_mut(normalized) {
+            Some(existing) => merge_path_items(existing, item, openapi.components.as_ref())
+                .map_err(|reason| FilterError::PathCollision {
+                    path: path.clone(),
+                    normalized: normalized.to_string(),
+                    reason,
+                })?,
+            None => {
+                paths.insert(normalized.to_string(), item);
+            }
+        }
+    }
+    openapi.paths.paths = paths;
+    link_transformer::rename_operation_refs(openapi, &renames);
+    Ok(())
+}
+
+/// Returns the path without its trailing slashes, or `/` for the root path
+pub fn strip_trailing_slash(path: &str) -> &str {
+    match path.trim_end_matches('/') {
+        "" if !path.is_empty() => "/",
+        stripped => stripped,
+    }
+}
+
+/// Merges the operations and path level fields of the source path item into the target, see `strip_trailing_slashes`
+fn merge_path_items(
+    target: &mut ReferenceOr<PathItem>,
+    source: ReferenceOr<PathItem>,
+    components: Option<&Components>,
+) -> Result<(), String> {
+    let (ReferenceOr::Item(target), ReferenceOr::Item(mut source)) = (target, source) else {
+        return Err(String::from("path item references cannot be merged"));
+    };
+    if target.parameters != source.parameters || target.servers != source.servers {
+        operation_transformer::push_down_path_fields(target, components);
+        operation_transformer::push_down_path_fields(&mut source, components);
+    }
+    let PathItem {
+        summary,
+        description,
+        get,
+        put,
+        post,
+        delete,
+        options,
+        head,
+        patch,
+        trace,
+        extensions,
+        ..
+    } = source;
+    for (method, target_operation, source_operation) in [
+        ("get", &mut target.get, get),
+        ("put", &mut target.put, put),
+        ("post", &mut target.post, post),
+        ("delete", &mut target.delete, delete),
+        ("options", &mut target.options, options),
+        ("head", &mut target.head, head),
+        ("patch", &mut target.patch, patch),
+        ("trace", &mut target.trace, trace),
+    ] {
+        if let Some(operation) = source_operation {
+            if target_operation.is_some() {
+                return Err(format!("both declare a {} operation", method));
+            }
+            *target_operation = Some(operation);
+        }
+    }
+    target.summary = target.summary.take().or(summary);
+    target.description = target.description.take().or(description);
+    for (key, value) in extensions {
+        target.extensions.entry(key).or_insert(value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_trims_paths_tags_and_security_scheme_names() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Sloppy", "version": "1.0.0"},
+            "tags": [{"name": "users "}],
+            "security": [{" api_key": []}],
+            "paths": {"/users ": {"get": {
+                "tags": ["users "],
+                "security": [{"oauth ": ["read"]}],
+                "responses": {}
+            }}},
+            "components": {"securitySchemes": {
+                " api_key": {"type": "apiKey", "name": "key", "in": "header"},
+                "oauth ": {"type": "http", "scheme": "bearer"}
+            }}
+        }))
+        .unwrap();
+
+        normalize_keys(&mut openapi);
+
+        let document = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(document["tags"], json!([{"name": "users"}]));
+        assert_eq!(document["security"], json!([{"api_key": []}]));
+        assert_eq!(document["paths"]["/users"]["get"]["tags"], json!(["users"]));
+        assert_eq!(
+            document["paths"]["/users"]["get"]["security"],
+            json!([{"oauth": ["read"]}])
+        );
+        let scheme_names: Vec<&String> = openapi
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .keys()
+            .collect();
+        assert_eq!(scheme_names, vec!["api_key", "oauth"]);
+    }
+
+    #[test]
+    fn it_merges_paths_that_differ_by_a_trailing_slash() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Slashes", "version": "1.0.0"},
+            "paths": {
+                "/": {"get": {"responses": {}}},
+                "/pets": {"get": {"responses": {}}},
+                "/users/": {"get": {"responses": {}}},
+                "/pets/": {"summary": "Pets", "post": {"responses": {}}}
+            }
+        }))
+        .unwrap();
+
+        strip_trailing_slashes(&mut openapi).unwrap();
+
+        assert_eq!(
+            openapi.paths.paths.keys().collect::<Vec<_>>(),
+            vec!["/", "/pets", "/users"]
+        );
+        let pets = openapi.paths.paths["/pets"].as_item().unwrap();
+        assert!(pets.get.is_some() && pets.post.is_some());
+        assert_eq!(pets.summary.as_deref(), Some("Pets"));
+    }
+
+    #[test]
+    fn it_fails_to_merge_paths_declaring_the_same_operation() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Slashes", "version": "1.0.0"},
+            "paths": {
+                "/pets": {"get": {"responses": {}}},
+                "/pets/": {"get": {"responses": {}}}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            strip_trailing_slashes(&mut openapi)
+                .unwrap_err()
+                .to_string(),
+            "Path '/pets/' collides with '/pets' after removing the trailing slash: both declare a get operation"
+        );
+    }
+
+    #[test]
+    fn it_keeps_path_level_parameters_and_servers_on_their_own_operations_when_merging() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Slashes", "version": "1.0.0"},
+            "paths": {
+                "/pets": {
+                    "parameters": [{"name": "limit", "in": "query", "schema": {"type": "integer"}}],
+                    "get": {"responses": {}}
+                },
+                "/pets/": {
+                    "servers": [{"url": "https://write.example.com"}],
+                    "parameters": [
+                        {"name": "limit", "in": "query", "schema": {"type": "string"}},
+                        {"name": "X-Trace", "in": "header", "schema": {"type": "string"}}
+                    ],
+                    "post": {
+                        "parameters": [{"name": "X-Trace", "in": "header", "required": true, "schema": {"type": "string"}}],
+                        "responses": {}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        strip_trailing_slashes(&mut openapi).unwrap();
+
+        let document = serde_json::to_value(&openapi).unwrap();
+        let pets = &document["paths"]["/pets"];
+        assert!(pets.get("parameters").is_none() && pets.get("servers").is_none());
+        assert_eq!(
+            pets["get"]["parameters"],
+            json!([{"name": "limit", "in": "query", "style": "form", "schema": {"type": "integer"}}])
+        );
+        assert!(pets["get"].get("servers").is_none());
+        assert_eq!(
+            pets["post"]["parameters"],
+            json!([
+                {"name": "limit", "in": "query", "style": "form", "schema": {"type": "string"}},
+                {"name": "X-Trace", "in": "header", "required": true, "style": "simple", "schema": {"type": "string"}}
+            ])
+        );
+        assert_eq!(
+            pets["post"]["servers"],
+            json!([{"url": "https://write.example.com"}])
+        );
+    }
+
+    #[test]
+    fn it_rewrites_operation_refs_of_renamed_paths() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Slashes", "version": "1.0.0"},
+            "paths": {
+                "/pets/": {"post": {"responses": {"201": {
+                    "description": "Created",
+                    "links": {"Self": {"operationRef": "#/paths/~1pets~1/get"}}
+                }}}},
+                "/pets": {"get": {"responses": {}}}
+            },
+            "components": {"links": {
+                "AddPet": {"operationRef": "#/paths/~1pets~1/post"}
+            }}
+        }))
+        .unwrap();
+
+        strip_trailing_slashes(&mut openapi).unwrap();
+
+        let document = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(
+            document["paths"]["/pets"]["post"]["responses"]["201"]["links"]["Self"]["operationRef"],
+            json!("#/paths/~1pets/get")
+        );
+        assert_eq!(
+            document["components"]["links"]["AddPet"]["operationRef"],
+            json!("#/paths/~1pets/post")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::filter::content::openapi31_fields::resolve_path_item;
+use crate::filter::content::reference_collector::{escape_segment, pointer_segments};
+use indexmap::IndexMap;
+use openapiv3::{Link, LinkOperation, OpenAPI, ReferenceOr, Response};
+use std::collections::{HashMap, HashSet};
+
+/// Operations of a document that links can target
+#[derive(Debug, Default)]
+struct LinkTargets {
+    ///`operationId` values of the operations
+    operation_ids: HashSet<String>,
+    ///local `operationRef` pointers to the operations, e.g. `#/paths/~1pets~1{petId}/get`
+    operation_refs: HashSet<String>,
+}
+
+impl LinkTargets {
+    fn from_document(openapi: &OpenAPI) -> Self {
+        let mut targets = LinkTargets::default();
+        for (path_name, path_item) in openapi.paths.iter() {
+            let Some(path_item) = resolve_path_item(path_item, openapi.components.as_ref()) else {
+                continue;
+            };
+            let escaped_path = escape_segment(path_name);
+            for (method, operation) in path_item.iter() {
+                targets
+                    .operation_refs
+                    .insert(format!("#/paths/{}/{}", escaped_path, method));
+                targets
+                    .operation_ids
+                    .extend(operation.operation_id.iter().cloned());
+            }
+        }
+        targets
+    }
+
+    fn contains(&self, operation: &LinkOperation) -> bool {
+        match operation {
+            LinkOperation::OperationRef(reference) => self.operation_refs.contains(reference),
+            LinkOperation::OperationId(operation_id) => self.operation_ids.contains(operation_id),
+        }
+    }
+}
+
+/// Removes the links whose target operation was removed by filtering.
+///
+/// A link is removed when its `operationRef` or `operationId` targets an operation of the original document
+/// that is missing from the filtered document. Links to external documents or to operations that could not be resolved
+/// in the original document are kept as they are. References to removed reusable links are removed as well.
+///
+/// # Arguments
+///
+/// * `original` - A reference to the document before filtering.
+/// * `filtered` - A mutable reference to the filtered document.
+pub fn remove_dangling_links(original: &OpenAPI, filtered: &mut OpenAPI) {
+    let original_targets = LinkTargets::from_document(original);
+    let kept_targets = LinkTargets::from_document(filtered);
+    let is_dangling = |link: &Link| {
+        original_targets.contains(&link.operation) && !kept_targets.contains(&link.operation)
+    };
+
+    let mut kept_links: HashSet<String> = HashSet::new();
+    if let Some(components) = filtered.components.as_mut() {
+        components
+            .links
+            .retain(|_, link| link.as_item().is_none_or(|link| !is_dangling(link)));
+        kept_links.extend(components.links.keys().cloned());
+    }
+    let retain_links = |links: &mut IndexMap<String, ReferenceOr<Link>>| {
+        links.retain(|_, link| match link {
+            ReferenceOr::Item(link) => !is_dangling(link),
+            ReferenceOr::Reference { reference } => reference
+                .strip_prefix("#/components/links/")
+                .is_none_or(|name| kept_links.contains(name)),
+        })
+    };
+    let retain_response_links = |response: &mut ReferenceOr<Response>| {
+        if let ReferenceOr::Item(response) = response {
+            retain_links(&mut response.links);
+        }
+    };
+
+    if let Some(components) = filtered.components.as_mut() {
+        components
+            .responses
+            .values_mut()
+            .for_each(&retain_response_links);
+    }
+    for path_item in filtered.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            [
+                &mut path_item.get,
+                &mut path_item.put,
+                &mut path_item.post,
+                &mut path_item.delete,
+                &mut path_item.options,
+                &mut path_item.head,
+                &mut path_item.patch,
+                &mut path_item.trace,
+            ]
+            .into_iter()
+            .flatten()
+            .for_each(|operation| {
+                operation
+                    .responses
+                    .default
+                    .iter_mut()
+                    .chain(operation.responses.responses.values_mut())
+                    .for_each(&retain_response_links)
+            });
+        }
+    }
+}
+
+/// Rewrites the local `operationRef` pointers of the links to operations of renamed paths.
+///
+/// Links of the reusable links and responses in components and of the responses of the operations are rewritten,
+/// e.g. `#/paths/~1pets~1/get` becomes `#/paths/~1pets/get` when `/pets/` is renamed to `/pets`.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document with the renamed paths.
+/// * `renames` - The new path names by the old path names.
+pub fn rename_operation_refs(openapi: &mut OpenAPI, renames: &HashMap<String, String>) {
+    if renames.is_empty() {
+        return;
+    }
+    for links in links_mut(openapi) {
+        for link in links.values_mut() {
+            if let ReferenceOr::Item(Link {
+                operation: LinkOperation::OperationRef(reference),
+                ..
+            }) = link
+                && let Some(renamed) = renamed_operation_ref(reference, renames)
+            {
+                *reference = renamed;
+            }
+        }
+    }
+}
+
+/// Returns the `operationRef` pointer with its path renamed, or None when it does not point to a renamed path
+fn renamed_operation_ref(reference: &str, renames: &HashMap<String, String>) -> Option<String> {
+    let (escaped_path, operation) = reference.strip_prefix("#/paths/")?.split_once('/')?;
+    let renamed = renames.get(&pointer_segments(escaped_path).concat())?;
+    Some(format!("#/paths/{}/{}", escape_segment(renamed), operation))
+}
+
+/// Returns the link maps of the reusable links and responses in components and of the responses of the operations
+fn links_mut(openapi: &mut OpenAPI) -> Vec<&mut IndexMap<String, ReferenceOr<Link>>> {
+    let mut links = Vec::new();
+    if let Some(components) = openapi.components.as_mut() {
+        links.push(&mut components.links);
+        links.extend(components.responses.values_mut().filter_map(response_links));
+    }
+    for path_item in openapi.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path_item) = path_item {
+            for operation in [
+                &mut path_item.get,
+                &mut path_item.put,
+                &mut path_item.post,
+                &mut path_item.delete,
+                &mut path_item.options,
+                &mut path_item.head,
+                &mut path_item.patch,
+                &mut path_item.trace,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                links.extend(
+                    operation
+                        .responses
+                        .default
+                        .iter_mut()
+                        .chain(operation.responses.responses.values_mut())
+                        .filter_map(response_links),
+                );
+            }
+        }
+    }
+    links
+}
+
+/// Returns the links of the response, or None for a reference to a reusable response
+fn response_links(
+    response: &mut ReferenceOr<Response>,
+) -> Option<&mut IndexMap<String, ReferenceOr<Link>>> {
+    match response {
+        ReferenceOr::Item(response) => Some(&mut response.links),
+        ReferenceOr::Reference { .. } => None,
+    }
+}
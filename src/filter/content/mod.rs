@@ -1,3 +1,17 @@
+pub mod component_renamer;
+pub mod example_validator;
+pub mod extension_renamer;
 pub mod json_path_filter;
+pub mod jsonpath_selector;
+pub mod key_normalizer;
+pub mod link_transformer;
+pub mod name_matcher;
+pub mod openapi31_fields;
+pub mod operation_transformer;
 pub mod reference_collector;
 pub mod reference_processor;
+pub mod schema_inliner;
+pub mod schema_transformer;
+pub mod server_transformer;
+pub mod tag_group_resolver;
+pub mod yaml_comments;
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use wildmatch::WildMatch;
+
+/// Matches names against exact names and `*`/`?` wildcard patterns.
+///
+/// A name matches when it is equal to one of the exact names or matches any of the patterns,
+/// which allows combining exact and glob filters for the same dimension.
+#[derive(Debug, Clone, Default)]
+pub struct NameMatcher {
+    names: HashSet<String>,
+    patterns: Vec<WildMatch>,
+}
+
+impl NameMatcher {
+    /// Creates a matcher from optional exact names and optional wildcard patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `names` - An optional list of names that are matched exactly.
+    /// * `patterns` - An optional list of wildcard patterns.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<NameMatcher>` - The matcher, or None when neither names nor patterns are provided.
+    pub fn new(names: Option<Vec<String>>, patterns: Option<Vec<String>>) -> Option<Self> {
+        if names.is_none() && patterns.is_none() {
+            return None;
+        }
+        Some(NameMatcher {
+            names: names.into_iter().flatten().collect(),
+            patterns: patterns
+                .into_iter()
+                .flatten()
+                .map(|pattern| WildMatch::new(&pattern))
+                .collect(),
+        })
+    }
+
+    /// Returns true when the name is one of the exact names or matches one of the patterns
+    pub fn matches(&self, name: &str) -> bool {
+        self.names.contains(name) || self.patterns.iter().any(|pattern| pattern.matches(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_exact_names_and_patterns() {
+        let matcher = NameMatcher::new(
+            Some(vec![String::from("api_key")]),
+            Some(vec![String::from("oauth2_*")]),
+        )
+        .unwrap();
+
+        assert!(matcher.matches("api_key"));
+        assert!(matcher.matches("oauth2_v1"));
+        assert!(matcher.matches("oauth2_v2"));
+        assert!(!matcher.matches("api_key_v2"));
+        assert!(!matcher.matches("basic_auth"));
+    }
+
+    #[test]
+    fn it_is_not_created_without_names_or_patterns() {
+        assert!(NameMatcher::new(None, None).is_none());
+    }
+}
@@ -0,0 +1,276 @@
+use openapiv3::{Components, PathItem, ReferenceOr};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value as YamlValue};
+
+/// Key of the OpenAPI 3.1 reusable path items under `components`
+pub const PATH_ITEMS_KEY: &str = "pathItems";
+/// Extension key the reusable path items are kept under while the document is handled as `openapiv3::OpenAPI`
+///
+/// The `openapiv3` crate models OpenAPI 3.0 and only keeps `x-` prefixed unknown keys as extensions,
+/// so OpenAPI 3.1 fields are moved under extension keys when parsing and back when writing the output.
+pub const PATH_ITEMS_EXTENSION: &str = "x-openapiv3-filter-pathItems";
+
+/// Key of the OpenAPI 3.1 webhooks at the root of the document
+pub const WEBHOOKS_KEY: &str = "webhooks";
+/// Extension key the webhooks are kept under while the document is handled as `openapiv3::OpenAPI`
+pub const WEBHOOKS_EXTENSION: &str = "x-openapiv3-filter-webhooks";
+
+/// Extension key the callbacks of an operation are kept under when any of them is a reference
+///
+/// The `openapiv3` crate models operation callbacks only as inline objects, so callback maps containing
+/// `$ref: '#/components/callbacks/...'` entries are moved under this key of the operation, keeping their references
+/// visible to the reference collection.
+pub const CALLBACKS_EXTENSION: &str = "x-openapiv3-filter-callbacks";
+
+/// HTTP methods under which a path item holds its operations
+const PATH_ITEM_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Moves fields the `openapiv3` crate does not model under extension keys of the parsed document.
+///
+/// These are the operation callbacks that contain references and, for OpenAPI 3.1 documents, the reusable path items
+/// and webhooks. Non-string mapping keys inside the moved fields (e.g. unquoted response codes) are converted to strings,
+/// because extensions are stored as JSON values.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the YAML value of the whole document.
+/// * `openapi31` - Whether the OpenAPI 3.1 fields are kept. Otherwise they are left in place and dropped by deserializing.
+pub fn stash_fields(document: &mut YamlValue, openapi31: bool) {
+    for_each_path_item(document, stash_callbacks);
+    if !openapi31 {
+        return;
+    }
+    if let Some(root) = document.as_mapping_mut() {
+        rename_key(root, WEBHOOKS_KEY, WEBHOOKS_EXTENSION);
+        if let Some(webhooks) = root.get_mut(WEBHOOKS_EXTENSION) {
+            stringify_keys(webhooks);
+        }
+    }
+    if let Some(components) = document
+        .get_mut("components")
+        .and_then(|components| components.as_mapping_mut())
+    {
+        rename_key(components, PATH_ITEMS_KEY, PATH_ITEMS_EXTENSION);
+        if let Some(path_items) = components.get_mut(PATH_ITEMS_EXTENSION) {
+            stringify_keys(path_items);
+        }
+    }
+}
+
+/// Moves the fields stashed by `stash_fields` back under their own keys.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the YAML value of the whole document.
+pub fn restore_fields(document: &mut YamlValue) {
+    if let Some(components) = document
+        .get_mut("components")
+        .and_then(|components| components.as_mapping_mut())
+    {
+        rename_key(components, PATH_ITEMS_EXTENSION, PATH_ITEMS_KEY);
+    }
+    if let Some(root) = document.as_mapping_mut() {
+        rename_key(root, WEBHOOKS_EXTENSION, WEBHOOKS_KEY);
+    }
+    for_each_path_item(document, restore_callbacks);
+}
+
+/// Applies a function to the path items of the paths, the webhooks, the reusable path items and the reusable callbacks
+fn for_each_path_item(document: &mut YamlValue, apply: fn(&mut YamlValue)) {
+    for key in ["paths", WEBHOOKS_KEY, WEBHOOKS_EXTENSION] {
+        if let Some(path_items) = document
+            .get_mut(key)
+            .and_then(|path_items| path_items.as_mapping_mut())
+        {
+            path_items.values_mut().for_each(apply);
+        }
+    }
+    let Some(components) = document.get_mut("components") else {
+        return;
+    };
+    for category in [PATH_ITEMS_KEY, PATH_ITEMS_EXTENSION] {
+        if let Some(path_items) = components
+            .get_mut(category)
+            .and_then(|path_items| path_items.as_mapping_mut())
+        {
+            path_items.values_mut().for_each(apply);
+        }
+    }
+    if let Some(callbacks) = components
+        .get_mut("callbacks")
+        .and_then(|callbacks| callbacks.as_mapping_mut())
+    {
+        callbacks
+            .values_mut()
+            .filter_map(|callback| callback.as_mapping_mut())
+            .for_each(|callback| callback.values_mut().for_each(apply));
+    }
+}
+
+/// Returns the operations of a path item
+fn operations_mut(path_item: &mut YamlValue) -> impl Iterator<Item = &mut Mapping> {
+    path_item
+        .as_mapping_mut()
+        .into_iter()
+        .flat_map(|path_item| path_item.iter_mut())
+        .filter(|(method, _)| {
+            method
+                .as_str()
+                .is_some_and(|method| PATH_ITEM_METHODS.contains(&method))
+        })
+        .filter_map(|(_, operation)| operation.as_mapping_mut())
+}
+
+/// Returns the path items of the inline callbacks of an operation
+fn callback_path_items_mut(callbacks: &mut YamlValue) -> impl Iterator<Item = &mut YamlValue> {
+    callbacks
+        .as_mapping_mut()
+        .into_iter()
+        .flat_map(|callbacks| callbacks.values_mut())
+        .filter_map(|callback| callback.as_mapping_mut())
+        .flat_map(|callback| callback.values_mut())
+}
+
+/// Moves the callbacks of the operations of the path item under the extension key when any of them is a reference
+fn stash_callbacks(path_item: &mut YamlValue) {
+    for operation in operations_mut(path_item) {
+        let Some(callbacks) = operation.get_mut("callbacks") else {
+            continue;
+        };
+        callback_path_items_mut(callbacks).for_each(stash_callbacks);
+        let has_references = callbacks.as_mapping().is_some_and(|callbacks| {
+            callbacks
+                .values()
+                .any(|callback| callback.get("$ref").is_some())
+        });
+        if has_references {
+            stringify_keys(callbacks);
+            rename_key(operation, "callbacks", CALLBACKS_EXTENSION);
+        }
+    }
+}
+
+/// Moves the callbacks stashed by `stash_callbacks` back under the `callbacks` key of the operations
+fn restore_callbacks(path_item: &mut YamlValue) {
+    for operation in operations_mut(path_item) {
+        rename_key(operation, CALLBACKS_EXTENSION, "callbacks");
+        if let Some(callbacks) = operation.get_mut("callbacks") {
+            callback_path_items_mut(callbacks).for_each(restore_callbacks);
+        }
+    }
+}
+
+/// Serializes the document into a YAML value with the OpenAPI 3.1 fields moved back under their own keys.
+///
+/// # Arguments
+///
+/// * `document` - The document to serialize.
+///
+/// # Returns
+///
+/// * `Result<YamlValue, serde_yaml::Error>` - The YAML value of the document, or a serde_yaml::Error if serializing fails.
+pub fn to_document_value<T: Serialize>(document: &T) -> Result<YamlValue, serde_yaml::Error> {
+    let mut document = serde_yaml::to_value(document)?;
+    restore_fields(&mut document);
+    Ok(document)
+}
+
+/// Resolves a path item that may be a reference to the reusable path items of the components.
+///
+/// # Arguments
+///
+/// * `path_item` - The path item or a reference to it.
+/// * `components` - Components of the document.
+///
+/// # Returns
+///
+/// * `Option<PathItem>` - The resolved path item, or None when the reference cannot be resolved.
+pub fn resolve_path_item(
+    path_item: &ReferenceOr<PathItem>,
+    components: Option<&Components>,
+) -> Option<PathItem> {
+    match path_item {
+        ReferenceOr::Item(path_item) => Some(path_item.clone()),
+        ReferenceOr::Reference { reference } => reference
+            .strip_prefix("#/components/pathItems/")
+            .and_then(|name| {
+                components?
+                    .extensions
+                    .get(PATH_ITEMS_EXTENSION)?
+                    .get(name)
+                    .cloned()
+            })
+            .and_then(|path_item| serde_json::from_value(path_item).ok()),
+    }
+}
+
+/// Renames a key of the mapping keeping its position
+fn rename_key(mapping: &mut Mapping, from: &str, to: &str) {
+    if !mapping.contains_key(from) {
+        return;
+    }
+    *mapping = std::mem::take(mapping)
+        .into_iter()
+        .map(|(key, value)| match key.as_str() {
+            Some(key) if key == from => (YamlValue::from(to), value),
+            _ => (key, value),
+        })
+        .collect();
+}
+
+/// Recursively converts numeric and boolean mapping keys to strings
+fn stringify_keys(value: &mut YamlValue) {
+    match value {
+        YamlValue::Mapping(mapping) => {
+            *mapping = std::mem::take(mapping)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    stringify_keys(&mut value);
+                    let key = match key {
+                        YamlValue::Number(number) => YamlValue::from(number.to_string()),
+                        YamlValue::Bool(boolean) => YamlValue::from(boolean.to_string()),
+                        key => key,
+                    };
+                    (key, value)
+                })
+                .collect();
+        }
+        YamlValue::Sequence(items) => items.iter_mut().for_each(stringify_keys),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_stashes_and_restores_path_items() {
+        let original: YamlValue = serde_yaml::from_str(
+            "components:\n  schemas: {}\n  pathItems:\n    Pets:\n      get:\n        responses:\n          200:\n            description: ok\n  parameters: {}\n",
+        )
+        .unwrap();
+        let mut document = original.clone();
+
+        stash_fields(&mut document, true);
+        let components = document["components"].as_mapping().unwrap();
+        let keys: Vec<&str> = components.keys().filter_map(|key| key.as_str()).collect();
+        assert_eq!(keys, vec!["schemas", PATH_ITEMS_EXTENSION, "parameters"]);
+        assert!(
+            components[PATH_ITEMS_EXTENSION]["Pets"]["get"]["responses"]
+                .get("200")
+                .is_some()
+        );
+
+        restore_fields(&mut document);
+        let keys: Vec<&str> = document["components"]
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .filter_map(|key| key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["schemas", PATH_ITEMS_KEY, "parameters"]);
+    }
+}
@@ -0,0 +1,469 @@
+use indexmap::IndexMap;
+use openapiv3::{
+    Components, OpenAPI, Operation, Parameter, PathItem, ReferenceOr, Response, Responses, Tag,
+};
+use std::collections::HashSet;
+
+/// Generates an `operationId` for every operation of the paths that does not have one.
+///
+/// Generated ids are derived from the method and the path, e.g. `get_pet_petId` for `GET /pet/{petId}`.
+/// Ids colliding with existing or previously generated ids get a numeric suffix starting from `_2`.
+/// Operations of path items referenced from the components are not modified.
+///
+/// # Arguments
+///
+/// * `paths` - A mutable reference to the paths of the document.
+pub fn ensure_operation_ids(paths: &mut IndexMap<String, ReferenceOr<PathItem>>) {
+    let mut used_ids: HashSet<String> = paths
+        .values()
+        .filter_map(ReferenceOr::as_item)
+        .flat_map(|path| path.iter())
+        .filter_map(|(_, operation)| operation.operation_id.clone())
+        .collect();
+    for (path_name, path) in paths.iter_mut() {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+        for (method, operation) in operations_mut(path) {
+            if operation.operation_id.is_some() {
+                continue;
+            }
+            let base_id = generated_operation_id(method, path_name);
+            let operation_id = (1..)
+                .map(|index| match index {
+                    1 => base_id.clone(),
+                    index => format!("{}_{}", base_id, index),
+                })
+                .find(|candidate| !used_ids.contains(candidate))
+                .expect("There is always an unused suffix");
+            used_ids.insert(operation_id.clone());
+            operation.operation_id = Some(operation_id);
+        }
+    }
+}
+
+/// Adds a `default` response with the description `Unexpected error` to every operation of the paths without any response.
+///
+/// Operations that already have responses, with or without a `default` response, are not modified. Neither are the
+/// operations of path items referenced from the components.
+///
+/// # Arguments
+///
+/// * `paths` - A mutable reference to the paths of the document.
+pub fn ensure_default_responses(paths: &mut IndexMap<String, ReferenceOr<PathItem>>) {
+    for path in paths.values_mut() {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+        for (_, operation) in operations_mut(path) {
+            if operation.responses.default.is_none() && operation.responses.responses.is_empty() {
+                operation.responses.default = Some(ReferenceOr::Item(Response {
+                    description: String::from("Unexpected error"),
+                    ..Default::default()
+                }));
+            }
+        }
+    }
+}
+
+/// Tags the operations without tags by the first segment of their path, e.g. `pets` for `GET /pets/{petId}`.
+///
+/// A root tag is added for each derived tag that the document does not define yet. Paths starting with a template
+/// parameter or without any segment are left untagged, as are the operations of path items referenced from the components.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+pub fn tag_operations_by_path(openapi: &mut OpenAPI) {
+    let mut derived_tags: Vec<String> = Vec::new();
+    for (path_name, path) in openapi.paths.paths.iter_mut() {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+        let Some(tag) = path_name
+            .split('/')
+            .find(|segment| !segment.is_empty())
+            .filter(|segment| !segment.starts_with('{'))
+        else {
+            continue;
+        };
+        for (_, operation) in operations_mut(path) {
+            if operation.tags.is_empty() {
+                operation.tags.push(tag.to_string());
+                if !derived_tags.iter().any(|derived| derived == tag) {
+                    derived_tags.push(tag.to_string());
+                }
+            }
+        }
+    }
+    for name in derived_tags {
+        if !openapi.tags.iter().any(|tag| tag.name == name) {
+            openapi.tags.push(Tag {
+                name,
+                ..Default::default()
+            });
+        }
+    }
+}
+
+/// Removes the responses of the path item operations whose status code is not listed.
+///
+/// Codes are matched case-insensitively against the status codes as written in the document, e.g. `200`, `4XX` or `default`.
+///
+/// # Arguments
+///
+/// * `path` - A mutable reference to the path item with the kept operations.
+/// * `codes` - The status codes of the responses to keep.
+pub fn retain_responses(path: &mut PathItem, codes: &[String]) {
+    let is_kept = |code: &str| codes.iter().any(|kept| kept.eq_ignore_ascii_case(code));
+    for (_, operation) in operations_mut(path) {
+        if !is_kept("default") {
+            operation.responses.default = None;
+        }
+        operation
+            .responses
+            .responses
+            .retain(|status, _| is_kept(&status.to_string()));
+    }
+}
+
+/// Reduces the operations of the path item to stubs for mocking scaffolds.
+///
+/// Each operation keeps only its `operationId` and `summary` and gets a single `default` response with an empty description.
+/// The path level parameters are removed as well, so the stubs reference no components.
+///
+/// # Arguments
+///
+/// * `path` - A mutable reference to the path item with the kept operations.
+pub fn stub_operations(path: &mut PathItem) {
+    path.parameters.clear();
+    for (_, operation) in operations_mut(path) {
+        *operation = Operation {
+            operation_id: operation.operation_id.take(),
+            summary: operation.summary.take(),
+            responses: Responses {
+                default: Some(ReferenceOr::Item(Response::default())),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+    }
+}
+
+/// Removes an extension from the path item and its operations.
+///
+/// # Arguments
+///
+/// * `path` - A mutable reference to the path item.
+/// * `key` - The extension key, e.g. `x-internal`.
+pub fn remove_extension(path: &mut PathItem, key: &str) {
+    path.extensions.shift_remove(key);
+    for (_, operation) in operations_mut(path) {
+        operation.extensions.shift_remove(key);
+    }
+}
+
+/// Moves the path level parameters and servers of the path item into each of its operations.
+///
+/// Operations keep their own parameters where they have the same name and location as a path level parameter,
+/// since those override the path level ones, and operations with their own servers keep them. Parameters whose
+/// reference cannot be resolved are moved as they are.
+///
+/// # Arguments
+///
+/// * `path` - A mutable reference to the path item.
+/// * `components` - Components of the document, used to resolve referenced parameters.
+pub fn push_down_path_fields(path: &mut PathItem, components: Option<&Components>) {
+    let parameters = std::mem::take(&mut path.parameters);
+    let servers = std::mem::take(&mut path.servers);
+    let parameter_key = |parameter: &ReferenceOr<Parameter>| {
+        resolve_parameter(parameter, components).map(|parameter| {
+            (
+                parameter.parameter_data_ref().name.clone(),
+                parameter_location(parameter),
+            )
+        })
+    };
+    for (_, operation) in operations_mut(path) {
+        let overridden: HashSet<(String, &str)> = operation
+            .parameters
+            .iter()
+            .filter_map(parameter_key)
+            .collect();
+        let inherited: Vec<ReferenceOr<Parameter>> = parameters
+            .iter()
+            .filter(|parameter| {
+                parameter_key(parameter).is_none_or(|key| !overridden.contains(&key))
+            })
+            .cloned()
+            .collect();
+        operation.parameters.splice(0..0, inherited);
+        if operation.servers.is_empty() {
+            operation.servers = servers.clone();
+        }
+    }
+}
+
+/// Picks a random sample of the operations, seeded so that the same seed always gives the same sample.
+///
+/// The sampled operations are returned in their original order. All the operations are returned when there are no
+/// more of them than the sample size.
+///
+/// # Arguments
+///
+/// * `operations` - The (path, method) pairs of the operations to sample from.
+/// * `count` - The number of operations in the sample.
+/// * `seed` - The seed of the random number generator.
+///
+/// # Returns
+///
+/// * `Vec<(String, String)>` - The (path, method) pairs of the sampled operations.
+pub fn sample_operations(
+    operations: &[(String, String)],
+    count: usize,
+    seed: u64,
+) -> Vec<(String, String)> {
+    let mut state = seed;
+    let mut indexes: Vec<usize> = (0..operations.len()).collect();
+    // Partial Fisher-Yates shuffle, only the first `count` positions are needed
+    for position in 0..count.min(indexes.len()) {
+        let remaining = (indexes.len() - position) as u64;
+        let chosen = position + (next_random(&mut state) % remaining) as usize;
+        indexes.swap(position, chosen);
+    }
+    indexes.truncate(count);
+    indexes.sort_unstable();
+    indexes
+        .into_iter()
+        .map(|index| operations[index].clone())
+        .collect()
+}
+
+/// Advances the SplitMix64 generator state and returns the next random number
+fn next_random(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut value = *state;
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+/// Resolves a parameter that may be a reference to the parameters in document components.
+///
+/// # Arguments
+///
+/// * `parameter` - The parameter or a reference to it.
+/// * `components` - Components of the document.
+///
+/// # Returns
+///
+/// * `Option<&Parameter>` - The resolved parameter, or None when the reference cannot be resolved.
+pub fn resolve_parameter<'a>(
+    parameter: &'a ReferenceOr<Parameter>,
+    components: Option<&'a Components>,
+) -> Option<&'a Parameter> {
+    match parameter {
+        ReferenceOr::Item(parameter) => Some(parameter),
+        ReferenceOr::Reference { reference } => reference
+            .strip_prefix("#/components/parameters/")
+            .and_then(|name| components?.parameters.get(name))
+            .and_then(|parameter| parameter.as_item()),
+    }
+}
+
+/// Returns the location of the parameter as it is written in the `in` field of the parameter
+pub fn parameter_location(parameter: &Parameter) -> &'static str {
+    match parameter {
+        Parameter::Query { .. } => "query",
+        Parameter::Header { .. } => "header",
+        Parameter::Path { .. } => "path",
+        Parameter::Cookie { .. } => "cookie",
+    }
+}
+
+/// Returns the operations of the path item with their method names
+fn operations_mut(path: &mut PathItem) -> impl Iterator<Item = (&'static str, &mut Operation)> {
+    [
+        ("get", &mut path.get),
+        ("put", &mut path.put),
+        ("post", &mut path.post),
+        ("delete", &mut path.delete),
+        ("options", &mut path.options),
+        ("head", &mut path.head),
+        ("patch", &mut path.patch),
+        ("trace", &mut path.trace),
+    ]
+    .into_iter()
+    .filter_map(|(method, operation)| operation.as_mut().map(|operation| (method, operation)))
+}
+
+/// Derives an operation id from the method and the segments of the path, replacing characters other than letters and digits with underscores
+fn generated_operation_id(method: &str, path_name: &str) -> String {
+    let segments = path_name
+        .split('/')
+        .map(|segment| segment.trim_start_matches('{').trim_end_matches('}'))
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            segment
+                .chars()
+                .map(|character| {
+                    if character.is_ascii_alphanumeric() {
+                        character
+                    } else {
+                        '_'
+                    }
+                })
+                .collect::<String>()
+        });
+    std::iter::once(method.to_string())
+        .chain(segments)
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_generates_unique_operation_ids_for_operations_without_one() {
+        let mut paths: IndexMap<String, ReferenceOr<PathItem>> = serde_json::from_value(json!({
+            "/pet/{petId}": {
+                "get": {"responses": {}},
+                "delete": {"operationId": "removePet", "responses": {}}
+            },
+            "/pet/petId": {"get": {"responses": {}}},
+            "/pet/{petId}/": {"get": {"responses": {}}},
+            "/pet/{pet-id}": {"get": {"responses": {}}},
+            "/": {"get": {"responses": {}}},
+            "/store": {"post": {"operationId": "get_pet_petId_3", "responses": {}}}
+        }))
+        .unwrap();
+
+        ensure_operation_ids(&mut paths);
+
+        let operation_ids: Vec<(&str, &str)> = paths
+            .iter()
+            .flat_map(|(path_name, path)| {
+                path.as_item()
+                    .unwrap()
+                    .iter()
+                    .map(move |(method, operation)| {
+                        (
+                            method,
+                            operation.operation_id.as_deref().unwrap_or(path_name),
+                        )
+                    })
+            })
+            .collect();
+        assert_eq!(
+            operation_ids,
+            vec![
+                ("get", "get_pet_petId"),
+                ("delete", "removePet"),
+                ("get", "get_pet_petId_2"),
+                ("get", "get_pet_petId_4"),
+                ("get", "get_pet_pet_id"),
+                ("get", "get"),
+                ("post", "get_pet_petId_3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_tags_operations_by_the_first_path_segment() {
+        let mut openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Untagged", "version": "1.0.0"},
+            "tags": [{"name": "pets", "description": "Pets of the store"}],
+            "paths": {
+                "/pets/{petId}": {
+                    "get": {"responses": {}},
+                    "put": {"tags": ["admin"], "responses": {}}
+                },
+                "/users": {"get": {"responses": {}}},
+                "/{tenant}/orders": {"get": {"responses": {}}},
+                "/": {"get": {"responses": {}}}
+            }
+        }))
+        .unwrap();
+
+        tag_operations_by_path(&mut openapi);
+
+        let tags: Vec<(&str, &str, Vec<String>)> = openapi
+            .paths
+            .iter()
+            .flat_map(|(path_name, path)| {
+                path.as_item()
+                    .unwrap()
+                    .iter()
+                    .map(move |(method, operation)| {
+                        (path_name.as_str(), method, operation.tags.clone())
+                    })
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                ("/pets/{petId}", "get", vec![String::from("pets")]),
+                ("/pets/{petId}", "put", vec![String::from("admin")]),
+                ("/users", "get", vec![String::from("users")]),
+                ("/{tenant}/orders", "get", vec![]),
+                ("/", "get", vec![])
+            ]
+        );
+        let root_tags: Vec<(&str, Option<&str>)> = openapi
+            .tags
+            .iter()
+            .map(|tag| (tag.name.as_str(), tag.description.as_deref()))
+            .collect();
+        assert_eq!(
+            root_tags,
+            vec![("pets", Some("Pets of the store")), ("users", None)]
+        );
+    }
+
+    #[test]
+    fn it_samples_operations_deterministically_in_document_order() {
+        let operations: Vec<(String, String)> = (0..10)
+            .map(|index| (format!("/items/{}", index), String::from("get")))
+            .collect();
+
+        let sample = sample_operations(&operations, 3, 42);
+        assert_eq!(sample.len(), 3);
+        assert_eq!(sample, sample_operations(&operations, 3, 42));
+        let positions: Vec<usize> = sample
+            .iter()
+            .map(|operation| operations.iter().position(|o| o == operation).unwrap())
+            .collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_ne!(sample, sample_operations(&operations, 3, 7));
+        assert_eq!(sample_operations(&operations, 20, 42), operations);
+    }
+
+    #[test]
+    fn it_adds_default_responses_to_operations_without_responses() {
+        let mut paths: IndexMap<String, ReferenceOr<PathItem>> = serde_json::from_value(json!({
+            "/pets": {
+                "get": {"responses": {}},
+                "post": {"responses": {"201": {"description": "Created"}}}
+            },
+            "/pets/{petId}": {"$ref": "#/components/pathItems/Pet"}
+        }))
+        .unwrap();
+
+        ensure_default_responses(&mut paths);
+
+        assert_eq!(
+            serde_json::to_value(&paths).unwrap(),
+            json!({
+                "/pets": {
+                    "get": {"responses": {"default": {"description": "Unexpected error"}}},
+                    "post": {"responses": {"201": {"description": "Created"}}}
+                },
+                "/pets/{petId}": {"$ref": "#/components/pathItems/Pet"}
+            })
+        );
+    }
+}
@@ -1,6 +1,178 @@
-use openapiv3::Operation;
+use openapiv3::{Operation, SecurityRequirement};
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+/// Default maximum nesting depth the reference collectors descend into
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 512;
+
+/// Error returned when a document is nested deeper than the maximum recursion depth of the reference collectors
+#[derive(Debug, PartialEq)]
+pub struct RecursionLimitExceeded;
+
+impl fmt::Display for RecursionLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Document is nested deeper than the maximum recursion depth"
+        )
+    }
+}
+
+impl Error for RecursionLimitExceeded {}
+
+/// Finds local references that do not resolve to any content in the document.
+///
+/// Only references starting with `#` are checked; references to external documents are ignored.
+///
+/// # Arguments
+///
+/// * `document` - A reference to the JSON value of the whole document.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, RecursionLimitExceeded>` - The unresolved references in sorted order, or an error if the document is nested too deep.
+pub fn find_dangling_refs(document: &Value) -> Result<Vec<String>, RecursionLimitExceeded> {
+    let mut refs: HashSet<String> = HashSet::new();
+    collect_path_refs(document, &mut refs, None, DEFAULT_MAX_RECURSION_DEPTH)?;
+    let mut dangling_refs: Vec<String> = refs
+        .into_iter()
+        .filter(|reference| {
+            reference
+                .strip_prefix('#')
+                .is_some_and(|pointer| document.pointer(&decode_reference(pointer)).is_none())
+        })
+        .collect();
+    dangling_refs.sort();
+    Ok(dangling_refs)
+}
+
+/// Decodes the percent-encoded characters of a reference, e.g. `#/components/schemas/Customer%20Record`.
+///
+/// References are URIs, so characters of the component names can be percent-encoded in them. The JSON Pointer escapes
+/// `~0` and `~1` are kept, so the result can be compared with pointers built from escaped keys. A reference whose
+/// decoded bytes are not valid UTF-8 is returned as it is.
+///
+/// # Arguments
+///
+/// * `reference` - The `$ref` value to decode.
+///
+/// # Returns
+///
+/// * `String` - The reference with its percent-encoded characters decoded.
+pub fn decode_reference(reference: &str) -> String {
+    let bytes = reference.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .filter(|hex| bytes[index] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| reference.to_string())
+}
+
+/// Splits a JSON Pointer into its segments, unescaping `~1` to `/` and `~0` to `~` in each of them.
+///
+/// # Arguments
+///
+/// * `pointer` - A JSON Pointer without the leading `#`, e.g. `schemas/Order~1Detail`.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The unescaped segments, e.g. `["schemas", "Order/Detail"]`.
+pub fn pointer_segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Escapes a name for use as a JSON Pointer segment, replacing `~` with `~0` and `/` with `~1`.
+///
+/// # Arguments
+///
+/// * `name` - The name to escape, e.g. `Order/Detail`.
+///
+/// # Returns
+///
+/// * `String` - The escaped segment, e.g. `Order~1Detail`.
+pub fn escape_segment(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Finds local references that do not resolve to any content in the document, along with where they are used.
+///
+/// # Arguments
+///
+/// * `document` - A reference to the JSON value of the whole document.
+///
+/// # Returns
+///
+/// * `Result<Vec<(String, String)>, RecursionLimitExceeded>` - Pairs of the location of the object containing the `$ref`
+///   as a `#/...` pointer and the unresolved reference, in document order, or an error if the document is nested too deep.
+pub fn locate_dangling_refs(
+    document: &Value,
+) -> Result<Vec<(String, String)>, RecursionLimitExceeded> {
+    let dangling_refs: HashSet<String> = find_dangling_refs(document)?.into_iter().collect();
+    let mut locations = Vec::new();
+    if !dangling_refs.is_empty() {
+        locate_refs(document, "#", &dangling_refs, &mut locations);
+    }
+    Ok(locations)
+}
+
+/// Collects the locations of the references in the set, the depth is already limited by `find_dangling_refs`
+fn locate_refs(
+    value: &Value,
+    pointer: &str,
+    references: &HashSet<String>,
+    locations: &mut Vec<(String, String)>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str() {
+                    Some(reference) if key == "$ref" && references.contains(reference) => {
+                        locations.push((pointer.to_string(), reference.to_string()));
+                    }
+                    _ => {
+                        locate_refs(
+                            value,
+                            &format!("{}/{}", pointer, escape_segment(key)),
+                            references,
+                            locations,
+                        );
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                locate_refs(
+                    item,
+                    &format!("{}/{}", pointer, index),
+                    references,
+                    locations,
+                );
+            }
+        }
+        _ => {}
+    }
+}
 
 /// Recursively collects `$ref` object keys from the API specification under a given path.
 ///
@@ -13,35 +185,91 @@ use std::collections::{HashMap, HashSet};
 /// * `value` - A reference to the `Value` (JSON-like structure) to traverse.
 /// * `refs` - A mutable reference to a `HashSet<String>` to store the collected `$ref` values.
 /// * `key_name` - An optional reference to a `String` representing the key of the current value being processed.
-pub fn collect_path_refs(value: &Value, refs: &mut HashSet<String>, key_name: Option<&String>) {
+/// * `max_depth` - How many levels deeper the traversal is allowed to descend from `value`.
+///
+/// # Returns
+///
+/// * `Result<(), RecursionLimitExceeded>` - An error if the value is nested deeper than `max_depth`.
+pub fn collect_path_refs(
+    value: &Value,
+    refs: &mut HashSet<String>,
+    key_name: Option<&String>,
+    max_depth: usize,
+) -> Result<(), RecursionLimitExceeded> {
+    if max_depth == 0 {
+        return Err(RecursionLimitExceeded);
+    }
     match value {
         Value::Object(map) => {
             // Check if this object has a $ref key
-            if let Some(ref_value) = map.get("$ref") {
-                if let Some(ref_str) = ref_value.as_str() {
-                    refs.insert(ref_str.to_string());
-                }
+            if let Some(ref_value) = map.get("$ref")
+                && let Some(ref_str) = ref_value.as_str()
+            {
+                refs.insert(ref_str.to_string());
             }
 
             // Recurse into all object values
             for (k, v) in map {
-                collect_path_refs(v, refs, Some(k));
+                collect_path_refs(v, refs, Some(k), max_depth - 1)?;
             }
         }
         Value::Array(arr) => {
             // Recurse into array elements
             for item in arr {
-                collect_path_refs(item, refs, None);
+                collect_path_refs(item, refs, None, max_depth - 1)?;
             }
         }
         value => {
-            if key_name.is_some() && key_name.unwrap() == "$ref" {
-                if let Some(ref_str) = value.as_str() {
-                    refs.insert(ref_str.to_string());
+            if key_name.is_some_and(|key| key == "$ref")
+                && let Some(ref_str) = value.as_str()
+            {
+                refs.insert(ref_str.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Counts how many times each `$ref` value is used under a given value.
+///
+/// Works like `collect_path_refs`, but keeps the number of uses of each reference instead of only the distinct references.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the `Value` (JSON-like structure) to traverse.
+/// * `counts` - A mutable reference to a `HashMap<String, usize>` to add the uses of the `$ref` values to.
+/// * `max_depth` - How many levels deeper the traversal is allowed to descend from `value`.
+///
+/// # Returns
+///
+/// * `Result<(), RecursionLimitExceeded>` - An error if the value is nested deeper than `max_depth`.
+pub fn count_path_refs(
+    value: &Value,
+    counts: &mut HashMap<String, usize>,
+    max_depth: usize,
+) -> Result<(), RecursionLimitExceeded> {
+    if max_depth == 0 {
+        return Err(RecursionLimitExceeded);
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str() {
+                    Some(reference) if key == "$ref" => {
+                        *counts.entry(reference.to_string()).or_default() += 1;
+                    }
+                    _ => count_path_refs(value, counts, max_depth - 1)?,
                 }
             }
         }
+        Value::Array(items) => {
+            for item in items {
+                count_path_refs(item, counts, max_depth - 1)?;
+            }
+        }
+        _ => {}
     }
+    Ok(())
 }
 
 /// Collects all tags from under HTTP operation elements.
@@ -79,42 +307,58 @@ pub fn collect_operation_tags(
     });
 }
 
+/// Returns the security requirements of the operation, or the document level ones when the operation does not declare its own.
+///
+/// An operation with an empty `security` list overrides the document level requirements and has no security.
+///
+/// # Arguments
+///
+/// * `operation` - A reference to the operation.
+/// * `root_security` - The document level security requirements.
+///
+/// # Returns
+///
+/// * `Option<&Vec<SecurityRequirement>>` - The requirements that apply to the operation, or None when neither declares any.
+pub fn effective_security<'a>(
+    operation: &'a Operation,
+    root_security: Option<&'a Vec<SecurityRequirement>>,
+) -> Option<&'a Vec<SecurityRequirement>> {
+    operation.security.as_ref().or(root_security)
+}
+
 /// Collects security definitions under operation.
 ///
 /// This function iterates through a vector of `Operation` references and extracts all security requirements associated with each operation.
-/// The extracted security requirements are then added to the provided `tags` HashSet. If `allowed_securities` is not empty, only security
-/// requirements present in the `allowed_securities` set are collected.
+/// Operations without their own `security` use the document level requirements.
+/// The extracted security requirements are then added to the provided `tags` HashSet. Only security requirements accepted by
+/// the `is_allowed` predicate are collected.
 ///
 /// # Arguments
 ///
 /// * `operations` - A vector of references to `Operation` objects.
+/// * `root_security` - The document level security requirements inherited by operations without their own.
 /// * `tags` - A mutable reference to a `HashSet<String>` to store the collected security requirements.
-/// * `allowed_securities` - A reference to a `HashSet<String>` containing the allowed security requirements. If empty, all are collected.
+/// * `is_allowed` - A predicate returning true for the security scheme names that are collected. Accepts all names when not filtering by security.
 pub fn collect_operation_securities(
     operations: Vec<&&Operation>,
+    root_security: Option<&Vec<SecurityRequirement>>,
     tags: &mut HashSet<String>,
-    allowed_securities: &HashSet<String>,
+    is_allowed: &dyn Fn(&str) -> bool,
 ) {
-    let filter_securities = allowed_securities.iter().count() > 0;
     let found_securities: Vec<String> = operations
         .iter()
         .flat_map(|operation| {
-            operation.security.iter().flat_map(|vec_item| {
-                vec_item
-                    .iter()
-                    .map(|item| item.iter().map(|(key, _)| key.clone()).collect::<String>())
-            })
+            effective_security(operation, root_security)
+                .into_iter()
+                .flat_map(|vec_item| {
+                    vec_item
+                        .iter()
+                        .flat_map(|item| item.iter().map(|(key, _)| key.clone()))
+                })
         })
         .collect();
 
-    tags.extend(if filter_securities {
-        found_securities
-            .into_iter()
-            .filter(|item| allowed_securities.contains(item))
-            .collect()
-    } else {
-        found_securities
-    });
+    tags.extend(found_securities.into_iter().filter(|item| is_allowed(item)));
 }
 /// Collects references from under the components element in the API specification.
 ///
@@ -128,22 +372,34 @@ pub fn collect_operation_securities(
 /// * `current_path` - A string slice representing the current path within the `components` structure.
 /// * `refs` - A mutable reference to a `HashMap<String, Vec<String>>` to store the collected references.
 ///   The keys are the paths to the references, and the values are vectors of the reference strings.
-/// * `allowed_key_recursion_levels` - maximum recursion levels
+/// * `allowed_key_recursion_levels` - how many levels of keys make up the component pointers, e.g. 2 for the category and
+///   name of `#/components/requestBodies/CreatePet`. References nested deeper are attributed to that component, and the
+///   traversal itself continues down to `max_depth`.
 /// * `recursion_level` - current recursion level
+/// * `max_depth` - recursion level at which the traversal fails
+///
+/// # Returns
+///
+/// * `Result<(), RecursionLimitExceeded>` - An error if the value is nested deeper than `max_depth`.
 pub fn collect_component_refs(
     value: &Value,
     current_path: &str,
     refs: &mut HashMap<String, Vec<String>>,
-    allowed_key_recursion_levels: i8,
-    recursion_level: i8,
-) {
+    allowed_key_recursion_levels: usize,
+    recursion_level: usize,
+    max_depth: usize,
+) -> Result<(), RecursionLimitExceeded> {
+    if recursion_level >= max_depth {
+        return Err(RecursionLimitExceeded);
+    }
     match value {
         Value::Object(map) => {
             for (key, val) in map {
                 let new_path = if current_path.is_empty() {
                     key.to_string()
                 } else if recursion_level < allowed_key_recursion_levels {
-                    format!("{}/{}", current_path, key)
+                    // Keys are escaped like in the `$ref` pointers, e.g. `Order/Detail` as `Order~1Detail`
+                    format!("{}/{}", current_path, escape_segment(key))
                 } else {
                     current_path.to_string()
                 };
@@ -162,22 +418,161 @@ pub fn collect_component_refs(
                         refs,
                         allowed_key_recursion_levels,
                         recursion_level + 1,
-                    );
+                        max_depth,
+                    )?;
                 }
             }
         }
         Value::Array(array) => {
             for (index, item) in array.iter().enumerate() {
-                let new_path = format!("{}/{}", current_path, index);
+                // Indexes are part of the key only above the allowed levels, like object keys,
+                // so that references in arrays nested in a component are attributed to the component
+                let new_path = if recursion_level < allowed_key_recursion_levels {
+                    format!("{}/{}", current_path, index)
+                } else {
+                    current_path.to_string()
+                };
                 collect_component_refs(
                     item,
                     &new_path,
                     refs,
                     allowed_key_recursion_levels,
                     recursion_level + 1,
-                );
+                    max_depth,
+                )?;
             }
         }
         _ => {}
     }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_finds_dangling_refs() {
+        let document = json!({
+            "paths": {
+                "/pets": {"$ref": "#/components/pathItems/Pets"},
+                "/users": {"$ref": "#/components/pathItems/Users"},
+                "/orders": {"$ref": "orders.yaml#/Orders"}
+            },
+            "components": {"pathItems": {"Pets": {}}}
+        });
+
+        assert_eq!(
+            find_dangling_refs(&document),
+            Ok(vec![String::from("#/components/pathItems/Users")])
+        );
+    }
+
+    #[test]
+    fn it_locates_dangling_refs() {
+        let document = json!({
+            "paths": {
+                "/pets": {"get": {"parameters": [
+                    {"$ref": "#/components/parameters/Limit"},
+                    {"$ref": "#/components/parameters/Missing"}
+                ]}}
+            },
+            "components": {
+                "parameters": {"Limit": {}},
+                "schemas": {"Pet": {"$ref": "#/components/schemas/Missing"}}
+            }
+        });
+
+        assert_eq!(
+            locate_dangling_refs(&document),
+            Ok(vec![
+                (
+                    String::from("#/paths/~1pets/get/parameters/1"),
+                    String::from("#/components/parameters/Missing")
+                ),
+                (
+                    String::from("#/components/schemas/Pet"),
+                    String::from("#/components/schemas/Missing")
+                )
+            ])
+        );
+    }
+
+    #[test]
+    fn it_attributes_references_in_arrays_to_their_component() {
+        let components = json!({
+            "schemas": {
+                "Dog": {"allOf": [{"$ref": "#/components/schemas/Pet"}]},
+                "Pet": {"type": "object"}
+            }
+        });
+
+        let mut refs = HashMap::new();
+        collect_component_refs(
+            &components,
+            "#/components",
+            &mut refs,
+            2,
+            0,
+            DEFAULT_MAX_RECURSION_DEPTH,
+        )
+        .unwrap();
+
+        assert_eq!(
+            refs,
+            HashMap::from([(
+                String::from("#/components/schemas/Dog"),
+                vec![String::from("#/components/schemas/Pet")]
+            )])
+        );
+    }
+
+    #[test]
+    fn it_fails_gracefully_on_deeply_nested_values() {
+        let mut value = json!({"$ref": "#/components/schemas/Deep"});
+        // Every round adds an object and an array level
+        for _ in 0..DEFAULT_MAX_RECURSION_DEPTH / 2 + 1 {
+            value = json!({ "nested": [value] });
+        }
+
+        let mut refs = HashSet::new();
+        assert_eq!(
+            collect_path_refs(&value, &mut refs, None, DEFAULT_MAX_RECURSION_DEPTH),
+            Err(RecursionLimitExceeded)
+        );
+        assert_eq!(
+            collect_component_refs(
+                &value,
+                "#/components",
+                &mut HashMap::new(),
+                2,
+                0,
+                DEFAULT_MAX_RECURSION_DEPTH
+            ),
+            Err(RecursionLimitExceeded)
+        );
+        assert_eq!(
+            collect_path_refs(&value, &mut refs, None, DEFAULT_MAX_RECURSION_DEPTH * 2),
+            Ok(())
+        );
+        assert!(refs.contains("#/components/schemas/Deep"));
+    }
+
+    #[test]
+    fn it_decodes_references_and_unescapes_pointer_segments() {
+        assert_eq!(
+            decode_reference("#/components/schemas/Customer%20Record"),
+            "#/components/schemas/Customer Record"
+        );
+        assert_eq!(
+            decode_reference("#/components/schemas/Order~1Detail%2"),
+            "#/components/schemas/Order~1Detail%2"
+        );
+        assert_eq!(decode_reference("#/a%+1b%C3%A4"), "#/a%+1bä");
+        assert_eq!(
+            pointer_segments("schemas/Order~1Detail/Amount~0EUR~01"),
+            vec!["schemas", "Order/Detail", "Amount~EUR~1"]
+        );
+    }
 }
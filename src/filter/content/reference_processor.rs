@@ -1,3 +1,4 @@
+use crate::filter::content::reference_collector::RecursionLimitExceeded;
 use std::collections::{HashMap, HashSet};
 
 /// Constructs reference path arrays from all the component references.
@@ -14,16 +15,25 @@ use std::collections::{HashMap, HashSet};
 /// * `current_path` - A vector of strings representing the current path being explored.
 /// * `visited` - A mutable reference to a `HashSet<String>` to track visited keys in the current path.
 /// * `result` - A mutable reference to a `Vec<Vec<String>>` to store the resulting reference paths.
+/// * `max_depth` - Maximum length of a reference path.
+///
+/// # Returns
+///
+/// * `Result<(), RecursionLimitExceeded>` - An error if a reference chain is longer than `max_depth`.
 fn collect_reference_paths(
     map: &HashMap<String, Vec<String>>,
     key: &str,
     current_path: Vec<String>,
     visited: &mut HashSet<String>,
     result: &mut Vec<Vec<String>>,
-) {
+    max_depth: usize,
+) -> Result<(), RecursionLimitExceeded> {
     // If we already visited this key in the same path, avoid infinite loops
     if visited.contains(key) {
-        return;
+        return Ok(());
+    }
+    if current_path.len() >= max_depth {
+        return Err(RecursionLimitExceeded);
     }
 
     // Add key to visited set to prevent cycles
@@ -39,12 +49,13 @@ fn collect_reference_paths(
     // Recursively explore references in the map
     if let Some(references) = map.get(key) {
         for ref_key in references {
-            collect_reference_paths(map, ref_key, new_path.clone(), visited, result);
+            collect_reference_paths(map, ref_key, new_path.clone(), visited, result, max_depth)?;
         }
     }
 
     // Backtrack: Remove from visited to allow other paths to use it
     visited.remove(key);
+    Ok(())
 }
 
 /// Generates all possible reference paths from a map of component references.
@@ -56,20 +67,25 @@ fn collect_reference_paths(
 ///
 /// * `map` - A reference to a `HashMap<String, Vec<String>>` representing the component references.
 ///   The keys are component names, and the values are vectors of component names that the key component references.
+/// * `max_depth` - Maximum length of a reference path.
 ///
 /// # Returns
 ///
-/// * `Vec<Vec<String>>` - A vector of vectors, where each inner vector represents a reference path.
-fn reference_paths(map: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
+/// * `Result<Vec<Vec<String>>, RecursionLimitExceeded>` - A vector of vectors, where each inner vector represents a reference path,
+///   or an error if a reference chain is longer than `max_depth`.
+fn reference_paths(
+    map: &HashMap<String, Vec<String>>,
+    max_depth: usize,
+) -> Result<Vec<Vec<String>>, RecursionLimitExceeded> {
     let mut result = Vec::new();
 
     // Start DFS traversal from each key
     for key in map.keys() {
         let mut visited = HashSet::new();
-        collect_reference_paths(map, key, Vec::new(), &mut visited, &mut result);
+        collect_reference_paths(map, key, Vec::new(), &mut visited, &mut result, max_depth)?;
     }
 
-    result
+    Ok(result)
 }
 
 /// Filters out references that are not needed according to filtering parameters.
@@ -83,15 +99,18 @@ fn reference_paths(map: &HashMap<String, Vec<String>>) -> Vec<Vec<String>> {
 /// * `map` - A reference to a `HashMap<String, Vec<String>>` representing the component references.
 ///   The keys are component names, and the values are vectors of component names that the key component references.
 /// * `referenced_components` - A reference to a `HashSet<String>` containing the names of the referenced components.
+/// * `max_depth` - Maximum length of a reference chain between components.
 ///
 /// # Returns
 ///
-/// * `HashSet<String>` - A set of all unique references that are kept after filtering.
+/// * `Result<HashSet<String>, RecursionLimitExceeded>` - A set of all unique references that are kept after filtering,
+///   or an error if a reference chain is longer than `max_depth`.
 pub fn get_kept_references(
     map: &HashMap<String, Vec<String>>,
     referenced_components: &HashSet<String>,
-) -> HashSet<String> {
-    let mut kept_references = reference_paths(map)
+    max_depth: usize,
+) -> Result<HashSet<String>, RecursionLimitExceeded> {
+    let mut kept_references = reference_paths(map, max_depth)?
         .iter()
         .filter(|reference_path| !reference_path.is_empty())
         .filter(|reference_path| referenced_components.contains(&reference_path[0]))
@@ -104,12 +123,13 @@ pub fn get_kept_references(
             .cloned()
             .collect::<HashSet<String>>(),
     );
-    kept_references
+    Ok(kept_references)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::filter::content::reference_collector::DEFAULT_MAX_RECURSION_DEPTH;
 
     #[test]
     fn it_collects_multi_level_references() {
@@ -128,9 +148,9 @@ mod tests {
             String::from("D"),
         ];
         let short_vector = vec![String::from("E"), String::from("F"), String::from("G")];
-        let result = reference_paths(&map);
+        let result = reference_paths(&map, DEFAULT_MAX_RECURSION_DEPTH).unwrap();
 
-        assert!(contains_all(&result, &vec![long_vector, short_vector]));
+        assert!(contains_all(&result, &[long_vector, short_vector]));
     }
 
     #[test]
@@ -149,7 +169,9 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect::<HashSet<_>>(),
-        );
+            DEFAULT_MAX_RECURSION_DEPTH,
+        )
+        .unwrap();
 
         assert_eq!(result.len(), 4);
         assert!(result.contains("D"));
@@ -158,7 +180,23 @@ mod tests {
         assert!(result.contains("G"));
     }
 
-    fn contains_all(vec_of_vecs: &Vec<Vec<String>>, target_vec_of_vecs: &Vec<Vec<String>>) -> bool {
+    #[test]
+    fn it_fails_gracefully_on_too_long_reference_chains() {
+        let chain_length = DEFAULT_MAX_RECURSION_DEPTH * 2;
+        let map: HashMap<String, Vec<String>> = (0..chain_length)
+            .map(|index| (index.to_string(), vec![(index + 1).to_string()]))
+            .collect();
+
+        let result = get_kept_references(
+            &map,
+            &HashSet::from([String::from("0")]),
+            DEFAULT_MAX_RECURSION_DEPTH,
+        );
+
+        assert_eq!(result, Err(RecursionLimitExceeded));
+    }
+
+    fn contains_all(vec_of_vecs: &[Vec<String>], target_vec_of_vecs: &[Vec<String>]) -> bool {
         // Check if each target vector exists in the vec_of_vecs
         target_vec_of_vecs
             .iter()
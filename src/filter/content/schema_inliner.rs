@@ -0,0 +1,181 @@
+use crate::filter::content::component_renamer::SCHEMA_REFERENCE_PREFIX;
+use crate::filter::content::reference_collector::{
+    RecursionLimitExceeded, count_path_refs, escape_segment,
+};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Inlines the component schemas that are referenced exactly once into their usage site and removes them from `components.schemas`.
+///
+/// Schemas referenced more than once stay as components, as do schemas that reference themselves, schemas named in a
+/// discriminator mapping and schemas with references to locations inside them, since those need the schema to have a name.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the JSON value of the whole document.
+/// * `max_depth` - How deep the document may be nested.
+///
+/// # Returns
+///
+/// * `Result<(), RecursionLimitExceeded>` - An error if the document is nested deeper than `max_depth`.
+pub fn inline_single_use_schemas(
+    document: &mut Value,
+    max_depth: usize,
+) -> Result<(), RecursionLimitExceeded> {
+    let Some(Value::Object(schemas)) = document.pointer("/components/schemas") else {
+        return Ok(());
+    };
+    let names: Vec<String> = schemas.keys().cloned().collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    count_path_refs(document, &mut counts, max_depth)?;
+    let mut mapped_schemas: HashSet<String> = HashSet::new();
+    collect_mapping_targets(document, &mut mapped_schemas);
+    for name in names {
+        let reference = format!("{}{}", SCHEMA_REFERENCE_PREFIX, escape_segment(&name));
+        let inner_prefix = format!("{}/", reference);
+        if counts.get(&reference) != Some(&1)
+            || mapped_schemas.contains(&name)
+            || mapped_schemas.contains(&reference)
+            || counts.keys().any(|key| key.starts_with(&inner_prefix))
+        {
+            continue;
+        }
+        let Some(Value::Object(schemas)) = document.pointer_mut("/components/schemas") else {
+            break;
+        };
+        let Some(schema) = schemas.shift_remove(&name) else {
+            continue;
+        };
+        let mut schema_counts: HashMap<String, usize> = HashMap::new();
+        count_path_refs(&schema, &mut schema_counts, max_depth)?;
+        if schema_counts.contains_key(&reference) {
+            // The only use is the schema itself, so it cannot be inlined
+            schemas.insert(name, schema);
+            continue;
+        }
+        if let Some(site) = find_reference(document, &reference, String::new())
+            && let Some(target) = document.pointer_mut(&site)
+        {
+            *target = schema;
+        }
+    }
+    Ok(())
+}
+
+/// Collects the targets of discriminator mappings, both plain schema names and references
+fn collect_mapping_targets(value: &Value, targets: &mut HashSet<String>) {
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Object(mapping)) = map
+                .get("discriminator")
+                .and_then(|discriminator| discriminator.get("mapping"))
+            {
+                targets.extend(
+                    mapping
+                        .values()
+                        .filter_map(|target| target.as_str().map(str::to_string)),
+                );
+            }
+            map.values()
+                .for_each(|value| collect_mapping_targets(value, targets));
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_mapping_targets(item, targets)),
+        _ => {}
+    }
+}
+
+/// Returns the JSON pointer of the first object using the reference
+fn find_reference(value: &Value, reference: &str, pointer: String) -> Option<String> {
+    match value {
+        Value::Object(map) if map.get("$ref").and_then(Value::as_str) == Some(reference) => {
+            Some(pointer)
+        }
+        Value::Object(map) => map.iter().find_map(|(key, value)| {
+            find_reference(
+                value,
+                reference,
+                format!("{}/{}", pointer, escape_segment(key)),
+            )
+        }),
+        Value::Array(items) => items.iter().enumerate().find_map(|(index, item)| {
+            find_reference(item, reference, format!("{}/{}", pointer, index))
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_inlines_schemas_referenced_once() {
+        let mut document = json!({
+            "paths": {"/pets": {"get": {"responses": {"200": {
+                "description": "Pets",
+                "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pets"}}}
+            }}}}},
+            "components": {
+                "schemas": {
+                    "Pets": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}},
+                    "Pet": {"properties": {
+                        "status": {"$ref": "#/components/schemas/Status"},
+                        "previousStatus": {"$ref": "#/components/schemas/Status"},
+                        "parent": {"$ref": "#/components/schemas/Pet"}
+                    }},
+                    "Status": {"type": "string", "enum": ["available", "sold"]},
+                    "Unused": {"type": "string"}
+                }
+            }
+        });
+
+        inline_single_use_schemas(&mut document, 64).unwrap();
+
+        assert_eq!(
+            document,
+            json!({
+                "paths": {"/pets": {"get": {"responses": {"200": {
+                    "description": "Pets",
+                    "content": {"application/json": {"schema": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}}}}
+                }}}}},
+                "components": {
+                    "schemas": {
+                        "Pet": {"properties": {
+                            "status": {"$ref": "#/components/schemas/Status"},
+                            "previousStatus": {"$ref": "#/components/schemas/Status"},
+                            "parent": {"$ref": "#/components/schemas/Pet"}
+                        }},
+                        "Status": {"type": "string", "enum": ["available", "sold"]},
+                        "Unused": {"type": "string"}
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_keeps_schemas_named_by_discriminators_and_inner_references() {
+        let mut document = json!({
+            "components": {
+                "schemas": {
+                    "Pet": {
+                        "oneOf": [{"$ref": "#/components/schemas/Dog"}],
+                        "discriminator": {"propertyName": "kind", "mapping": {"dog": "Dog"}},
+                        "properties": {"id": {"$ref": "#/components/schemas/Id/properties/value"}}
+                    },
+                    "Dog": {"type": "object"},
+                    "Id": {"properties": {"value": {"type": "integer"}}},
+                    "Owner": {"properties": {"id": {"$ref": "#/components/schemas/Id"}}}
+                }
+            }
+        });
+        let expected = document.clone();
+
+        inline_single_use_schemas(&mut document, 64).unwrap();
+
+        assert_eq!(document, expected);
+    }
+}
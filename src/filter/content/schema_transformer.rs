@@ -0,0 +1,630 @@
+use crate::filter::content::reference_collector::pointer_segments;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Schema keywords whose value is a single schema
+const SCHEMA_KEYWORDS: [&str; 3] = ["items", "additionalProperties", "not"];
+/// Schema keywords whose value is a list of schemas
+const SCHEMA_LIST_KEYWORDS: [&str; 3] = ["allOf", "oneOf", "anyOf"];
+/// Schema keywords whose value is a map of schemas
+const SCHEMA_MAP_KEYWORDS: [&str; 2] = ["properties", "patternProperties"];
+
+/// Recursively visits a schema and all of its inline subschemas.
+///
+/// This function calls `visit` for the schema object and then descends into the keywords that contain subschemas
+/// (`properties`, `items`, `allOf` and so on). Keys of `properties` are property names, not schema keywords, so they are never visited as schemas.
+/// Subschemas are visited after their parent, so the visitor sees the parent before any of its children are modified.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+/// * `visit` - A function that is called with every schema object found.
+pub fn walk_schema(schema: &mut Value, visit: &mut dyn FnMut(&mut Map<String, Value>)) {
+    if let Value::Object(map) = schema {
+        visit(map);
+        for keyword in SCHEMA_KEYWORDS {
+            if let Some(subschema) = map.get_mut(keyword) {
+                walk_schema(subschema, visit);
+            }
+        }
+        for keyword in SCHEMA_LIST_KEYWORDS {
+            if let Some(Value::Array(subschemas)) = map.get_mut(keyword) {
+                subschemas
+                    .iter_mut()
+                    .for_each(|subschema| walk_schema(subschema, visit));
+            }
+        }
+        for keyword in SCHEMA_MAP_KEYWORDS {
+            if let Some(Value::Object(subschemas)) = map.get_mut(keyword) {
+                subschemas
+                    .values_mut()
+                    .for_each(|subschema| walk_schema(subschema, visit));
+            }
+        }
+    }
+}
+
+/// Removes `example` and `examples` keywords from the schema and all of its inline subschemas.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn prune_schema_examples(schema: &mut Value) {
+    walk_schema(schema, &mut |schema| {
+        schema.remove("example");
+        schema.remove("examples");
+    });
+}
+
+/// Adds `additionalProperties: false` to the object schemas that do not specify `additionalProperties`.
+///
+/// Schemas using `allOf`, `oneOf` or `anyOf` are left as they are, as are their direct subschemas:
+/// closing the members of a composition would reject the properties declared by the other members.
+/// Properties and items of the members are still visited.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn disallow_additional_properties(schema: &mut Value) {
+    close_object_schemas(schema, false);
+}
+
+/// Adds `additionalProperties: false` to the subschemas of a schema that is a member of a composition.
+///
+/// The schema itself is left open like the inline members of compositions, e.g. a component schema that other schemas
+/// extend with `allOf`.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn disallow_additional_properties_in_member(schema: &mut Value) {
+    close_object_schemas(schema, true);
+}
+
+/// Collects the names of the component schemas that are referenced directly as members of `allOf`, `oneOf` or `anyOf`.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the JSON value of a document element, e.g. the components or the paths.
+/// * `members` - A mutable reference to the set collecting the unescaped schema names.
+pub fn collect_composition_members(value: &Value, members: &mut HashSet<String>) {
+    match value {
+        Value::Object(map) => {
+            for keyword in SCHEMA_LIST_KEYWORDS {
+                if let Some(Value::Array(subschemas)) = map.get(keyword) {
+                    members.extend(
+                        subschemas
+                            .iter()
+                            .filter_map(|subschema| subschema.get("$ref")?.as_str())
+                            .filter_map(|reference| reference.strip_prefix("#/components/schemas/"))
+                            .map(|name| pointer_segments(name).join("/")),
+                    );
+                }
+            }
+            map.values()
+                .for_each(|child| collect_composition_members(child, members));
+        }
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| collect_composition_members(item, members)),
+        _ => {}
+    }
+}
+
+fn close_object_schemas(schema: &mut Value, composition_member: bool) {
+    if let Value::Object(map) = schema {
+        let is_composed = SCHEMA_LIST_KEYWORDS
+            .iter()
+            .any(|keyword| map.contains_key(*keyword));
+        let is_object = map.get("type").is_some_and(|kind| kind == "object")
+            || (!map.contains_key("type") && map.contains_key("properties"));
+        if is_object
+            && !is_composed
+            && !composition_member
+            && !map.contains_key("additionalProperties")
+        {
+            map.insert(String::from("additionalProperties"), Value::Bool(false));
+        }
+        for keyword in SCHEMA_KEYWORDS {
+            if let Some(subschema) = map.get_mut(keyword) {
+                close_object_schemas(subschema, false);
+            }
+        }
+        for keyword in SCHEMA_LIST_KEYWORDS {
+            if let Some(Value::Array(subschemas)) = map.get_mut(keyword) {
+                subschemas
+                    .iter_mut()
+                    .for_each(|subschema| close_object_schemas(subschema, true));
+            }
+        }
+        for keyword in SCHEMA_MAP_KEYWORDS {
+            if let Some(Value::Object(subschemas)) = map.get_mut(keyword) {
+                subschemas
+                    .values_mut()
+                    .for_each(|subschema| close_object_schemas(subschema, false));
+            }
+        }
+    }
+}
+
+/// Removes the properties marked with `readOnly: true` from the schema and its inline subschemas.
+///
+/// The removed properties are also removed from the `required` list of their schema, so the schema describes a request model.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn remove_read_only_properties(schema: &mut Value) {
+    remove_flagged_properties(schema, "readOnly");
+}
+
+/// Removes the properties marked with `writeOnly: true` from the schema and its inline subschemas.
+///
+/// The removed properties are also removed from the `required` list of their schema, so the schema describes a response model.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn remove_write_only_properties(schema: &mut Value) {
+    remove_flagged_properties(schema, "writeOnly");
+}
+
+/// Removes the properties whose schema sets the boolean keyword to true, along with their `required` entries
+fn remove_flagged_properties(schema: &mut Value, keyword: &str) {
+    walk_schema(schema, &mut |schema| {
+        let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+            return;
+        };
+        let flagged: Vec<String> = properties
+            .iter()
+            .filter(|(_, property)| property.get(keyword) == Some(&Value::Bool(true)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if flagged.is_empty() {
+            return;
+        }
+        flagged.iter().for_each(|name| {
+            properties.remove(name);
+        });
+        if let Some(Value::Array(required)) = schema.get_mut("required") {
+            required.retain(|name| {
+                !name
+                    .as_str()
+                    .is_some_and(|name| flagged.iter().any(|flagged| flagged == name))
+            });
+            if required.is_empty() {
+                schema.remove("required");
+            }
+        }
+    });
+}
+
+/// Unwraps `allOf` compositions with a single inline subschema in the schema and its inline subschemas.
+///
+/// The keywords of the subschema replace the `allOf` keyword of the composing schema. Compositions with several subschemas,
+/// a `$ref` subschema or keywords defined both in the composing schema and in the subschema are left as they are,
+/// so the unwrapped schema always validates the same values.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn unwrap_single_all_of(schema: &mut Value) {
+    walk_schema(schema, &mut |schema| while unwrap_all_of(schema) {});
+}
+
+/// Replaces a single inline `allOf` subschema with its keywords, returning true when the schema was changed
+fn unwrap_all_of(schema: &mut Map<String, Value>) -> bool {
+    let Some(Value::Array(subschemas)) = schema.get("allOf") else {
+        return false;
+    };
+    let [Value::Object(subschema)] = subschemas.as_slice() else {
+        return false;
+    };
+    // The subschema may be a single `allOf` wrapper itself, which replaces the unwrapped `allOf`
+    let conflicts = subschema
+        .keys()
+        .any(|key| key != "allOf" && schema.contains_key(key));
+    if subschema.contains_key("$ref") || conflicts {
+        return false;
+    }
+    let subschema = subschema.clone();
+    *schema = std::mem::take(schema)
+        .into_iter()
+        .flat_map(|(key, value)| match key.as_str() {
+            "allOf" => subschema.clone().into_iter().collect::<Vec<_>>(),
+            _ => vec![(key, value)],
+        })
+        .collect();
+    true
+}
+
+/// Flattens `allOf` compositions of the schema and its inline subschemas into single object schemas.
+///
+/// The `properties` and `required` lists of the subschemas are combined into the composing schema, and `type` is taken from the subschemas.
+/// Subschemas referencing `#/components/schemas` are resolved from `schemas`. Only single-level compositions are flattened:
+/// compositions with subschemas that are compositions themselves, or that reference schemas missing from `schemas`, are left as they are.
+/// When several subschemas define the same property differently, the last definition is kept.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+/// * `schemas` - The component schemas that references of the subschemas are resolved from.
+///
+/// # Returns
+///
+/// * `Vec<String>` - Names of the properties whose conflicting definitions were replaced by a later definition.
+pub fn merge_all_of(schema: &mut Value, schemas: &Map<String, Value>) -> Vec<String> {
+    let mut conflicts: Vec<String> = Vec::new();
+    walk_schema(schema, &mut |schema| {
+        let Some(subschemas) = all_of_subschemas(schema, schemas) else {
+            return;
+        };
+        schema.remove("allOf");
+        for subschema in subschemas {
+            if let Some(Value::Object(properties)) = subschema.get("properties") {
+                let merged_properties = schema
+                    .entry("properties")
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Value::Object(merged_properties) = merged_properties {
+                    for (name, property) in properties {
+                        if merged_properties
+                            .insert(name.clone(), property.clone())
+                            .is_some_and(|previous| previous != *property)
+                        {
+                            conflicts.push(name.clone());
+                        }
+                    }
+                }
+            }
+            if let Some(Value::Array(required)) = subschema.get("required") {
+                let merged_required = schema
+                    .entry("required")
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(merged_required) = merged_required {
+                    for name in required {
+                        if !merged_required.contains(name) {
+                            merged_required.push(name.clone());
+                        }
+                    }
+                }
+            }
+            if let Some(kind) = subschema.get("type") {
+                schema.insert(String::from("type"), kind.clone());
+            }
+        }
+        if !schema.contains_key("type") && schema.contains_key("properties") {
+            schema.insert(String::from("type"), Value::from("object"));
+        }
+    });
+    conflicts
+}
+
+/// Resolves the subschemas of a single-level `allOf` composition, or returns None when the composition cannot be flattened
+fn all_of_subschemas(
+    schema: &Map<String, Value>,
+    schemas: &Map<String, Value>,
+) -> Option<Vec<Value>> {
+    let Some(Value::Array(subschemas)) = schema.get("allOf") else {
+        return None;
+    };
+    subschemas
+        .iter()
+        .map(|subschema| {
+            let subschema = match subschema.get("$ref").and_then(Value::as_str) {
+                Some(reference) => schemas.get(reference.strip_prefix("#/components/schemas/")?)?,
+                None => subschema,
+            };
+            let is_composed = SCHEMA_LIST_KEYWORDS
+                .iter()
+                .any(|keyword| subschema.get(*keyword).is_some());
+            (subschema.is_object() && !is_composed).then(|| subschema.clone())
+        })
+        .collect()
+}
+
+/// Rewrites the OpenAPI 3.0 specific keywords of the schema and its inline subschemas to their JSON Schema 2020-12 equivalents.
+///
+/// * `nullable: true` adds `"null"` to the `type`, e.g. `type: string` becomes `type: [string, "null"]`, and to the
+///   values of an `enum`, since they would reject `null` otherwise. A `const` becomes an `enum` of the constant and
+///   `null`. The keyword is removed without a replacement when the schema has none of these, since it has no effect then.
+/// * `example` becomes a single item `examples` list, placed before the items of an existing `examples` list.
+/// * Boolean `exclusiveMinimum` and `exclusiveMaximum` become the numeric bound taken from `minimum` and `maximum`.
+///
+/// # Arguments
+///
+/// * `schema` - A mutable reference to the JSON value of the schema.
+pub fn convert_to_json_schema_2020_12(schema: &mut Value) {
+    walk_schema(schema, &mut |schema| {
+        if schema.shift_remove("nullable") == Some(Value::Bool(true)) {
+            if let Some(Value::String(schema_type)) = schema.get("type") {
+                let schema_type = Value::Array(vec![
+                    Value::String(schema_type.clone()),
+                    Value::String(String::from("null")),
+                ]);
+                schema.insert(String::from("type"), schema_type);
+            }
+            if let Some(constant) = schema.shift_remove("const") {
+                schema.insert(String::from("enum"), Value::Array(vec![constant]));
+            }
+            if let Some(Value::Array(values)) = schema.get_mut("enum")
+                && !values.contains(&Value::Null)
+            {
+                values.push(Value::Null);
+            }
+        }
+        if let Some(example) = schema.shift_remove("example") {
+            match schema.get_mut("examples") {
+                Some(Value::Array(examples)) => examples.insert(0, example),
+                _ => {
+                    schema.insert(String::from("examples"), Value::Array(vec![example]));
+                }
+            }
+        }
+        for (exclusive, bound) in [
+            ("exclusiveMinimum", "minimum"),
+            ("exclusiveMaximum", "maximum"),
+        ] {
+            if let Some(Value::Bool(is_exclusive)) = schema.get(exclusive).cloned() {
+                schema.shift_remove(exclusive);
+                if is_exclusive && let Some(bound) = schema.shift_remove(bound) {
+                    schema.insert(String::from(exclusive), bound);
+                }
+            }
+        }
+    });
+}
+
+/// Applies a schema transform to every inline schema found under a document element.
+///
+/// Inline schemas are the values of the `schema` keys of parameters, headers and media types.
+/// Example values are not descended into, since they may contain `schema` keys that are not schemas.
+///
+/// # Arguments
+///
+/// * `value` - A mutable reference to the JSON value of a document element, e.g. paths or request bodies.
+/// * `transform` - A function that transforms a single schema.
+pub fn transform_inline_schemas(value: &mut Value, transform: fn(&mut Value)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match key.as_str() {
+                    "schema" => transform(child),
+                    "example" | "examples" => {}
+                    _ => transform_inline_schemas(child, transform),
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| transform_inline_schemas(item, transform)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_prunes_examples_from_nested_schemas() {
+        let mut schema = json!({
+            "type": "object",
+            "example": {"example": "value"},
+            "properties": {
+                "example": {"type": "string", "example": "text"},
+                "tags": {"type": "array", "items": {"type": "string", "examples": ["a"]}},
+                "kind": {"oneOf": [{"type": "integer", "example": 1}]}
+            }
+        });
+
+        prune_schema_examples(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "properties": {
+                    "example": {"type": "string"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "kind": {"oneOf": [{"type": "integer"}]}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_merges_single_level_all_of_compositions() {
+        let schemas = json!({
+            "Pet": {
+                "type": "object",
+                "required": ["name"],
+                "properties": {"name": {"type": "string"}, "age": {"type": "integer"}}
+            },
+            "Animal": {"oneOf": [{"type": "object"}]}
+        });
+        let schemas = schemas.as_object().unwrap();
+        let mut dog = json!({
+            "description": "A dog",
+            "allOf": [
+                {"$ref": "#/components/schemas/Pet"},
+                {
+                    "required": ["breed"],
+                    "properties": {"breed": {"type": "string"}, "age": {"type": "number"}}
+                }
+            ]
+        });
+        let mut cat = json!({"allOf": [{"$ref": "#/components/schemas/Animal"}]});
+
+        assert_eq!(merge_all_of(&mut dog, schemas), vec!["age"]);
+        assert_eq!(merge_all_of(&mut cat, schemas), Vec::<String>::new());
+
+        assert_eq!(
+            dog,
+            json!({
+                "description": "A dog",
+                "type": "object",
+                "required": ["name", "breed"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "age": {"type": "number"},
+                    "breed": {"type": "string"}
+                }
+            })
+        );
+        assert_eq!(
+            cat,
+            json!({"allOf": [{"$ref": "#/components/schemas/Animal"}]})
+        );
+    }
+
+    #[test]
+    fn it_unwraps_single_inline_all_of_subschemas() {
+        let mut schema = json!({
+            "description": "A pet",
+            "allOf": [{"allOf": [{"type": "object", "properties": {
+                "owner": {"allOf": [{"$ref": "#/components/schemas/Owner"}]},
+                "tags": {"items": {"allOf": [{"type": "string"}]}},
+                "kind": {"description": "Kind", "allOf": [{"description": "Other", "type": "string"}]},
+                "size": {"allOf": [{"type": "integer"}, {"minimum": 0}]}
+            }}]}],
+            "nullable": true
+        });
+
+        unwrap_single_all_of(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({
+                "description": "A pet",
+                "type": "object",
+                "properties": {
+                    "owner": {"allOf": [{"$ref": "#/components/schemas/Owner"}]},
+                    "tags": {"items": {"type": "string"}},
+                    "kind": {"description": "Kind", "allOf": [{"description": "Other", "type": "string"}]},
+                    "size": {"allOf": [{"type": "integer"}, {"minimum": 0}]}
+                },
+                "nullable": true
+            })
+        );
+        assert_eq!(
+            schema.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["description", "type", "properties", "nullable"]
+        );
+    }
+
+    #[test]
+    fn it_removes_read_only_and_write_only_properties() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name", "password"],
+            "properties": {
+                "id": {"type": "integer", "readOnly": true},
+                "name": {"type": "string"},
+                "password": {"type": "string", "writeOnly": true},
+                "owner": {"type": "object", "required": ["id"], "properties": {
+                    "id": {"type": "integer", "readOnly": true}
+                }}
+            }
+        });
+
+        let mut request = schema.clone();
+        remove_read_only_properties(&mut request);
+        let mut response = schema.clone();
+        remove_write_only_properties(&mut response);
+
+        assert_eq!(
+            request,
+            json!({
+                "type": "object",
+                "required": ["name", "password"],
+                "properties": {
+                    "name": {"type": "string"},
+                    "password": {"type": "string", "writeOnly": true},
+                    "owner": {"type": "object", "properties": {}}
+                }
+            })
+        );
+        assert_eq!(response["required"], json!(["id", "name"]));
+        assert_eq!(
+            response["properties"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            vec!["id", "name", "owner"]
+        );
+    }
+
+    #[test]
+    fn it_disallows_additional_properties_outside_compositions() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string"},
+                "meta": {"type": "object", "additionalProperties": true},
+                "tags": {"type": "array", "items": {"properties": {"id": {"type": "string"}}}},
+                "pet": {"allOf": [
+                    {"$ref": "#/components/schemas/Pet"},
+                    {"type": "object", "properties": {"owner": {"type": "object"}}}
+                ]}
+            }
+        });
+
+        disallow_additional_properties(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "name": {"type": "string"},
+                    "meta": {"type": "object", "additionalProperties": true},
+                    "tags": {"type": "array", "items": {
+                        "additionalProperties": false,
+                        "properties": {"id": {"type": "string"}}
+                    }},
+                    "pet": {"allOf": [
+                        {"$ref": "#/components/schemas/Pet"},
+                        {"type": "object", "properties": {"owner": {"type": "object", "additionalProperties": false}}}
+                    ]}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_converts_schemas_to_json_schema_2020_12() {
+        let mut schema = json!({
+            "type": "object",
+            "example": {"age": 3},
+            "properties": {
+                "name": {"type": "string", "nullable": true, "example": "Rex", "examples": ["Fido"]},
+                "owner": {"allOf": [{"$ref": "#/components/schemas/Owner"}], "nullable": true},
+                "age": {"type": "integer", "minimum": 0, "exclusiveMinimum": true, "exclusiveMaximum": false},
+                "tags": {"type": "array", "nullable": false, "items": {"type": "string", "nullable": true}},
+                "status": {"type": "string", "enum": ["available", "sold"], "nullable": true},
+                "kind": {"const": "pet", "nullable": true}
+            }
+        });
+
+        convert_to_json_schema_2020_12(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({
+                "type": "object",
+                "examples": [{"age": 3}],
+                "properties": {
+                    "name": {"type": ["string", "null"], "examples": ["Rex", "Fido"]},
+                    "owner": {"allOf": [{"$ref": "#/components/schemas/Owner"}]},
+                    "age": {"type": "integer", "exclusiveMinimum": 0},
+                    "tags": {"type": "array", "items": {"type": ["string", "null"]}},
+                    "status": {"type": ["string", "null"], "enum": ["available", "sold", null]},
+                    "kind": {"enum": ["pet", null]}
+                }
+            })
+        );
+    }
+}
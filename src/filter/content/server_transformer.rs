@@ -0,0 +1,308 @@
+use crate::error::FilterError;
+use crate::filter::content::link_transformer;
+use indexmap::IndexMap;
+use openapiv3::{OpenAPI, ReferenceOr, Server};
+use std::collections::HashMap;
+use wildmatch::WildMatch;
+
+/// Calls the function with every list of servers of the document.
+///
+/// Servers are declared at the root of the document, on path items and on operations. Servers of path items
+/// referenced from the components are not visited.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+/// * `visit` - A function that is called with every list of servers found.
+pub fn for_each_server_list(openapi: &mut OpenAPI, mut visit: impl FnMut(&mut Vec<Server>)) {
+    visit(&mut openapi.servers);
+    for path in openapi.paths.paths.values_mut() {
+        if let ReferenceOr::Item(path) = path {
+            visit(&mut path.servers);
+            [
+                &mut path.get,
+                &mut path.put,
+                &mut path.post,
+                &mut path.delete,
+                &mut path.options,
+                &mut path.head,
+                &mut path.patch,
+                &mut path.trace,
+            ]
+            .into_iter()
+            .flatten()
+            .for_each(|operation| visit(&mut operation.servers));
+        }
+    }
+}
+
+/// Calls the function with every server of the document.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+/// * `visit` - A function that is called with every server found, see `for_each_server_list`.
+pub fn for_each_server(openapi: &mut OpenAPI, visit: fn(&mut Server)) {
+    for_each_server_list(openapi, |servers| servers.iter_mut().for_each(visit));
+}
+
+/// Keeps only the servers whose description matches any of the wildcard patterns.
+///
+/// Servers without a description are removed.
+///
+/// # Arguments
+///
+/// * `servers` - A mutable reference to a list of servers.
+/// * `patterns` - Wildcard patterns matched against the whole description, e.g. `prod*` created with `WildMatch::new_case_insensitive`.
+pub fn retain_servers_by_description(servers: &mut Vec<Server>, patterns: &[WildMatch]) {
+    servers.retain(|server| {
+        server
+            .description
+            .as_ref()
+            .is_some_and(|description| patterns.iter().any(|pattern| pattern.matches(description)))
+    });
+}
+
+/// Removes the variables of the server that are not referenced by a `{name}` placeholder in its URL template.
+///
+/// # Arguments
+///
+/// * `server` - A mutable reference to the server.
+pub fn prune_unused_variables(server: &mut Server) {
+    if let Some(variables) = server.variables.as_mut() {
+        variables.retain(|name, _| server.url.contains(&format!("{{{}}}", name)));
+        if variables.is_empty() {
+            server.variables = None;
+        }
+    }
+}
+
+/// Makes a relative server URL absolute by prefixing it with the base URL, e.g. `/api` and `https://prod.example.com`
+/// become `https://prod.example.com/api`.
+///
+/// URLs with a scheme, including templated ones such as `{scheme}://api.example.com`, and protocol-relative URLs are
+/// left as they are.
+///
+/// # Arguments
+///
+/// * `server` - A mutable reference to the server.
+/// * `base` - The absolute URL the relative URL is resolved against.
+pub fn resolve_relative_url(server: &mut Server, base: &str) {
+    if server.url.contains("://") || server.url.starts_with("//") {
+        return;
+    }
+    let base = base.trim_end_matches('/');
+    server.url = match server.url.trim_start_matches("./") {
+        "" | "/" | "." => base.to_string(),
+        path if path.starts_with('/') => format!("{}{}", base, path),
+        path => format!("{}/{}", base, path),
+    };
+}
+
+/// Moves the base path of the server URLs to the start of every path key, e.g. `https://api.example.com/v2` and `/pets`
+/// become `https://api.example.com` and `/v2/pets`.
+///
+/// All the servers of the document, including the servers of path items and operations, need to have the same base path.
+/// A trailing slash of the base path is ignored. The document is left as it is when the servers have no base path.
+/// Links whose `operationRef` points to an operation of a path are rewritten to the new path key.
+///
+/// # Arguments
+///
+/// * `openapi` - A mutable reference to the document.
+///
+/// # Returns
+///
+/// * `Result<(), FilterError>` - An error when the servers have different base paths or a base path uses server variables.
+pub fn embed_base_path(openapi: &mut OpenAPI) -> Result<(), FilterError> {
+    let mut base_paths: IndexMap<String, Vec<String>> = IndexMap::new();
+    for_each_server_list(openapi, |servers| {
+        for server in servers.iter() {
+            base_paths
+                .entry(split_base_path(&server.url).1.to_string())
+                .or_default()
+                .push(server.url.clone());
+        }
+    });
+    if base_paths.len() > 1 {
+        let servers: Vec<String> = base_paths
+            .iter()
+            .map(|(base_path, urls)| format!("'{}' in {}", base_path, urls.join(", ")))
+            .collect();
+        return Err(FilterError::BasePath(format!(
+            "the servers have different base paths {}",
+            servers.join(", ")
+        )));
+    }
+    let Some(base_path) = base_paths
+        .into_keys()
+        .next()
+        .filter(|base_path| !base_path.is_empty())
+    else {
+        return Ok(());
+    };
+    if base_path.contains('{') {
+        return Err(FilterError::BasePath(format!(
+            "base path '{}' uses server variables",
+            base_path
+        )));
+    }
+    for_each_server(openapi, |server| {
+        server.url = match split_base_path(&server.url).0 {
+            // A relative URL without its base path points to the root of the host
+            "" => String::from("/"),
+            origin => origin.to_string(),
+        };
+    });
+    let renames: HashMap<String, String> = openapi
+        .paths
+        .paths
+        .keys()
+        .map(|path| match path.as_str() {
+            "/" => (path.clone(), base_path.clone()),
+            _ => (path.clone(), format!("{}{}", base_path, path)),
+        })
+        .collect();
+    openapi.paths.paths = std::mem::take(&mut openapi.paths.paths)
+        .into_iter()
+        .map(|(path, item)| (renames[&path].clone(), item))
+        .collect();
+    link_transformer::rename_operation_refs(openapi, &renames);
+    Ok(())
+}
+
+/// Splits a server URL into the part before its path and its path without a trailing slash
+///
+/// Relative URLs such as `/v2` are all path, and URLs without a path have an empty one.
+fn split_base_path(url: &str) -> (&str, &str) {
+    let path_start = match url.find("://") {
+        Some(scheme_end) => url[scheme_end + 3..]
+            .find('/')
+            .map_or(url.len(), |index| scheme_end + 3 + index),
+        None => url.find('/').unwrap_or(url.len()),
+    };
+    let (origin, path) = url.split_at(path_start);
+    (origin, path.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_servers_with_matching_descriptions() {
+        let mut servers: Vec<Server> = serde_json::from_value(serde_json::json!([
+            {"url": "https://api.example.com", "description": "Production"},
+            {"url": "https://staging.example.com", "description": "Staging"},
+            {"url": "https://prod-eu.example.com", "description": "Production EU"},
+            {"url": "https://localhost"}
+        ]))
+        .unwrap();
+
+        retain_servers_by_description(&mut servers, &[WildMatch::new("Production*")]);
+
+        let urls: Vec<&str> = servers.iter().map(|server| server.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://api.example.com", "https://prod-eu.example.com"]
+        );
+    }
+
+    #[test]
+    fn it_prunes_variables_missing_from_url_template() {
+        let mut server: Server = serde_json::from_value(serde_json::json!({
+            "url": "https://{region}.api.example.com",
+            "variables": {
+                "region": {"default": "eu", "enum": ["eu", "us"]},
+                "version": {"default": "v1"}
+            }
+        }))
+        .unwrap();
+
+        prune_unused_variables(&mut server);
+
+        let variables = server.variables.unwrap();
+        assert_eq!(variables.keys().collect::<Vec<_>>(), vec!["region"]);
+        assert_eq!(variables["region"].enumeration, vec!["eu", "us"]);
+    }
+
+    #[test]
+    fn it_resolves_relative_urls_against_the_base() {
+        let resolve = |url: &str| {
+            let mut server: Server =
+                serde_json::from_value(serde_json::json!({ "url": url })).unwrap();
+            resolve_relative_url(&mut server, "https://prod.example.com/");
+            server.url
+        };
+
+        assert_eq!(resolve("/api"), "https://prod.example.com/api");
+        assert_eq!(resolve("api/v2"), "https://prod.example.com/api/v2");
+        assert_eq!(resolve("/"), "https://prod.example.com");
+        assert_eq!(
+            resolve("https://api.example.com"),
+            "https://api.example.com"
+        );
+        assert_eq!(
+            resolve("{scheme}://api.example.com"),
+            "{scheme}://api.example.com"
+        );
+        assert_eq!(resolve("//cdn.example.com"), "//cdn.example.com");
+    }
+
+    #[test]
+    fn it_embeds_the_base_path_of_the_servers_in_the_paths() {
+        let mut openapi: OpenAPI = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Base path", "version": "1.0.0"},
+            "servers": [{"url": "https://api.example.com/v2"}, {"url": "https://staging.example.com/v2/"}],
+            "paths": {
+                "/pets": {"get": {"responses": {}}, "servers": [{"url": "/v2"}]},
+                "/": {"get": {"responses": {"200": {
+                    "description": "Index",
+                    "links": {"Pets": {"operationRef": "#/paths/~1pets/get"}}
+                }}}}
+            }
+        }))
+        .unwrap();
+
+        embed_base_path(&mut openapi).unwrap();
+
+        let urls: Vec<&str> = openapi
+            .servers
+            .iter()
+            .map(|server| server.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec!["https://api.example.com", "https://staging.example.com"]
+        );
+        assert_eq!(
+            openapi.paths.paths.keys().collect::<Vec<_>>(),
+            vec!["/v2/pets", "/v2"]
+        );
+        assert_eq!(
+            openapi.paths.paths["/v2/pets"].as_item().unwrap().servers[0].url,
+            "/"
+        );
+        let document = serde_json::to_value(&openapi).unwrap();
+        assert_eq!(
+            document["paths"]["/v2"]["get"]["responses"]["200"]["links"]["Pets"]["operationRef"],
+            "#/paths/~1v2~1pets/get"
+        );
+    }
+
+    #[test]
+    fn it_fails_to_embed_different_base_paths() {
+        let mut openapi: OpenAPI = serde_json::from_value(serde_json::json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Base paths", "version": "1.0.0"},
+            "servers": [{"url": "https://api.example.com/v2"}, {"url": "https://api.example.com/v1"}],
+            "paths": {}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            embed_base_path(&mut openapi).unwrap_err().to_string(),
+            "Cannot embed the base path of the servers: the servers have different base paths '/v2' in https://api.example.com/v2, '/v1' in https://api.example.com/v1"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Name of the Redoc vendor extension that groups tags together
+const TAG_GROUPS_EXTENSION: &str = "x-tagGroups";
+
+/// Resolves tag group names into the tag names they contain.
+///
+/// This function reads the top-level `x-tagGroups` extension of the API document and looks up each of the
+/// requested group names from it. The tags of all the found groups are returned in the order they are listed in the document.
+///
+/// # Arguments
+///
+/// * `extensions` - A reference to the top-level extensions of the openapi document.
+/// * `group_names` - A slice of tag group names to resolve.
+///
+/// # Returns
+///
+/// * `Result<Vec<String>, Box<dyn std::error::Error>>` - A Result containing the tags of the groups, or an error if some of the groups do not exist.
+pub fn resolve_tag_groups(
+    extensions: &IndexMap<String, Value>,
+    group_names: &[String],
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let groups = extensions
+        .get(TAG_GROUPS_EXTENSION)
+        .and_then(|groups| groups.as_array())
+        .map_or(&[][..], |groups| groups.as_slice());
+
+    let mut tags: Vec<String> = Vec::new();
+    for group_name in group_names {
+        let group = groups
+            .iter()
+            .find(|group| group.get("name").and_then(Value::as_str) == Some(group_name))
+            .ok_or_else(|| {
+                format!(
+                    "Tag group '{}' was not found from {}",
+                    group_name, TAG_GROUPS_EXTENSION
+                )
+            })?;
+        group
+            .get("tags")
+            .and_then(|group_tags| group_tags.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.as_str())
+            .for_each(|tag| tags.push(tag.to_string()));
+    }
+    Ok(tags)
+}
+
+/// Removes the tags that were filtered out from the groups of the `x-tagGroups` extension.
+///
+/// Groups left without tags are removed as well, and the extension itself when no group is left.
+///
+/// # Arguments
+///
+/// * `extensions` - A mutable reference to the top-level extensions of the filtered document.
+/// * `tags` - The names of the tags kept in the filtered document.
+pub fn retain_tag_groups(extensions: &mut IndexMap<String, Value>, tags: &HashSet<String>) {
+    let Some(Value::Array(groups)) = extensions.get_mut(TAG_GROUPS_EXTENSION) else {
+        return;
+    };
+    for group in groups.iter_mut() {
+        if let Some(Value::Array(group_tags)) = group.get_mut("tags") {
+            group_tags.retain(|tag| tag.as_str().is_some_and(|tag| tags.contains(tag)));
+        }
+    }
+    groups.retain(|group| {
+        group
+            .get("tags")
+            .and_then(Value::as_array)
+            .is_some_and(|group_tags| !group_tags.is_empty())
+    });
+    if groups.is_empty() {
+        extensions.shift_remove(TAG_GROUPS_EXTENSION);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn extensions() -> IndexMap<String, Value> {
+        let mut extensions = IndexMap::new();
+        extensions.insert(
+            String::from("x-tagGroups"),
+            json!([
+                {"name": "users", "tags": ["user", "collection"]},
+                {"name": "items", "tags": ["item"]}
+            ]),
+        );
+        extensions
+    }
+
+    #[test]
+    fn it_resolves_tags_of_the_groups() {
+        let tags = resolve_tag_groups(
+            &extensions(),
+            &[String::from("items"), String::from("users")],
+        )
+        .unwrap();
+
+        assert_eq!(tags, vec!["item", "user", "collection"]);
+    }
+
+    #[test]
+    fn it_fails_when_group_does_not_exist() {
+        let result = resolve_tag_groups(&extensions(), &[String::from("payments")]);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Tag group 'payments' was not found from x-tagGroups"
+        );
+    }
+
+    #[test]
+    fn it_removes_filtered_tags_and_empty_groups() {
+        let mut extensions = extensions();
+
+        retain_tag_groups(&mut extensions, &HashSet::from([String::from("user")]));
+
+        assert_eq!(
+            extensions[TAG_GROUPS_EXTENSION],
+            json!([{"name": "users", "tags": ["user"]}])
+        );
+
+        retain_tag_groups(&mut extensions, &HashSet::new());
+
+        assert!(!extensions.contains_key(TAG_GROUPS_EXTENSION));
+    }
+}
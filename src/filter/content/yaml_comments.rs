@@ -0,0 +1,255 @@
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Value as YamlValue};
+
+/// Extension key the comments of a path item or an operation are kept under while the document is filtered
+///
+/// `serde_yaml` discards comments, so the comments written above the path and operation keys of the input are read from
+/// the raw document, carried through filtering under this key and written back above the same keys of the output.
+pub const COMMENTS_EXTENSION: &str = "x-openapiv3-filter-comments";
+
+/// HTTP methods under which a path item holds its operations
+const PATH_ITEM_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Comment lines by path and, for operations, lowercase method
+pub type Comments = IndexMap<(String, Option<String>), Vec<String>>;
+
+/// Key line of the block style `paths` mapping
+struct PathsEntry {
+    /// Index of the line of the key
+    line: usize,
+    /// Indentation of the key
+    indent: usize,
+    /// The path the key belongs to
+    path: String,
+    /// The method of an operation key, None for a path key
+    method: Option<String>,
+}
+
+/// Moves the comments written above the path and operation keys of the raw document under extension keys of the parsed document.
+///
+/// Only full-line comments directly above a key are kept, and only in the top-level `paths` mapping written in block style.
+///
+/// # Arguments
+///
+/// * `contents` - The raw contents of the document
+/// * `document` - A mutable reference to the YAML value of the whole document
+pub fn stash_comments(contents: &str, document: &mut YamlValue) {
+    let lines: Vec<&str> = contents.lines().collect();
+    let Some(paths) = document
+        .get_mut("paths")
+        .and_then(|paths| paths.as_mapping_mut())
+    else {
+        return;
+    };
+    for entry in paths_entries(&lines) {
+        let comments: Vec<YamlValue> = lines[..entry.line]
+            .iter()
+            .rev()
+            .take_while(|line| line.trim_start().starts_with('#'))
+            .map(|line| YamlValue::from(line.trim()))
+            .collect::<Vec<YamlValue>>()
+            .into_iter()
+            .rev()
+            .collect();
+        if comments.is_empty() {
+            continue;
+        }
+        let target = paths
+            .get_mut(entry.path.as_str())
+            .and_then(|path_item| match &entry.method {
+                Some(method) => path_item.get_mut(method.as_str()),
+                None => Some(path_item),
+            });
+        if let Some(target) = target.and_then(YamlValue::as_mapping_mut)
+            && !target.contains_key("$ref")
+        {
+            target.insert(
+                YamlValue::from(COMMENTS_EXTENSION),
+                YamlValue::Sequence(comments),
+            );
+        }
+    }
+}
+
+/// Removes the comments stashed by `stash_comments` from the path items and operations of the document.
+///
+/// # Arguments
+///
+/// * `document` - A mutable reference to the YAML value of the whole document
+///
+/// # Returns
+///
+/// * `Comments` - The removed comments by path and method
+pub fn take_comments(document: &mut YamlValue) -> Comments {
+    let mut comments = Comments::new();
+    let Some(paths) = document
+        .get_mut("paths")
+        .and_then(|paths| paths.as_mapping_mut())
+    else {
+        return comments;
+    };
+    for (path, path_item) in paths.iter_mut() {
+        let (Some(path), Some(path_item)) = (path.as_str(), path_item.as_mapping_mut()) else {
+            continue;
+        };
+        if let Some(lines) = take_comment_lines(path_item) {
+            comments.insert((path.to_string(), None), lines);
+        }
+        for method in PATH_ITEM_METHODS {
+            if let Some(lines) = path_item
+                .get_mut(method)
+                .and_then(YamlValue::as_mapping_mut)
+                .and_then(take_comment_lines)
+            {
+                comments.insert((path.to_string(), Some(method.to_string())), lines);
+            }
+        }
+    }
+    comments
+}
+
+/// Writes the comments above the path and operation keys of a YAML document written in block style.
+///
+/// # Arguments
+///
+/// * `yaml` - The serialized YAML document
+/// * `comments` - The comments by path and method, see `take_comments`
+///
+/// # Returns
+///
+/// * `String` - The document with the comment lines inserted, indented like the keys they describe
+pub fn write_comments(yaml: &str, comments: &Comments) -> String {
+    if comments.is_empty() {
+        return yaml.to_string();
+    }
+    let lines: Vec<&str> = yaml.lines().collect();
+    let mut entries = paths_entries(&lines).into_iter().peekable();
+    let mut result = String::with_capacity(yaml.len());
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(entry) = entries.next_if(|entry| entry.line == index)
+            && let Some(comment_lines) = comments.get(&(entry.path, entry.method))
+        {
+            for comment in comment_lines {
+                result.push_str(&" ".repeat(entry.indent));
+                result.push_str(comment);
+                result.push('\n');
+            }
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    if !yaml.ends_with('\n') {
+        result.pop();
+    }
+    result
+}
+
+/// Removes the comment extension of a path item or an operation, returning its lines
+fn take_comment_lines(mapping: &mut Mapping) -> Option<Vec<String>> {
+    let lines = mapping.remove(COMMENTS_EXTENSION)?;
+    Some(
+        lines
+            .as_sequence()?
+            .iter()
+            .filter_map(|line| line.as_str().map(str::to_string))
+            .collect(),
+    )
+}
+
+/// Finds the path keys and the operation keys of the top-level block style `paths` mapping
+fn paths_entries(lines: &[&str]) -> Vec<PathsEntry> {
+    let is_content = |line: &str| !line.trim().is_empty() && !line.trim_start().starts_with('#');
+    let indent = |line: &str| line.len() - line.trim_start_matches(' ').len();
+    let Some(paths_line) = lines.iter().position(|line| {
+        let line = line.trim_end();
+        line == "paths:"
+            || line
+                .strip_prefix("paths:")
+                .is_some_and(|rest| rest.trim_start().starts_with('#'))
+    }) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    let mut path_indent = None;
+    let mut method_indent = None;
+    let mut current_path: Option<String> = None;
+    for (index, line) in lines.iter().enumerate().skip(paths_line + 1) {
+        if !is_content(line) {
+            continue;
+        }
+        let line_indent = indent(line);
+        if line_indent == 0 {
+            break;
+        }
+        let path_indent = *path_indent.get_or_insert(line_indent);
+        if line_indent == path_indent {
+            current_path = mapping_key(line.trim());
+            method_indent = None;
+            if let Some(path) = &current_path {
+                entries.push(PathsEntry {
+                    line: index,
+                    indent: line_indent,
+                    path: path.clone(),
+                    method: None,
+                });
+            }
+            continue;
+        }
+        let Some(path) = &current_path else {
+            continue;
+        };
+        if *method_indent.get_or_insert(line_indent) != line_indent {
+            continue;
+        }
+        if let Some(method) = mapping_key(line.trim())
+            && PATH_ITEM_METHODS.contains(&method.as_str())
+        {
+            entries.push(PathsEntry {
+                line: index,
+                indent: line_indent,
+                path: path.clone(),
+                method: Some(method),
+            });
+        }
+    }
+    entries
+}
+
+/// Returns the key of a block mapping entry line, without quotes
+pub(crate) fn mapping_key(line: &str) -> Option<String> {
+    for quote in ['\'', '"'] {
+        if let Some(quoted) = line.strip_prefix(quote) {
+            return quoted.find(quote).map(|end| quoted[..end].to_string());
+        }
+    }
+    line.find(": ")
+        .or_else(|| line.strip_suffix(':').map(str::len))
+        .map(|end| line[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_moves_comments_through_the_document() {
+        let contents = "openapi: 3.0.3\n# Not a path comment\npaths:\n  # Pets\n  # of the store\n  /pets:\n    # Lists pets\n    get:\n      responses: {}\n    parameters: []\n  /users:\n    post: {}\n";
+        let mut document: YamlValue = serde_yaml::from_str(contents).unwrap();
+
+        stash_comments(contents, &mut document);
+        assert_eq!(
+            document["paths"]["/pets"][COMMENTS_EXTENSION],
+            serde_yaml::from_str::<YamlValue>("['# Pets', '# of the store']").unwrap()
+        );
+        let comments = take_comments(&mut document);
+        assert!(document["paths"]["/pets"].get(COMMENTS_EXTENSION).is_none());
+
+        let yaml = serde_yaml::to_string(&document).unwrap();
+        assert_eq!(
+            write_comments(&yaml, &comments),
+            "openapi: 3.0.3\npaths:\n  # Pets\n  # of the store\n  /pets:\n    # Lists pets\n    get:\n      responses: {}\n    parameters: []\n  /users:\n    post: {}\n"
+        );
+    }
+}
@@ -1,2 +1,3 @@
 pub mod content;
 pub mod openapi;
+pub mod report;
@@ -1,38 +1,188 @@
 use indexmap::map::IndexMap;
 use openapiv3::{
-    Components, OpenAPI, Operation, PathItem, Paths, ReferenceOr, SecurityScheme, Tag,
+    Components, OpenAPI, Operation, Parameter, PathItem, Paths, ReferenceOr, Schema,
+    SecurityRequirement, SecurityScheme, Tag,
 };
-use serde_json::json;
+use regex::Regex;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use wildmatch::WildMatch;
 
 // Define the filtering trait
+use crate::diagnostics;
+use crate::error::FilterError;
+use crate::filter::content::component_renamer;
+use crate::filter::content::extension_renamer;
 use crate::filter::content::json_path_filter;
+use crate::filter::content::jsonpath_selector::JsonPathSelector;
+use crate::filter::content::key_normalizer;
+use crate::filter::content::link_transformer;
+use crate::filter::content::name_matcher::NameMatcher;
+use crate::filter::content::openapi31_fields::{self, resolve_path_item};
+use crate::filter::content::operation_transformer::{self, parameter_location, resolve_parameter};
 use crate::filter::content::reference_collector;
 use crate::filter::content::reference_collector::{
-    collect_operation_securities, collect_operation_tags, collect_path_refs,
+    DEFAULT_MAX_RECURSION_DEPTH, RecursionLimitExceeded, collect_operation_securities,
+    collect_operation_tags, collect_path_refs, decode_reference, effective_security,
+    escape_segment, pointer_segments,
 };
 use crate::filter::content::reference_processor;
+use crate::filter::content::schema_inliner;
+use crate::filter::content::schema_transformer;
+use crate::filter::content::server_transformer;
+use crate::filter::content::tag_group_resolver;
+use crate::filter::content::yaml_comments;
+
+///HTTP methods whose request bodies have no defined semantics, OpenAPI only supports request bodies for the other methods
+const METHODS_WITHOUT_REQUEST_BODY: [&str; 4] = ["get", "head", "delete", "trace"];
 
 ///Type that is used for filtering openapi paths
 type PathFilter<'d> = Box<dyn Fn(&(&String, &ReferenceOr<PathItem>)) -> bool + 'd>;
 ///Type that is used for filtering openapi operations
 type OperationFilter<'d> = Box<dyn Fn(&(&str, &Operation)) -> bool + 'd>;
 
+///How the operation tags are matched against the tags given as filtering parameters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TagMatch {
+    /// Operations having at least one of the tags are kept
+    #[default]
+    Any,
+    /// Operations having every one of the tags are kept
+    All,
+}
+
+///Which properties are removed from the kept schemas to describe only one side of the API
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaProfile {
+    /// Properties marked `readOnly` are removed, leaving the request models
+    Request,
+    /// Properties marked `writeOnly` are removed, leaving the response models
+    Response,
+}
+
 ///Filtering parameters for the filtering trait
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct FilteringParameters {
     ///when provided only outputs paths that match the parameters
     pub paths: Option<Vec<String>>,
+    ///when true writes the path keys matched by each of the `paths` patterns to stderr
+    pub preview_path_matches: bool,
     ///when provided only outputs tags that match the parameters
     pub tags: Option<Vec<String>>,
+    ///when provided removes operations that have any of the tags, operations without tags are kept
+    pub exclude_tags: Option<Vec<String>>,
+    ///whether operations need any or all of the provided tags
+    pub tag_match: TagMatch,
     ///when provided only outputs http methods that match the parameters
     pub methods: Option<Vec<String>>,
+    ///when true fails if a path given without wildcards has none of the methods
+    pub strict_method_path: bool,
+    ///when provided only outputs operations that declare a request body (true) or that do not declare one (false)
+    pub has_request_body: Option<bool>,
     ///when provided only outputs endpoints that use provided security parameters
     pub security: Option<Vec<String>>,
+    ///when provided only outputs endpoints that use security schemes matching the provided wildcard patterns
+    pub security_globs: Option<Vec<String>>,
+    ///when provided only outputs operations whose security requirements list any of the provided scopes
+    pub scopes: Option<Vec<String>>,
+    ///when provided only outputs operations whose security requirements list a scope matching the provided wildcard patterns
+    pub scope_globs: Option<Vec<String>>,
+    ///when provided only outputs operations that declare parameters in the provided locations (query, path, header or cookie)
+    pub parameter_locations: Option<Vec<String>>,
+    ///when provided only outputs operations for which any of the JSONPath expressions yields a non-empty result
+    pub jsonpaths: Option<Vec<String>>,
+    ///when provided only outputs operations whose summary matches any of the wildcard patterns
+    pub summary_globs: Option<Vec<String>>,
+    ///when provided only outputs operations whose serialized JSON matches the regular expression
+    pub grep: Option<String>,
+    ///when provided only outputs operations that reference any of the `#/components/...` pointers directly or through other components
+    pub referenced_components: Option<Vec<String>>,
+    ///when provided always keeps the components whose `#/components/...` pointer matches any of the wildcard patterns, with the components they reference
+    pub retain_components: Option<Vec<String>>,
+    ///when provided only outputs the operations of the exact (path, lowercase method) pairs
+    pub selection: Option<Vec<(String, String)>>,
+    ///when true removes `example` and `examples` keywords from the schemas kept in components
+    pub prune_schema_examples: bool,
+    ///when true flattens single-level `allOf` compositions of the kept schemas into single object schemas
+    pub merge_all_of: bool,
+    ///when true generates an `operationId` from the method and path for the kept operations without one
+    pub ensure_operation_ids: bool,
+    ///when true adds a `default` response to the kept operations without any response
+    pub ensure_default_responses: bool,
+    ///when true replaces the kept schemas that are identical to an earlier kept schema, apart from descriptions, with references to it
+    pub dedupe_schemas: bool,
+    ///when true inlines the kept schemas that are referenced exactly once into their usage site and removes them from components
+    pub inline_single_use_schemas: bool,
+    ///when provided prefixes the names of the kept schemas and rewrites the references to them
+    pub schema_name_prefix: Option<String>,
+    ///pairs of extension keys renamed throughout the filtered document, e.g. `x-enum` to `x-ms-enum`
+    pub extension_renames: Vec<(String, String)>,
+    ///when provided only keeps the responses with the listed status codes, e.g. `200` or `default`, in the kept operations
+    pub keep_responses: Option<Vec<String>>,
+    ///when true kept operations with an empty `responses` object are not warned about
+    pub allow_empty_responses: bool,
+    ///when true reduces the kept operations to their `operationId`, `summary` and an empty `default` response
+    pub stub: bool,
+    ///when true removes the path level parameters that no kept operation uses and that are not in the path template
+    pub drop_unused_path_parameters: bool,
+    ///when true unwraps `allOf` compositions with a single inline subschema in the kept schemas
+    pub simplify_schemas: bool,
+    ///when provided removes the `readOnly` (request) or `writeOnly` (response) properties from the kept schemas
+    pub schema_profile: Option<SchemaProfile>,
+    ///when true adds `additionalProperties: false` to the kept object schemas that do not specify it, except in compositions
+    pub strict_schemas: bool,
+    ///when provided only keeps servers whose description matches the provided wildcard patterns
+    pub server_descriptions: Option<Vec<String>>,
+    ///when provided rewrites relative server URLs to absolute ones by prefixing them with this base URL
+    pub server_base: Option<String>,
+    ///when true moves the base path shared by the server URLs to the start of the path keys
+    pub embed_base_path: bool,
+    ///when true keeps only the first server of the document root, path items and operations
+    pub first_server_only: bool,
+    ///when true removes server variables that are not used in the URL template of their server
+    pub prune_server_variables: bool,
+    ///when true removes the OAuth2 scopes that no kept operation requires from the kept security schemes
+    pub prune_scopes: bool,
+    ///when true removes `requestBody` from the operations of methods without request body semantics (get, head, delete and trace)
+    pub normalize_bodies: bool,
+    ///when true tags the operations without tags by the first segment of their path before filtering
+    pub tag_from_path: bool,
+    ///when true trims whitespace from path keys, tag names and security scheme names before filtering
+    pub normalize_keys: bool,
+    ///when true removes trailing slashes from the path keys and the path patterns before filtering, merging the paths that only differ by them
+    pub normalize_trailing_slash: bool,
+    ///when provided overrides how deep the document may be nested before filtering fails, defaults to `DEFAULT_MAX_RECURSION_DEPTH`
+    pub max_recursion_depth: Option<usize>,
+    ///when provided keeps only a random sample of this many of the matched operations
+    pub sample: Option<usize>,
+    ///seed of the random sample, the same seed always gives the same sample
+    pub seed: u64,
     //pub content_types: Option<&'a Vec<String>>
 }
 
+impl FilteringParameters {
+    /// Returns true when no filter or transformation is requested, so filtering would return the document as it is.
+    ///
+    /// Parameters that only modify other parameters, such as `tag_match` without tags, `seed` without `sample` or
+    /// `strict_method_path` without paths and methods, do not count as requests of their own. Neither does
+    /// `allow_empty_responses`, which only silences a warning.
+    pub fn is_empty(&self) -> bool {
+        *self
+            == FilteringParameters {
+                preview_path_matches: self.preview_path_matches,
+                tag_match: self.tag_match,
+                strict_method_path: self.strict_method_path,
+                allow_empty_responses: self.allow_empty_responses,
+                max_recursion_depth: self.max_recursion_depth,
+                seed: self.seed,
+                ..Default::default()
+            }
+    }
+}
+
 ///Adds filtering capability to OpenAPI
 pub trait OpenAPIFilter {
     /// Filters an OpenAPI document based on provided criteria
@@ -40,7 +190,9 @@ pub trait OpenAPIFilter {
     /// This trait provides a method to filter and extract portions of an OpenAPI document according to specified parameters.
     /// The filtering can be done by paths, tags, HTTP methods, security schemes, and other criteria while maintaining referential integrity
     /// for used components and definitions.
-    fn filter_by_parameters(&self, filters: FilteringParameters) -> Option<Self>
+    ///
+    /// Fails when the document is nested deeper than the maximum recursion depth or a JSONPath expression is invalid.
+    fn filter_by_parameters(&self, filters: FilteringParameters) -> Result<Self, FilterError>
     where
         Self: Sized;
 }
@@ -52,41 +204,198 @@ pub trait OpenAPIFilter {
 /// for used components and definitions.
 impl OpenAPIFilter for OpenAPI {
     ///Returns the partial openapi where non filtered items are removed from the api contents
-    fn filter_by_parameters<'d>(&self, filters: FilteringParameters) -> Option<Self>
+    fn filter_by_parameters<'d>(&self, filters: FilteringParameters) -> Result<Self, FilterError>
     where
         Self: Sized,
     {
-        let path_filters = map_path_name_filters(filters.paths);
+        // Nothing to filter, so the document is returned without collecting and round-tripping its components
+        if filters.is_empty() {
+            return Ok(self.clone());
+        }
+        if filters.normalize_keys {
+            let mut normalized = self.clone();
+            key_normalizer::normalize_keys(&mut normalized);
+            return normalized.filter_by_parameters(FilteringParameters {
+                normalize_keys: false,
+                ..filters
+            });
+        }
+        if filters.normalize_trailing_slash {
+            let mut normalized = self.clone();
+            key_normalizer::strip_trailing_slashes(&mut normalized)?;
+            return normalized.filter_by_parameters(FilteringParameters {
+                paths: filters.paths.map(|paths| {
+                    paths
+                        .iter()
+                        .map(|path| key_normalizer::strip_trailing_slash(path).to_string())
+                        .collect()
+                }),
+                normalize_trailing_slash: false,
+                ..filters
+            });
+        }
+        if filters.tag_from_path {
+            let mut tagged = self.clone();
+            operation_transformer::tag_operations_by_path(&mut tagged);
+            return tagged.filter_by_parameters(FilteringParameters {
+                tag_from_path: false,
+                ..filters
+            });
+        }
+        if let Some(count) = filters.sample {
+            // The operations are matched first, so that the sample is taken from the matching operations only.
+            // Only the matching parameters are passed on, so transformations run and diagnostics are written once.
+            let matched = self.filter_by_parameters(FilteringParameters {
+                paths: filters.paths.clone(),
+                tags: filters.tags.clone(),
+                exclude_tags: filters.exclude_tags.clone(),
+                tag_match: filters.tag_match,
+                methods: filters.methods.clone(),
+                strict_method_path: filters.strict_method_path,
+                has_request_body: filters.has_request_body,
+                security: filters.security.clone(),
+                security_globs: filters.security_globs.clone(),
+                scopes: filters.scopes.clone(),
+                scope_globs: filters.scope_globs.clone(),
+                parameter_locations: filters.parameter_locations.clone(),
+                jsonpaths: filters.jsonpaths.clone(),
+                summary_globs: filters.summary_globs.clone(),
+                grep: filters.grep.clone(),
+                referenced_components: filters.referenced_components.clone(),
+                selection: filters.selection.clone(),
+                allow_empty_responses: true,
+                max_recursion_depth: filters.max_recursion_depth,
+                ..Default::default()
+            })?;
+            let operations: Vec<(String, String)> = matched
+                .paths
+                .iter()
+                .filter_map(|(path_name, path_ref)| {
+                    resolve_path_item(path_ref, matched.components.as_ref())
+                        .map(|path| (path_name, path))
+                })
+                .flat_map(|(path_name, path)| {
+                    path.iter()
+                        .map(|(method, _)| (path_name.clone(), method.to_string()))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            let selection =
+                operation_transformer::sample_operations(&operations, count, filters.seed);
+            return self.filter_by_parameters(FilteringParameters {
+                sample: None,
+                selection: Some(selection),
+                ..filters
+            });
+        }
+        if filters.strict_method_path
+            && let (Some(paths), Some(methods)) = (filters.paths.as_ref(), filters.methods.as_ref())
+        {
+            check_exact_paths_have_methods(self, paths, methods)?;
+        }
+        let max_depth = filters
+            .max_recursion_depth
+            .unwrap_or(DEFAULT_MAX_RECURSION_DEPTH);
+        let security_matcher = NameMatcher::new(filters.security, filters.security_globs);
+        let path_matches = RefCell::new(IndexMap::new());
+        let path_filters = map_path_name_filters(
+            filters.paths,
+            filters.preview_path_matches.then_some(&path_matches),
+        );
         let path_tag_filters = map_path_tags_filters(filters.tags.clone());
-        let path_security_filters = map_path_security_filters(filters.security.clone());
+        let path_exclude_tag_filters = map_path_exclude_tags_filters(filters.exclude_tags.clone());
+        let path_security_filters =
+            map_path_security_filters(security_matcher.clone(), self.security.clone());
+        let path_selection_filters = map_path_selection_filters(filters.selection.as_ref());
 
-        let path_filters: Vec<PathFilter> =
-            vec![path_filters, path_tag_filters, path_security_filters]
-                .into_iter()
-                .flatten()
-                .collect();
+        let path_filters: Vec<PathFilter> = vec![
+            path_filters,
+            path_tag_filters,
+            path_exclude_tag_filters,
+            path_security_filters,
+            path_selection_filters,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
 
         let mut filtered_paths: IndexMap<String, ReferenceOr<PathItem>> = self
             .paths
             .iter()
-            .filter(|x| path_filters.iter().all(|filter| filter(x)))
+            .filter(|(key, path_ref)| {
+                // Path filters inspect the operations, so references to reusable path items are resolved first
+                let resolved = resolve_path_item(path_ref, self.components.as_ref())
+                    .map_or_else(|| (*path_ref).clone(), ReferenceOr::Item);
+                path_filters.iter().all(|filter| filter(&(key, &resolved)))
+            })
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect();
+        if filters.preview_path_matches {
+            report_path_matches(&path_matches.borrow());
+        }
 
-        let operation_tag_filters = map_operation_tags_filters(filters.tags.clone());
+        let operation_tag_filters =
+            map_operation_tags_filters(filters.tags.clone(), filters.tag_match);
+        let operation_exclude_tag_filters =
+            map_operation_exclude_tags_filters(filters.exclude_tags);
         let allowed_tags: HashSet<String> = filters
             .tags
             .map_or_else(HashSet::new, |v| v.into_iter().collect());
         let operation_method_filters = map_operation_method_filters(filters.methods);
-        let operation_security_filters = map_operation_security_filters(filters.security.clone());
-        let allowed_securities: HashSet<String> = filters
-            .security
-            .map_or_else(HashSet::new, |v| v.into_iter().collect());
+        let operation_request_body_filters =
+            map_operation_request_body_filters(filters.has_request_body);
+        let operation_security_filters =
+            map_operation_security_filters(security_matcher.clone(), self.security.clone());
+        let operation_scope_filters = map_operation_scope_filters(
+            NameMatcher::new(filters.scopes, filters.scope_globs),
+            self.security.clone(),
+        );
+        let jsonpath_selector = filters
+            .jsonpaths
+            .as_deref()
+            .map(JsonPathSelector::new)
+            .transpose()?;
+        let operation_jsonpath_filters = map_operation_jsonpath_filters(jsonpath_selector);
+        let grep = filters
+            .grep
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|error| FilterError::InvalidPattern {
+                    kind: "regular expression",
+                    pattern: pattern.to_string(),
+                    reason: error.to_string(),
+                })
+            })
+            .transpose()?;
+        let operation_grep_filters = map_operation_grep_filters(grep);
+        let operation_summary_filters = map_operation_summary_filters(filters.summary_globs);
+        let component_references = filters
+            .referenced_components
+            .as_ref()
+            .map(|_| component_reference_map(&components_value(self), max_depth))
+            .transpose()?;
+        let operation_reference_filters = map_operation_reference_filters(
+            filters.referenced_components,
+            component_references,
+            max_depth,
+        );
+        let is_allowed_security = |name: &str| {
+            security_matcher
+                .as_ref()
+                .is_none_or(|security_matcher| security_matcher.matches(name))
+        };
 
         let operation_filters: Vec<OperationFilter<'d>> = vec![
             operation_tag_filters,
+            operation_exclude_tag_filters,
             operation_method_filters,
+            operation_request_body_filters,
             operation_security_filters,
+            operation_scope_filters,
+            operation_jsonpath_filters,
+            operation_grep_filters,
+            operation_summary_filters,
+            operation_reference_filters,
         ]
         .into_iter()
         .flatten()
@@ -95,89 +404,344 @@ impl OpenAPIFilter for OpenAPI {
         let mut components: HashSet<String> = HashSet::with_capacity(10);
         let mut tags: HashSet<String> = HashSet::with_capacity(10);
         let mut securities: HashSet<String> = HashSet::with_capacity(10);
-        for (_, path_ref) in filtered_paths.iter_mut() {
-            if let Some(old_path) = path_ref.as_item() {
+        for (path_name, path_ref) in filtered_paths.iter_mut() {
+            if let Some(old_path) = resolve_path_item(path_ref, self.components.as_ref()) {
+                let parameter_location_filters = map_operation_parameter_location_filters(
+                    filters.parameter_locations.as_ref(),
+                    old_path.parameters.clone(),
+                    self.components.as_ref(),
+                );
+                let selection_filters =
+                    map_operation_selection_filters(filters.selection.as_ref(), path_name);
+                // Comments stashed for --preserve-comments are not part of the operations, so they are never matched
+                let mut matched_path = old_path.clone();
+                operation_transformer::remove_extension(
+                    &mut matched_path,
+                    yaml_comments::COMMENTS_EXTENSION,
+                );
+                let matched_methods: HashSet<&str> = matched_path
+                    .iter()
+                    .filter(|operation| {
+                        operation_filters
+                            .iter()
+                            .chain(parameter_location_filters.iter())
+                            .chain(selection_filters.iter())
+                            .all(|filter| filter(operation))
+                    })
+                    .map(|(method, _)| method)
+                    .collect();
                 let filtered_operations: HashMap<&str, &Operation> = old_path
                     .iter()
-                    .filter(|operation| operation_filters.iter().all(|filter| filter(operation)))
+                    .filter(|(method, _)| matched_methods.contains(method))
                     .collect();
-                collect_operation_tags(
-                    filtered_operations.values().collect(),
-                    &mut tags,
-                    &allowed_tags,
-                );
-                collect_operation_securities(
-                    filtered_operations.values().collect(),
-                    &mut securities,
-                    &allowed_securities,
-                );
+                // Stubs and default responses give the kept operations a response
+                if !filters.allow_empty_responses
+                    && !filters.stub
+                    && !filters.ensure_default_responses
+                {
+                    warn_about_empty_responses(path_name, &old_path, &filtered_operations);
+                }
+                // Stubs of reusable path items are inlined, since the reused item would keep its full operations
+                let kept_whole = matches!(path_ref, ReferenceOr::Reference { .. })
+                    && !filtered_operations.is_empty()
+                    && !filters.stub;
+                if kept_whole {
+                    // Reusable path items are kept whole, so the tags and security requirements of all their operations are kept
+                    let operations: Vec<&Operation> =
+                        old_path.iter().map(|(_, operation)| operation).collect();
+                    collect_operation_tags(operations.iter().collect(), &mut tags, &HashSet::new());
+                    collect_operation_securities(
+                        operations.iter().collect(),
+                        self.security.as_ref(),
+                        &mut securities,
+                        &|_| true,
+                    );
+                } else if !filters.stub {
+                    // Stubs have no tags or security requirements left to collect
+                    collect_operation_tags(
+                        filtered_operations.values().collect(),
+                        &mut tags,
+                        &allowed_tags,
+                    );
+                    collect_operation_securities(
+                        filtered_operations.values().collect(),
+                        self.security.as_ref(),
+                        &mut securities,
+                        &is_allowed_security,
+                    );
+                }
+                // Reusable path items are kept whole behind their reference when any of their operations match
+                if let ReferenceOr::Reference { reference } = path_ref
+                    && !filters.stub
+                {
+                    if filtered_operations.is_empty() {
+                        *path_ref = ReferenceOr::Item(PathItem::default());
+                    } else {
+                        components.insert(reference.clone());
+                    }
+                    continue;
+                }
                 // Create new PathItem
-                let new_path = PathItem {
-                    get: filtered_operations
-                        .get("get")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    put: filtered_operations
-                        .get("put")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    post: filtered_operations
-                        .get("post")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    delete: filtered_operations
-                        .get("delete")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    options: filtered_operations
-                        .get("options")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    head: filtered_operations
-                        .get("head")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    patch: filtered_operations
-                        .get("patch")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
-                    trace: filtered_operations
-                        .get("trace")
-                        .map(|op| clone_operation(op, &allowed_tags, &allowed_securities)),
+                let clone_method = |method: &str| {
+                    filtered_operations.get(method).map(|op| {
+                        clone_operation(
+                            method,
+                            op,
+                            &allowed_tags,
+                            security_matcher.as_ref(),
+                            filters.normalize_bodies,
+                        )
+                    })
+                };
+                let mut new_path = PathItem {
+                    get: clone_method("get"),
+                    put: clone_method("put"),
+                    post: clone_method("post"),
+                    delete: clone_method("delete"),
+                    options: clone_method("options"),
+                    head: clone_method("head"),
+                    patch: clone_method("patch"),
+                    trace: clone_method("trace"),
                     ..old_path.clone()
                 };
+                if filters.drop_unused_path_parameters {
+                    retain_used_path_parameters(path_name, &mut new_path, self.components.as_ref());
+                }
+                if let Some(codes) = filters.keep_responses.as_ref() {
+                    operation_transformer::retain_responses(&mut new_path, codes);
+                    // Stubs and default responses replace the removed responses later on
+                    if !filters.allow_empty_responses
+                        && !filters.stub
+                        && !filters.ensure_default_responses
+                    {
+                        warn_about_removed_responses(path_name, &old_path, &new_path);
+                    }
+                }
+                if filters.stub {
+                    operation_transformer::stub_operations(&mut new_path);
+                }
 
                 collect_path_refs(
                     &serde_json::to_value(&new_path).unwrap(),
                     &mut components,
                     None,
-                );
+                    max_depth,
+                )?;
                 //collect_tags(&new_path,&mut tags,&allowed_tags);
 
                 // Assign the new path back to the container
                 *path_ref = ReferenceOr::Item(new_path); // Adjust this line based on your actual container type
             }
         }
-        let mut components_elements = found_refs_to_components(self, &mut components);
+        // OpenAPI 3.1 webhooks are kept as they are, so the components, tags and security schemes they use are kept as well
+        if let Some(webhooks) = self.extensions.get(openapi31_fields::WEBHOOKS_EXTENSION) {
+            collect_path_refs(webhooks, &mut components, None, max_depth)?;
+            let webhook_items: IndexMap<String, ReferenceOr<PathItem>> =
+                serde_json::from_value(webhooks.clone()).unwrap_or_default();
+            let webhook_items: Vec<PathItem> = webhook_items
+                .values()
+                .filter_map(|item| resolve_path_item(item, self.components.as_ref()))
+                .collect();
+            let operations: Vec<&Operation> = webhook_items
+                .iter()
+                .flat_map(|item| item.iter().map(|(_, operation)| operation))
+                .collect();
+            collect_operation_tags(operations.iter().collect(), &mut tags, &HashSet::new());
+            collect_operation_securities(
+                operations.iter().collect(),
+                self.security.as_ref(),
+                &mut securities,
+                &|_| true,
+            );
+        }
+        if let Some(patterns) = filters.retain_components.as_ref() {
+            retain_matching_components(self, patterns, &mut components, &mut securities);
+        }
+        let mut components_elements = found_refs_to_components(self, &mut components, max_depth)?;
         let tags_elements = found_refs_to_tags(self, &tags);
 
-        let paths_with_content: IndexMap<String, ReferenceOr<PathItem>> = filtered_paths
+        let mut paths_with_content: IndexMap<String, ReferenceOr<PathItem>> = filtered_paths
             .into_iter()
-            .filter(|(_, value)| {
-                value.as_item().is_some() && value.as_item().unwrap().iter().count() > 0
-            })
+            .filter(|(_, value)| value.as_item().is_none_or(|path| path.iter().count() > 0))
             .collect();
 
+        if filters.ensure_operation_ids {
+            operation_transformer::ensure_operation_ids(&mut paths_with_content);
+        }
+        if filters.ensure_default_responses {
+            operation_transformer::ensure_default_responses(&mut paths_with_content);
+        }
+
         let default_map = IndexMap::<String, ReferenceOr<SecurityScheme>>::default();
         let security_schemes = self
             .components
             .as_ref()
             .map_or(&default_map, |c| &c.security_schemes);
-        let filtered_securities = filter_securities(&securities, security_schemes);
+        let used_scopes = filters.prune_scopes.then(|| {
+            collect_used_scopes(
+                &paths_with_content,
+                &self.security,
+                self.components.as_ref(),
+            )
+        });
+        let filtered_securities =
+            filter_securities(&securities, security_schemes, used_scopes.as_ref());
         components_elements.security_schemes = filtered_securities;
+        if filters.prune_schema_examples {
+            components_elements.schemas = transform_schemas(
+                &components_elements.schemas,
+                schema_transformer::prune_schema_examples,
+            );
+        }
+        if filters.simplify_schemas {
+            transform_all_schemas(
+                &mut components_elements,
+                &mut paths_with_content,
+                schema_transformer::unwrap_single_all_of,
+            );
+        }
+        if filters.merge_all_of {
+            components_elements.schemas = merge_all_of_schemas(&components_elements.schemas);
+        }
+        if let Some(schema_profile) = filters.schema_profile {
+            components_elements.schemas = transform_schemas(
+                &components_elements.schemas,
+                match schema_profile {
+                    SchemaProfile::Request => schema_transformer::remove_read_only_properties,
+                    SchemaProfile::Response => schema_transformer::remove_write_only_properties,
+                },
+            );
+        }
+        if filters.strict_schemas {
+            // Component schemas extended by compositions are left open, closing them would reject the extending properties
+            let mut members = HashSet::new();
+            schema_transformer::collect_composition_members(
+                &serde_json::to_value(&components_elements)?,
+                &mut members,
+            );
+            schema_transformer::collect_composition_members(
+                &serde_json::to_value(&paths_with_content)?,
+                &mut members,
+            );
+            let schemas = std::mem::take(&mut components_elements.schemas);
+            transform_all_schemas(
+                &mut components_elements,
+                &mut paths_with_content,
+                schema_transformer::disallow_additional_properties,
+            );
+            components_elements.schemas = schemas
+                .into_iter()
+                .map(|(name, schema)| {
+                    let mut schema_value = serde_json::to_value(&schema).unwrap();
+                    if members.contains(&name) {
+                        schema_transformer::disallow_additional_properties_in_member(
+                            &mut schema_value,
+                        );
+                    } else {
+                        schema_transformer::disallow_additional_properties(&mut schema_value);
+                    }
+                    (name, serde_json::from_value(schema_value).unwrap())
+                })
+                .collect();
+        }
 
-        Some(OpenAPI {
+        let mut filtered_api = OpenAPI {
             paths: Paths {
                 paths: paths_with_content,
                 extensions: self.paths.extensions.clone(),
             },
-            components: Some(components_elements),
+            // A document without components is not given empty ones
+            components: (self.components.is_some() || components_elements != Components::default())
+                .then_some(components_elements),
             tags: tags_elements,
+            // The document level requirements are trimmed like the requirements of the operations
+            security: retain_matching_requirements(
+                self.security.as_ref(),
+                security_matcher.as_ref(),
+            ),
             ..self.clone()
-        })
+        };
+        tag_group_resolver::retain_tag_groups(&mut filtered_api.extensions, &tags);
+        if filters.stub {
+            // Stubs inherit the document level requirements, which are kept only when their schemes are kept, e.g. for webhooks
+            let kept_schemes = filtered_api
+                .components
+                .as_ref()
+                .map(|components| &components.security_schemes);
+            filtered_api.security = filtered_api
+                .security
+                .take()
+                .map(|security| {
+                    security
+                        .into_iter()
+                        .filter(|requirement| {
+                            requirement.keys().all(|name| {
+                                kept_schemes.is_some_and(|schemes| schemes.contains_key(name))
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .filter(|security| !security.is_empty());
+        }
+        link_transformer::remove_dangling_links(self, &mut filtered_api);
+        if let Some(server_descriptions) = filters.server_descriptions.as_ref() {
+            // Descriptions are free text, so `prod*` also matches `Production`
+            let patterns: Vec<WildMatch> = server_descriptions
+                .iter()
+                .map(|pattern| WildMatch::new_case_insensitive(pattern))
+                .collect();
+            server_transformer::for_each_server_list(&mut filtered_api, |servers| {
+                server_transformer::retain_servers_by_description(servers, &patterns)
+            });
+        }
+        if filters.first_server_only {
+            server_transformer::for_each_server_list(&mut filtered_api, |servers| {
+                servers.truncate(1)
+            });
+        }
+        if let Some(server_base) = filters.server_base.as_deref() {
+            server_transformer::for_each_server_list(&mut filtered_api, |servers| {
+                servers.iter_mut().for_each(|server| {
+                    server_transformer::resolve_relative_url(server, server_base)
+                })
+            });
+        }
+        if filters.embed_base_path {
+            server_transformer::embed_base_path(&mut filtered_api)?;
+        }
+        if filters.prune_server_variables {
+            server_transformer::for_each_server(
+                &mut filtered_api,
+                server_transformer::prune_unused_variables,
+            );
+        }
+        if filters.dedupe_schemas {
+            let mut document = serde_json::to_value(&filtered_api)?;
+            let duplicates = component_renamer::dedupe_schemas(&mut document);
+            filtered_api = serde_json::from_value(document)?;
+            for (duplicate, canonical) in duplicates {
+                diagnostics::info(format!(
+                    "schema '{}' is identical to schema '{}' and was replaced by it",
+                    duplicate, canonical
+                ));
+            }
+        }
+        if filters.inline_single_use_schemas {
+            let mut document = serde_json::to_value(&filtered_api)?;
+            schema_inliner::inline_single_use_schemas(&mut document, max_depth)?;
+            filtered_api = serde_json::from_value(document)?;
+        }
+        if let Some(prefix) = filters.schema_name_prefix {
+            let mut document = serde_json::to_value(&filtered_api)?;
+            component_renamer::prefix_schema_names(&mut document, &prefix);
+            filtered_api = serde_json::from_value(document)?;
+        }
+        if !filters.extension_renames.is_empty() {
+            let mut document = serde_json::to_value(&filtered_api)?;
+            extension_renamer::rename_extensions(&mut document, &filters.extension_renames);
+            filtered_api = serde_json::from_value(document)?;
+        }
+        Ok(filtered_api)
     }
 }
 
@@ -187,6 +751,7 @@ impl OpenAPIFilter for OpenAPI {
 ///
 /// * `allowed_securities` - names of the security definitions found from filtered paths.
 /// * `security_schemes` - security scheme definitions in the openapi document.
+/// * `used_scopes` - when provided, scopes of the OAuth2 flows not listed here for the scheme are removed.
 ///
 /// # Returns
 ///
@@ -194,79 +759,313 @@ impl OpenAPIFilter for OpenAPI {
 fn filter_securities(
     allowed_securities: &HashSet<String>,
     security_schemes: &IndexMap<String, ReferenceOr<SecurityScheme>>,
+    used_scopes: Option<&HashMap<String, HashSet<String>>>,
 ) -> IndexMap<String, ReferenceOr<SecurityScheme>> {
     let mut filtered_securities: IndexMap<String, ReferenceOr<SecurityScheme>> = IndexMap::new();
     security_schemes
         .iter()
         .filter(|scheme| allowed_securities.contains(scheme.0))
         .for_each(|(key, value)| {
-            filtered_securities.insert(key.clone(), value.clone());
+            let value = match used_scopes {
+                Some(used_scopes) => {
+                    prune_scheme_scopes(value, used_scopes.get(key).unwrap_or(&HashSet::new()))
+                }
+                None => value.clone(),
+            };
+            filtered_securities.insert(key.clone(), value);
         });
     filtered_securities
 }
 
+/// Removes the scopes that are not used from the flows of an OAuth2 security scheme
+///
+/// The scopes of the flows are not public in the `openapiv3` types, so the scheme is rewritten through its JSON value.
+///
+/// # Arguments
+///
+/// * `scheme` - The security scheme definition.
+/// * `used_scopes` - Scopes of the scheme required by the kept operations.
+///
+/// # Returns
+///
+/// * `ReferenceOr<SecurityScheme>` - The security scheme with only the used scopes.
+fn prune_scheme_scopes(
+    scheme: &ReferenceOr<SecurityScheme>,
+    used_scopes: &HashSet<String>,
+) -> ReferenceOr<SecurityScheme> {
+    let mut scheme_value = serde_json::to_value(scheme).unwrap();
+    if let Some(Value::Object(flows)) = scheme_value.get_mut("flows") {
+        for flow in flows.values_mut() {
+            if let Some(Value::Object(scopes)) = flow.get_mut("scopes") {
+                scopes.retain(|scope, _| used_scopes.contains(scope));
+            }
+        }
+    }
+    serde_json::from_value(scheme_value).unwrap()
+}
+
+/// Collects the scopes each security scheme is required with by the kept operations and the document root
+///
+/// # Arguments
+///
+/// * `paths` - The filtered paths.
+/// * `root_security` - Security requirements of the document root.
+/// * `components` - Components of the document, used to resolve referenced path items.
+///
+/// # Returns
+///
+/// * `HashMap<String, HashSet<String>>` - Used scopes by security scheme name.
+fn collect_used_scopes(
+    paths: &IndexMap<String, ReferenceOr<PathItem>>,
+    root_security: &Option<Vec<SecurityRequirement>>,
+    components: Option<&Components>,
+) -> HashMap<String, HashSet<String>> {
+    let mut used_scopes: HashMap<String, HashSet<String>> = HashMap::new();
+    let path_items: Vec<PathItem> = paths
+        .values()
+        .filter_map(|path| resolve_path_item(path, components))
+        .collect();
+    let operation_requirements = path_items
+        .iter()
+        .flat_map(|path| path.iter())
+        .flat_map(|(_, operation)| operation.security.iter().flatten());
+    for requirement in root_security.iter().flatten().chain(operation_requirements) {
+        for (scheme, scopes) in requirement {
+            used_scopes
+                .entry(scheme.clone())
+                .or_default()
+                .extend(scopes.iter().cloned());
+        }
+    }
+    used_scopes
+}
+
+/// Clones an operation keeping only the tags and security requirements allowed by the filters
+///
+/// When filtering by tags or security, only the tags and security schemes used as filters are kept in the operation.
+/// When normalizing bodies, the request body is dropped from methods listed in `METHODS_WITHOUT_REQUEST_BODY`.
+///
+/// # Arguments
+/// * `method` - The lowercase http method of the operation
+/// * `operation` - The operation to clone
+/// * `allowed_tags` - Tag names used as filters, empty when not filtering by tags
+/// * `security_matcher` - Matcher for the security scheme names used as filters, None when not filtering by security
+/// * `normalize_bodies` - Whether request bodies are removed from methods without request body semantics
+///
+/// # Returns
+/// The cloned operation
 fn clone_operation(
+    method: &str,
     operation: &Operation,
     allowed_tags: &HashSet<String>,
-    allowed_securities: &HashSet<String>,
+    security_matcher: Option<&NameMatcher>,
+    normalize_bodies: bool,
 ) -> Operation {
     let filter_tags = allowed_tags.iter().count() > 0;
-    let filter_securities = allowed_securities.iter().count() > 0;
-    if filter_tags || filter_securities {
+    let filter_securities = security_matcher.is_some();
+    let (new_tags, new_security) = if filter_tags || filter_securities {
         let new_tags: Vec<String> = operation
             .tags
             .clone()
             .into_iter()
             .filter(|tag| allowed_tags.contains(tag))
             .collect();
-        let mut new_security: Vec<IndexMap<String, Vec<String>>> = Vec::new();
-        operation.security.iter().for_each(|security_vec| {
-            security_vec.iter().for_each(|old_map| {
-                let mut new_map: IndexMap<String, Vec<String>> = IndexMap::new();
-                old_map
-                    .iter()
-                    .filter(|sec_map_item| allowed_securities.contains(sec_map_item.0))
-                    .for_each(|(key, value)| {
-                        new_map.insert(key.clone(), value.clone());
-                    });
-                if new_map.iter().count() > 0 {
-                    new_security.insert(0, new_map);
-                }
-            });
-        });
-        new_security.reverse();
-        Operation {
-            tags: new_tags,
-            security: if !new_security.is_empty() {
-                Some(new_security)
-            } else {
-                None
-            },
-            ..operation.clone()
-        }
+        let new_security = if filter_securities {
+            retain_matching_requirements(operation.security.as_ref(), security_matcher)
+        } else {
+            None
+        };
+        (new_tags, new_security)
+    } else {
+        (operation.tags.clone(), operation.security.clone())
+    };
+    let new_request_body = if normalize_bodies && METHODS_WITHOUT_REQUEST_BODY.contains(&method) {
+        None
     } else {
-        operation.clone()
+        operation.request_body.clone()
+    };
+    Operation {
+        tags: new_tags,
+        security: new_security,
+        request_body: new_request_body,
+        ..operation.clone()
     }
 }
 
-fn map_path_name_filters<'d>(paths: Option<Vec<String>>) -> Vec<PathFilter<'d>> {
+/// Removes the security schemes not matching the security filters from the security requirements
+///
+/// Requirements left without any schemes are removed, and the requirements are removed altogether when none remain.
+///
+/// # Arguments
+/// * `security` - The security requirements of an operation or the document root
+/// * `security_matcher` - When provided, matches the names of the security schemes that are kept
+///
+/// # Returns
+/// The security requirements with only the matching schemes, or the requirements as they are when not filtering by security
+fn retain_matching_requirements(
+    security: Option<&Vec<SecurityRequirement>>,
+    security_matcher: Option<&NameMatcher>,
+) -> Option<Vec<SecurityRequirement>> {
+    let Some(security_matcher) = security_matcher else {
+        return security.cloned();
+    };
+    let new_security: Vec<SecurityRequirement> = security
+        .into_iter()
+        .flatten()
+        .map(|requirement| {
+            requirement
+                .iter()
+                .filter(|(name, _)| security_matcher.matches(name))
+                .map(|(name, scopes)| (name.clone(), scopes.clone()))
+                .collect::<SecurityRequirement>()
+        })
+        .filter(|requirement| !requirement.is_empty())
+        .collect();
+    (!new_security.is_empty()).then_some(new_security)
+}
+
+/// Creates a vector of path filters based on the path names and wildcard patterns
+///
+/// # Arguments
+/// * `paths` - An optional list of path names or wildcard patterns
+/// * `path_matches` - When provided, collects the path keys matched by each pattern
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI paths
+fn map_path_name_filters<'d>(
+    paths: Option<Vec<String>>,
+    path_matches: Option<&'d RefCell<IndexMap<String, Vec<String>>>>,
+) -> Vec<PathFilter<'d>> {
     let path_filters: Vec<PathFilter<'d>> = paths
         .into_iter()
         .map(|path_patterns| {
+            if let Some(path_matches) = path_matches {
+                path_matches.borrow_mut().extend(
+                    path_patterns
+                        .iter()
+                        .map(|pattern| (pattern.clone(), Vec::new())),
+                );
+            }
             Box::new(move |(key, _value): &(&String, &ReferenceOr<PathItem>)| {
-                let path_matchers: Vec<WildMatch> = path_patterns
+                let matching_patterns: Vec<&String> = path_patterns
                     .iter()
-                    .map(|name| WildMatch::new(name))
+                    .filter(|pattern| WildMatch::new(pattern).matches(key))
                     .collect();
-                path_matchers
-                    .iter()
-                    .any(|pattern| pattern.matches(key.to_owned()))
+                if let Some(path_matches) = path_matches {
+                    let mut path_matches = path_matches.borrow_mut();
+                    for pattern in &matching_patterns {
+                        path_matches[*pattern].push(key.to_string());
+                    }
+                }
+                !matching_patterns.is_empty()
             }) as PathFilter<'d>
         })
         .collect();
     path_filters
 }
 
+/// Checks that every path given without wildcards has an operation with any of the methods
+///
+/// # Arguments
+/// * `openapi` - The document being filtered
+/// * `paths` - The path names and wildcard patterns given as filtering parameters
+/// * `methods` - The lowercase methods given as filtering parameters
+///
+/// # Returns
+/// An error naming the first exact path that is missing or has none of the methods
+fn check_exact_paths_have_methods(
+    openapi: &OpenAPI,
+    paths: &[String],
+    methods: &[String],
+) -> Result<(), FilterError> {
+    for path in paths.iter().filter(|path| !path.contains(['*', '?'])) {
+        let has_method = openapi
+            .paths
+            .paths
+            .get(path)
+            .and_then(|path_item| resolve_path_item(path_item, openapi.components.as_ref()))
+            .is_some_and(|path_item| {
+                path_item
+                    .iter()
+                    .any(|(method, _)| methods.iter().any(|requested| requested == method))
+            });
+        if !has_method {
+            return Err(FilterError::MissingOperation {
+                path: path.clone(),
+                methods: methods.join(" or "),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Writes a warning for every kept operation of the path whose `responses` object is empty
+///
+/// # Arguments
+/// * `path_name` - Key of the path
+/// * `path` - The path item of the operations
+/// * `kept_operations` - The operations of the path that are kept, by method
+fn warn_about_empty_responses(
+    path_name: &str,
+    path: &PathItem,
+    kept_operations: &HashMap<&str, &Operation>,
+) {
+    for (method, operation) in path.iter() {
+        if kept_operations.contains_key(method)
+            && operation.responses.responses.is_empty()
+            && operation.responses.default.is_none()
+        {
+            diagnostics::warn(format!(
+                "operation '{} {}' has no responses, which OpenAPI requires",
+                method, path_name
+            ));
+        }
+    }
+}
+
+/// Writes a warning for every operation of the path that `--keep-responses` left without responses
+///
+/// # Arguments
+/// * `path_name` - Key of the path
+/// * `old_path` - The path item before the responses were removed
+/// * `new_path` - The path item with the kept operations and responses
+fn warn_about_removed_responses(path_name: &str, old_path: &PathItem, new_path: &PathItem) {
+    let has_responses = |operation: &Operation| {
+        !operation.responses.responses.is_empty() || operation.responses.default.is_some()
+    };
+    for (method, operation) in new_path.iter() {
+        // Operations without responses in the document are already reported by `warn_about_empty_responses`
+        let had_responses = old_path.iter().any(|(old_method, old_operation)| {
+            old_method == method && has_responses(old_operation)
+        });
+        if had_responses && !has_responses(operation) {
+            diagnostics::warn(format!(
+                "operation '{} {}' has no responses left after --keep-responses, which OpenAPI requires",
+                method, path_name
+            ));
+        }
+    }
+}
+
+/// Writes the path keys matched by each path pattern to stderr
+///
+/// # Arguments
+/// * `path_matches` - The matched path keys by pattern
+fn report_path_matches(path_matches: &IndexMap<String, Vec<String>>) {
+    for (pattern, keys) in path_matches {
+        match keys.len() {
+            0 => diagnostics::info(format!("path '{}' matches no paths", pattern)),
+            count => diagnostics::info(format!(
+                "path '{}' matches {} path{}: {}",
+                pattern,
+                count,
+                if count == 1 { "" } else { "s" },
+                keys.join(", ")
+            )),
+        }
+    }
+}
+
 /// Creates a vector of path filters based on provided tags
 ///
 /// This function converts an optional list of tags into filter closures that can be applied to OpenAPI paths.
@@ -284,9 +1083,11 @@ fn map_path_tags_filters<'d>(tags: Option<Vec<String>>) -> Vec<PathFilter<'d>> {
         .map(|tags| {
             Box::new(
                 move |(_key, reference_or_path): &(&String, &ReferenceOr<PathItem>)| {
-                    reference_or_path.to_owned().as_item().unwrap().iter().any(
-                        |(_str, operation)| operation.tags.iter().any(|tag| tags.contains(tag)),
-                    )
+                    reference_or_path.as_item().is_some_and(|path| {
+                        path.iter().any(|(_str, operation)| {
+                            operation.tags.iter().any(|tag| tags.contains(tag))
+                        })
+                    })
                 },
             ) as PathFilter<'d>
         })
@@ -294,170 +1095,745 @@ fn map_path_tags_filters<'d>(tags: Option<Vec<String>>) -> Vec<PathFilter<'d>> {
     path_filters
 }
 
+/// Creates a vector of path filters based on excluded tags
+///
+/// This function converts an optional list of excluded tags into filter closures that can be applied to OpenAPI paths.
+/// The filters check if any operation in the path has none of the excluded tags, so that paths left without
+/// operations are removed.
+///
+/// # Arguments
+/// * `exclude_tags` - An optional list of excluded tag names
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI paths
+fn map_path_exclude_tags_filters<'d>(exclude_tags: Option<Vec<String>>) -> Vec<PathFilter<'d>> {
+    exclude_tags
+        .into_iter()
+        .map(|exclude_tags| {
+            Box::new(
+                move |(_key, reference_or_path): &(&String, &ReferenceOr<PathItem>)| {
+                    reference_or_path.as_item().is_some_and(|path| {
+                        path.iter().any(|(_str, operation)| {
+                            !operation.tags.iter().any(|tag| exclude_tags.contains(tag))
+                        })
+                    })
+                },
+            ) as PathFilter<'d>
+        })
+        .collect()
+}
+
 /// Creates a vector of path filters based on security requirements
 ///
-/// This function converts an optional list of security schemes into filter closures that can be applied to OpenAPI paths.
-/// The filters check if any operation in the path uses one of the specified security schemes.
+/// This function converts an optional security scheme name matcher into filter closures that can be applied to OpenAPI paths.
+/// The filters check if any operation in the path uses one of the matching security schemes.
 /// Runs filtering for all the methods under path to find out which paths to keep
 ///
 /// # Arguments
-/// * `securities` - An optional list of security scheme names
+/// * `security_matcher` - An optional matcher for exact security scheme names and security scheme name patterns
+/// * `root_security` - The document level security requirements inherited by operations without their own
 ///
 /// # Returns
 /// A vector of filter closures that can be applied to OpenAPI paths
 ///
-fn map_path_security_filters<'d>(securities: Option<Vec<String>>) -> Vec<PathFilter<'d>> {
-    let path_filters: Vec<PathFilter<'d>> = securities
-        .into_iter()
-        .map(|securities| {
+fn map_path_security_filters<'d>(
+    security_matcher: Option<NameMatcher>,
+    root_security: Option<Vec<SecurityRequirement>>,
+) -> Vec<PathFilter<'d>> {
+    let path_filters: Vec<PathFilter<'d>> = security_matcher
+        .map(|security_matcher| {
             Box::new(
                 move |(_key, reference_or_path): &(&String, &ReferenceOr<PathItem>)| {
-                    reference_or_path.to_owned().as_item().unwrap().iter().any(
-                        |(_str, operation)| {
-                            operation.security.iter().any(|security| {
-                                security.iter().any(|item| {
-                                    item.keys()
-                                        .any(|security_name| securities.contains(security_name))
-                                })
-                            })
-                        },
-                    )
+                    reference_or_path.as_item().is_some_and(|path| {
+                        path.iter().any(|(_str, operation)| {
+                            uses_matching_security(
+                                operation,
+                                root_security.as_ref(),
+                                &security_matcher,
+                            )
+                        })
+                    })
                 },
             ) as PathFilter<'d>
         })
+        .into_iter()
         .collect();
     path_filters
 }
 
+/// Returns true when any of the effective security requirements of the operation uses a matching security scheme
+fn uses_matching_security(
+    operation: &Operation,
+    root_security: Option<&Vec<SecurityRequirement>>,
+    security_matcher: &NameMatcher,
+) -> bool {
+    effective_security(operation, root_security).is_some_and(|security| {
+        security.iter().any(|requirement| {
+            requirement
+                .keys()
+                .any(|security_name| security_matcher.matches(security_name))
+        })
+    })
+}
+
 /// Creates a vector of path filters based on provided tags
 ///
 /// This function converts an optional list of tags into filter closures that can be applied to OpenAPI operations.
-/// The filters check if any operation in the operation has a matching tag.
+/// The filters check if the operation has any of the tags, or every one of them when all tags are required.
 ///
 /// # Arguments
 /// * `tags` - An optional list of tag names
+/// * `tag_match` - Whether any or all of the tags are required
 ///
 /// # Returns
 /// A vector of filter closures that can be applied to OpenAPI paths
-fn map_operation_tags_filters<'d>(tags: Option<Vec<String>>) -> Vec<OperationFilter<'d>> {
+fn map_operation_tags_filters<'d>(
+    tags: Option<Vec<String>>,
+    tag_match: TagMatch,
+) -> Vec<OperationFilter<'d>> {
     let operation_filters: Vec<OperationFilter<'d>> = tags
         .into_iter()
-        .map(|operations| {
-            Box::new(move |(_key, operation): &(&str, &Operation)| {
-                operation.tags.iter().any(|tag| operations.contains(tag))
-            }) as OperationFilter<'d>
+        .map(|tags| match tag_match {
+            TagMatch::Any => Box::new(move |(_key, operation): &(&str, &Operation)| {
+                operation.tags.iter().any(|tag| tags.contains(tag))
+            }) as OperationFilter<'d>,
+            TagMatch::All => Box::new(move |(_key, operation): &(&str, &Operation)| {
+                tags.iter().all(|tag| operation.tags.contains(tag))
+            }) as OperationFilter<'d>,
         })
         .collect();
     operation_filters
 }
 
-/// Creates a vector of method filters based on requirements
+/// Creates a vector of operation filters based on excluded tags
 ///
-/// This function converts an optional list of operations into filter closures that can be applied to OpenAPI operations.
-/// The filters check if any operation in the path uses one of the specified security schemes.
+/// The filters remove the operations that have any of the excluded tags. Operations without tags are kept.
 ///
 /// # Arguments
-/// * `methods` - An optional list of http methods
+/// * `exclude_tags` - An optional list of excluded tag names
 ///
 /// # Returns
-/// A vector of filter closures that can be applied to OpenAPI paths
-///
-fn map_operation_method_filters<'d>(operations: Option<Vec<String>>) -> Vec<OperationFilter<'d>> {
-    let operation_filters: Vec<OperationFilter<'d>> = operations
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_exclude_tags_filters<'d>(
+    exclude_tags: Option<Vec<String>>,
+) -> Vec<OperationFilter<'d>> {
+    exclude_tags
         .into_iter()
-        .map(|operations| {
-            Box::new(move |(operation_name, _operation): &(&str, &Operation)| {
-                operations.contains(&operation_name.to_string())
+        .map(|exclude_tags| {
+            Box::new(move |(_key, operation): &(&str, &Operation)| {
+                !operation.tags.iter().any(|tag| exclude_tags.contains(tag))
             }) as OperationFilter<'d>
         })
-        .collect();
-    operation_filters
+        .collect()
 }
 
-/// Creates a vector of operation filters based on a list of security requirements.
+/// Creates a vector of operation filters based on JSONPath expressions
 ///
-/// This function takes an optional list of security requirements and converts them into a vector of `OperationFilter` closures.
-/// Each `OperationFilter` closure checks if a given operation has any of the provided security requirements.
+/// This function converts an optional JSONPath selector into filter closures that can be applied to OpenAPI operations.
+/// The expressions are evaluated against the JSON value of each operation, which does not include the comments stashed
+/// for `--preserve-comments`.
 ///
 /// # Arguments
-///
-/// * `securitites` - An `Option` containing a vector of security requirements.
+/// * `jsonpath_selector` - An optional selector built from the JSONPath expressions
 ///
 /// # Returns
-///
-/// * `Vec<OperationFilter<'d>>` - A vector of `OperationFilter` closures.
-fn map_operation_security_filters<'d>(securities: Option<Vec<String>>) -> Vec<OperationFilter<'d>> {
-    let operation_filters: Vec<OperationFilter<'d>> = securities
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_jsonpath_filters<'d>(
+    jsonpath_selector: Option<JsonPathSelector>,
+) -> Vec<OperationFilter<'d>> {
+    jsonpath_selector
         .into_iter()
-        .map(|securities| {
-            Box::new(move |(_key, operation): &(&str, &Operation)| {
-                operation.security.iter().any(|security| {
-                    security
-                        .iter()
-                        .any(|map| map.keys().any(|key| securities.contains(key)))
-                })
+        .map(|jsonpath_selector| {
+            Box::new(move |(_operation_name, operation): &(&str, &Operation)| {
+                serde_json::to_value(operation)
+                    .is_ok_and(|operation| jsonpath_selector.matches(&operation))
             }) as OperationFilter<'d>
         })
-        .collect();
-    operation_filters
+        .collect()
 }
 
-/// Filters and retains only used component references
+/// Creates a vector of operation filters based on a regular expression
 ///
-/// This function examines an OpenAPI document and its collection of referenced components, filtering out any components that are not actually referenced in the filtered paths.
-/// It ensures that only the necessary components remain in the document after filtering.
+/// The expression is matched anywhere in the compact JSON serialization of each operation, e.g. `"ssn"` matches
+/// operations mentioning a property named `ssn`. References are not resolved, so only the operation itself is searched,
+/// without the comments stashed for `--preserve-comments`.
 ///
 /// # Arguments
-/// * `openapi` - The OpenAPI document to filter
-/// * `components` - A set of component names that have been referenced in the filtered paths
+/// * `grep` - An optional regular expression
 ///
 /// # Returns
-/// The filtered Components object containing only used components
-fn found_refs_to_components(openapi: &OpenAPI, components: &mut HashSet<String>) -> Components {
-    let mut component_references: HashMap<String, Vec<String>> = HashMap::new();
-
-    reference_collector::collect_component_refs(
-        serde_json::to_value(openapi)
-            .unwrap()
-            .get("components")
-            .unwrap(),
-        "#/components",
-        &mut component_references,
-        2,
-        0,
-    );
-    let found_references: HashMap<String, Vec<String>> = component_references
-        .into_iter()
-        .filter(|(key, _)| components.contains(key))
-        .collect();
-    let final_references = reference_processor::get_kept_references(&found_references, components);
-    let component_json_paths: Vec<String> = final_references
-        .iter()
-        .filter(|component| component.starts_with("#/components/"))
-        .map(|component| &component[13..])
-        .map(|component| component.split("/"))
-        .map(|component_path_elements| {
-            component_path_elements
-                .collect::<Vec<_>>()
-                .join(".")
-                .to_owned()
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_grep_filters<'d>(grep: Option<Regex>) -> Vec<OperationFilter<'d>> {
+    grep.into_iter()
+        .map(|grep| {
+            Box::new(move |(_operation_name, operation): &(&str, &Operation)| {
+                serde_json::to_string(operation).is_ok_and(|operation| grep.is_match(&operation))
+            }) as OperationFilter<'d>
+        })
+        .collect()
+}
+
+/// Creates a vector of operation filters based on wildcard patterns of operation summaries
+///
+/// Operations without a summary are matched as if their summary was empty, so only a pattern like `*` matches them.
+///
+/// # Arguments
+/// * `summary_globs` - An optional list of summary patterns with `*` and `?` wildcards
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_summary_filters<'d>(
+    summary_globs: Option<Vec<String>>,
+) -> Vec<OperationFilter<'d>> {
+    summary_globs
+        .into_iter()
+        .map(|summary_globs| {
+            let patterns: Vec<WildMatch> = summary_globs
+                .iter()
+                .map(|pattern| WildMatch::new(pattern))
+                .collect();
+            Box::new(move |(_operation_name, operation): &(&str, &Operation)| {
+                let summary = operation.summary.as_deref().unwrap_or("");
+                patterns.iter().any(|pattern| pattern.matches(summary))
+            }) as OperationFilter<'d>
+        })
+        .collect()
+}
+
+/// Creates a vector of operation filters based on referenced components
+///
+/// This function converts an optional list of component references into filter closures that can be applied to OpenAPI operations.
+/// The filters check if the operation uses any of the components, directly or through the references of other components.
+///
+/// # Arguments
+/// * `referenced_components` - An optional list of component references, e.g. `#/components/schemas/Pet`
+/// * `component_references` - The references of each component, by the reference of the component
+/// * `max_depth` - How deep the operations and components may be nested
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_reference_filters<'d>(
+    referenced_components: Option<Vec<String>>,
+    component_references: Option<HashMap<String, Vec<String>>>,
+    max_depth: usize,
+) -> Vec<OperationFilter<'d>> {
+    referenced_components
+        .into_iter()
+        .zip(component_references)
+        .map(|(referenced_components, component_references)| {
+            Box::new(move |(_operation_name, operation): &(&str, &Operation)| {
+                let mut operation_references: HashSet<String> = HashSet::new();
+                serde_json::to_value(operation)
+                    .ok()
+                    .and_then(|operation| {
+                        collect_path_refs(&operation, &mut operation_references, None, max_depth)
+                            .ok()
+                    })
+                    .and_then(|_| {
+                        reference_processor::get_kept_references(
+                            &component_references,
+                            &operation_references,
+                            max_depth,
+                        )
+                        .ok()
+                    })
+                    .is_some_and(|kept_references| {
+                        referenced_components
+                            .iter()
+                            .any(|component| kept_references.contains(component))
+                    })
+            }) as OperationFilter<'d>
+        })
+        .collect()
+}
+
+/// Creates a vector of operation filters based on whether the operations declare a request body
+///
+/// Request bodies given as references count as declared.
+///
+/// # Arguments
+/// * `has_request_body` - When provided, whether the kept operations have a request body or not
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_request_body_filters<'d>(
+    has_request_body: Option<bool>,
+) -> Vec<OperationFilter<'d>> {
+    has_request_body
+        .into_iter()
+        .map(|has_request_body| {
+            Box::new(move |(_key, operation): &(&str, &Operation)| {
+                operation.request_body.is_some() == has_request_body
+            }) as OperationFilter<'d>
+        })
+        .collect()
+}
+
+/// Creates a vector of method filters based on requirements
+///
+/// This function converts an optional list of operations into filter closures that can be applied to OpenAPI operations.
+/// The filters check if any operation in the path uses one of the specified security schemes.
+///
+/// # Arguments
+/// * `methods` - An optional list of http methods
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI paths
+///
+fn map_operation_method_filters<'d>(operations: Option<Vec<String>>) -> Vec<OperationFilter<'d>> {
+    let operation_filters: Vec<OperationFilter<'d>> = operations
+        .into_iter()
+        .map(|operations| {
+            Box::new(move |(operation_name, _operation): &(&str, &Operation)| {
+                operations.contains(&operation_name.to_string())
+            }) as OperationFilter<'d>
+        })
+        .collect();
+    operation_filters
+}
+
+/// Creates a vector of operation filters based on a list of security requirements.
+///
+/// This function takes an optional security scheme name matcher and converts it into a vector of `OperationFilter` closures.
+/// Each `OperationFilter` closure checks if a given operation has any security requirement matching the matcher.
+///
+/// # Arguments
+///
+/// * `security_matcher` - An `Option` containing a matcher for exact security scheme names and security scheme name patterns.
+/// * `root_security` - The document level security requirements inherited by operations without their own.
+///
+/// # Returns
+///
+/// * `Vec<OperationFilter<'d>>` - A vector of `OperationFilter` closures.
+fn map_operation_security_filters<'d>(
+    security_matcher: Option<NameMatcher>,
+    root_security: Option<Vec<SecurityRequirement>>,
+) -> Vec<OperationFilter<'d>> {
+    let operation_filters: Vec<OperationFilter<'d>> = security_matcher
+        .map(|security_matcher| {
+            Box::new(move |(_key, operation): &(&str, &Operation)| {
+                uses_matching_security(operation, root_security.as_ref(), &security_matcher)
+            }) as OperationFilter<'d>
+        })
+        .into_iter()
+        .collect();
+    operation_filters
+}
+
+/// Creates a vector of operation filters based on security requirement scopes
+///
+/// The filters keep the operations whose effective security requirements list a matching scope for any scheme.
+///
+/// # Arguments
+/// * `scope_matcher` - An optional matcher of exact scopes and scope wildcard patterns
+/// * `root_security` - The document level security requirements inherited by operations without their own
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI operations
+fn map_operation_scope_filters<'d>(
+    scope_matcher: Option<NameMatcher>,
+    root_security: Option<Vec<SecurityRequirement>>,
+) -> Vec<OperationFilter<'d>> {
+    scope_matcher
+        .map(|scope_matcher| {
+            Box::new(move |(_key, operation): &(&str, &Operation)| {
+                effective_security(operation, root_security.as_ref()).is_some_and(|security| {
+                    security
+                        .iter()
+                        .flat_map(|requirement| requirement.values().flatten())
+                        .any(|scope| scope_matcher.matches(scope))
+                })
+            }) as OperationFilter<'d>
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Creates a vector of path filters based on selected operations
+///
+/// The filters keep the paths that are selected with at least one method. Path names are matched exactly.
+///
+/// # Arguments
+/// * `selection` - An optional list of selected (path, method) pairs
+///
+/// # Returns
+/// A vector of filter closures that can be applied to OpenAPI paths
+fn map_path_selection_filters<'d>(
+    selection: Option<&'d Vec<(String, String)>>,
+) -> Vec<PathFilter<'d>> {
+    selection
+        .map(|selection| {
+            Box::new(
+                move |(key, _reference_or_path): &(&String, &ReferenceOr<PathItem>)| {
+                    selection.iter().any(|(path, _method)| path == *key)
+                },
+            ) as PathFilter<'d>
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Creates a vector of operation filters based on selected operations
+///
+/// The filters keep the operations of a single path whose method is selected for the path.
+///
+/// # Arguments
+/// * `selection` - An optional list of selected (path, method) pairs
+/// * `path_name` - Name of the path the filtered operations belong to
+///
+/// # Returns
+/// A vector of filter closures that can be applied to the operations of the path
+fn map_operation_selection_filters<'d>(
+    selection: Option<&'d Vec<(String, String)>>,
+    path_name: &'d str,
+) -> Vec<OperationFilter<'d>> {
+    selection
+        .map(|selection| {
+            Box::new(move |(operation_name, _operation): &(&str, &Operation)| {
+                selection
+                    .iter()
+                    .any(|(path, method)| path == path_name && method == operation_name)
+            }) as OperationFilter<'d>
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Creates a vector of operation filters based on parameter locations.
+///
+/// This function converts an optional list of parameter locations into filter closures that can be applied to the operations of a single path.
+/// The filters check if the operation, or the path the operation belongs to, declares a parameter in one of the locations.
+/// Parameter references are resolved against the parameters in document components.
+///
+/// # Arguments
+///
+/// * `locations` - An optional list of parameter locations (query, path, header or cookie)
+/// * `path_parameters` - Parameters declared on the path level of the filtered operations
+/// * `components` - Components of the document used for resolving parameter references
+///
+/// # Returns
+///
+/// * `Vec<OperationFilter<'d>>` - A vector of `OperationFilter` closures.
+fn map_operation_parameter_location_filters<'d>(
+    locations: Option<&'d Vec<String>>,
+    path_parameters: Vec<ReferenceOr<Parameter>>,
+    components: Option<&'d Components>,
+) -> Vec<OperationFilter<'d>> {
+    locations
+        .map(|locations| {
+            Box::new(move |(_key, operation): &(&str, &Operation)| {
+                path_parameters
+                    .iter()
+                    .chain(operation.parameters.iter())
+                    .filter_map(|parameter| resolve_parameter(parameter, components))
+                    .any(|parameter| {
+                        locations
+                            .iter()
+                            .any(|location| location == parameter_location(parameter))
+                    })
+            }) as OperationFilter<'d>
+        })
+        .into_iter()
+        .collect()
+}
+
+/// Removes the path level parameters that are not used after filtering the operations of the path.
+///
+/// Path parameters are kept when the path template contains them. Other parameters are kept when at least one
+/// of the kept operations does not override them with a parameter of the same name and location.
+/// Parameters whose reference cannot be resolved are kept as they are.
+///
+/// # Arguments
+/// * `path_name` - The path template, e.g. `/pets/{petId}`
+/// * `path` - The path item with its filtered operations
+/// * `components` - Components of the document
+fn retain_used_path_parameters(
+    path_name: &str,
+    path: &mut PathItem,
+    components: Option<&Components>,
+) {
+    let overrides: Vec<HashSet<(&str, &str)>> = path
+        .iter()
+        .map(|(_, operation)| {
+            operation
+                .parameters
+                .iter()
+                .filter_map(|parameter| resolve_parameter(parameter, components))
+                .map(|parameter| {
+                    (
+                        parameter.parameter_data_ref().name.as_str(),
+                        parameter_location(parameter),
+                    )
+                })
+                .collect()
         })
         .collect();
-    let component_json_path_refs: Vec<&str> = component_json_paths
+    let is_used = |parameter: &ReferenceOr<Parameter>| {
+        let Some(parameter) = resolve_parameter(parameter, components) else {
+            return true;
+        };
+        let name = parameter.parameter_data_ref().name.as_str();
+        match parameter {
+            Parameter::Path { .. } => path_name.contains(&format!("{{{}}}", name)),
+            parameter => {
+                let key = (name, parameter_location(parameter));
+                overrides
+                    .iter()
+                    .any(|operation_parameters| !operation_parameters.contains(&key))
+            }
+        }
+    };
+    let parameters: Vec<ReferenceOr<Parameter>> = path
+        .parameters
         .iter()
-        .map(|path| path.as_str())
+        .filter(|parameter| is_used(parameter))
+        .cloned()
         .collect();
+    path.parameters = parameters;
+}
 
-    let filtered_components = json_path_filter::filter_json(
-        &serde_json::to_value(&openapi.components).unwrap(),
-        &component_json_path_refs,
-    );
+/// Filters and retains only used component references
+///
+/// This function examines an OpenAPI document and its collection of referenced components, filtering out any components that are not actually referenced in the filtered paths.
+/// It ensures that only the necessary components remain in the document after filtering.
+///
+/// # Arguments
+/// * `openapi` - The OpenAPI document to filter
+/// * `components` - A set of component names that have been referenced in the filtered paths
+/// * `max_depth` - Maximum nesting depth of the components and their reference chains
+///
+/// # Returns
+/// The filtered Components object containing only used components, or an error if the components are nested too deep
+fn found_refs_to_components(
+    openapi: &OpenAPI,
+    components: &mut HashSet<String>,
+    max_depth: usize,
+) -> Result<Components, RecursionLimitExceeded> {
+    let components_value = components_value(openapi);
+    let component_references = component_reference_map(&components_value, max_depth)?;
+    // Percent-encoded references are decoded so that they match the pointers built from the component names
+    let referenced: HashSet<String> = components.iter().map(|r| decode_reference(r)).collect();
+    // All the component references are needed so that references of the referenced components are kept as well
+    let final_references =
+        reference_processor::get_kept_references(&component_references, &referenced, max_depth)?;
+    // Segments are taken directly from the `#/components/...` pointers, so component names containing dots are kept intact,
+    // and are unescaped to the component names, e.g. `Order~1Detail` to `Order/Detail`
+    let component_segments: Vec<Vec<String>> = final_references
+        .iter()
+        .filter_map(|component| component.strip_prefix("#/components/"))
+        .map(pointer_segments)
+        .collect();
+    let component_json_paths: Vec<Vec<&str>> = component_segments
+        .iter()
+        .map(|segments| segments.iter().map(String::as_str).collect())
+        .collect();
+
+    let filtered_components =
+        json_path_filter::filter_json(&components_value, &component_json_paths);
     match filtered_components {
-        Some(filtered_components) => serde_json::from_value(filtered_components).ok().unwrap(),
-        None => serde_json::from_value(json!({})).ok().unwrap(),
+        Some(mut filtered_components) => {
+            rename_component_category(
+                &mut filtered_components,
+                openapi31_fields::PATH_ITEMS_KEY,
+                openapi31_fields::PATH_ITEMS_EXTENSION,
+            );
+            Ok(serde_json::from_value(filtered_components).ok().unwrap())
+        }
+        None => Ok(serde_json::from_value(json!({})).ok().unwrap()),
+    }
+}
+
+/// Adds the components whose `#/components/<category>/<name>` pointer matches any of the wildcard patterns to the kept components
+///
+/// Matching security schemes are added to the kept security scheme names, which are filtered separately from the other components.
+///
+/// # Arguments
+/// * `openapi` - The original OpenAPI document
+/// * `patterns` - Wildcard patterns matched against the component pointers
+/// * `components` - The references of the kept components
+/// * `securities` - The names of the kept security schemes
+fn retain_matching_components(
+    openapi: &OpenAPI,
+    patterns: &[String],
+    components: &mut HashSet<String>,
+    securities: &mut HashSet<String>,
+) {
+    let patterns: Vec<WildMatch> = patterns
+        .iter()
+        .map(|pattern| WildMatch::new(pattern))
+        .collect();
+    let Value::Object(categories) = components_value(openapi) else {
+        return;
+    };
+    for (category, category_components) in categories.iter() {
+        let Value::Object(category_components) = category_components else {
+            continue;
+        };
+        for name in category_components.keys() {
+            let pointer = format!("#/components/{}/{}", category, escape_segment(name));
+            if !patterns.iter().any(|pattern| pattern.matches(&pointer)) {
+                continue;
+            }
+            if category == "securitySchemes" {
+                securities.insert(name.clone());
+            } else {
+                components.insert(pointer);
+            }
+        }
+    }
+}
+
+/// Serializes the components of the document for collecting and filtering references
+///
+/// Reusable path items are stashed as an extension, they are handled like any other component category in the returned value.
+///
+/// # Arguments
+/// * `openapi` - The original OpenAPI document
+///
+/// # Returns
+/// The JSON value of the components
+fn components_value(openapi: &OpenAPI) -> Value {
+    let mut components_value = serde_json::to_value(&openapi.components).unwrap();
+    rename_component_category(
+        &mut components_value,
+        openapi31_fields::PATH_ITEMS_EXTENSION,
+        openapi31_fields::PATH_ITEMS_KEY,
+    );
+    components_value
+}
+
+/// Collects the references between the components
+///
+/// # Arguments
+/// * `components_value` - JSON value of the components
+/// * `max_depth` - Maximum nesting depth of the components
+///
+/// # Returns
+/// The references of each component keyed by the `#/components/<category>/<name>` pointer of the component
+fn component_reference_map(
+    components_value: &Value,
+    max_depth: usize,
+) -> Result<HashMap<String, Vec<String>>, RecursionLimitExceeded> {
+    let mut component_references: HashMap<String, Vec<String>> = HashMap::new();
+    reference_collector::collect_component_refs(
+        components_value,
+        "#/components",
+        &mut component_references,
+        2,
+        0,
+        max_depth,
+    )?;
+    // Percent-encoded references are decoded so that they match the pointers built from the component names
+    Ok(component_references
+        .into_iter()
+        .map(|(component, references)| {
+            let references = references.iter().map(|r| decode_reference(r)).collect();
+            (component, references)
+        })
+        .collect())
+}
+
+/// Renames a component category in the JSON value of the components
+///
+/// # Arguments
+/// * `components` - JSON value of the components
+/// * `from` - Current key of the category
+/// * `to` - New key of the category
+fn rename_component_category(components: &mut Value, from: &str, to: &str) {
+    if let Some(components) = components.as_object_mut()
+        && let Some(category) = components.remove(from)
+    {
+        components.insert(to.to_string(), category);
     }
 }
 
+/// Applies a transformation to the JSON values of the schemas
+///
+/// # Arguments
+/// * `schemas` - Schemas from the components of the document
+/// * `transform` - Function modifying the JSON value of a single schema
+///
+/// # Returns
+/// The transformed schemas
+fn transform_schemas(
+    schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    transform: fn(&mut Value),
+) -> IndexMap<String, ReferenceOr<Schema>> {
+    schemas
+        .iter()
+        .map(|(name, schema)| {
+            let mut schema_value = serde_json::to_value(schema).unwrap();
+            transform(&mut schema_value);
+            (name.clone(), serde_json::from_value(schema_value).unwrap())
+        })
+        .collect()
+}
+
+/// Flattens the `allOf` compositions of the schemas, warning about the conflicting property definitions that were replaced
+///
+/// # Arguments
+/// * `schemas` - Schemas kept in the components of the document
+///
+/// # Returns
+/// The schemas with their single-level `allOf` compositions merged
+fn merge_all_of_schemas(
+    schemas: &IndexMap<String, ReferenceOr<Schema>>,
+) -> IndexMap<String, ReferenceOr<Schema>> {
+    let schema_values: serde_json::Map<String, Value> = schemas
+        .iter()
+        .map(|(name, schema)| (name.clone(), serde_json::to_value(schema).unwrap()))
+        .collect();
+    schemas
+        .keys()
+        .map(|name| {
+            let mut schema_value = schema_values[name].clone();
+            for property in schema_transformer::merge_all_of(&mut schema_value, &schema_values) {
+                diagnostics::warn(format!(
+                    "property '{}' is defined differently by the allOf subschemas of schema '{}', the last definition is kept",
+                    property, name
+                ));
+            }
+            (name.clone(), serde_json::from_value(schema_value).unwrap())
+        })
+        .collect()
+}
+
+/// Applies a schema transformation to the component schemas and to the inline schemas of the components and paths
+///
+/// # Arguments
+/// * `components` - The filtered components
+/// * `paths` - The filtered paths
+/// * `transform` - The transformation applied to each schema
+fn transform_all_schemas(
+    components: &mut Components,
+    paths: &mut IndexMap<String, ReferenceOr<PathItem>>,
+    transform: fn(&mut Value),
+) {
+    components.schemas = transform_schemas(&components.schemas, transform);
+    components.parameters = transform_inline_schemas(&components.parameters, transform);
+    components.headers = transform_inline_schemas(&components.headers, transform);
+    components.request_bodies = transform_inline_schemas(&components.request_bodies, transform);
+    components.responses = transform_inline_schemas(&components.responses, transform);
+    *paths = transform_inline_schemas(paths, transform);
+}
+
+/// Applies a schema transformation to the inline schemas of a document element
+///
+/// # Arguments
+/// * `element` - The document element containing inline schemas, e.g. the paths or a component category
+/// * `transform` - The transformation applied to each inline schema
+///
+/// # Returns
+/// A transformed copy of the element
+fn transform_inline_schemas<T>(element: &T, transform: fn(&mut Value)) -> T
+where
+    T: Serialize + DeserializeOwned,
+{
+    let mut element_value = serde_json::to_value(element).unwrap();
+    schema_transformer::transform_inline_schemas(&mut element_value, transform);
+    serde_json::from_value(element_value).unwrap()
+}
+
 /// Filters and retains only used tags
 ///
 /// This function examines an OpenAPI document and its collection of tags, filtering out any tags that are not actually referenced in the filtered paths.
@@ -482,66 +1858,102 @@ fn found_refs_to_tags(openapi: &OpenAPI, tags: &HashSet<String>) -> Vec<Tag> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ParseError;
     use crate::parser;
     use insta::assert_json_snapshot;
-    use parser::ParsedType;
+    use parser::{ParseOptions, ParsedType, SpecVersion};
 
     #[test]
     fn it_filters_paths_with_no_matches() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/user-reference.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 paths: Some(vec![String::from("non-matching-path")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_paths_with_partial_path_name_match() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/user-reference.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 paths: Some(vec![String::from("*userId*")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_paths_with_method_name_match() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/user-reference.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 methods: Some(vec![String::from("post")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_paths_with_tag_name_match() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/user-reference.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 tags: Some(vec![String::from("item")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
+    }
+
+    #[test]
+    fn it_requires_all_tags_with_tag_match_all() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let operation_ids = |tag_match: TagMatch| -> Vec<String> {
+            let filtered_api = openapi
+                .filter_by_parameters(FilteringParameters {
+                    tags: Some(vec![String::from("user"), String::from("item")]),
+                    tag_match,
+                    ..Default::default()
+                })
+                .unwrap();
+            filtered_api
+                .operations()
+                .map(|(path, method, _)| format!("{} {}", method, path))
+                .collect()
+        };
+
+        assert_eq!(operation_ids(TagMatch::Any).len(), 2);
+        assert_eq!(operation_ids(TagMatch::All), vec!["get /users/{userId}"]);
     }
 
     #[test]
     fn it_filters_paths_with_partial_path_tag_name_and_method_name_match() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/user-reference.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 methods: Some(vec![String::from("get")]),
@@ -549,28 +1961,32 @@ mod tests {
                 paths: Some(vec![String::from("*userId*")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_petstore_with_full_path() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/petstore.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 paths: Some(vec![String::from("/pet/{petId}")]),
                 methods: Some(vec![String::from("get")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_petstore_with_full_path_an_api_key_auth() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/petstore.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 paths: Some(vec![String::from("/pet/{petId}")]),
@@ -578,21 +1994,1580 @@ mod tests {
                 security: Some(vec![String::from("api_key")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
     }
 
     #[test]
     fn it_filters_petstore_with_partial_path_and_does_not_keep_unnecessary_security_schemes() {
-        let openapi: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-            parser::parse_document(&String::from("tests/resources/petstore.yaml"));
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
         let filtered_api =
             extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
                 paths: Some(vec![String::from("*createWithList")]),
                 ..Default::default()
             });
-        assert!(filtered_api.is_some());
-        assert_json_snapshot!(filtered_api);
+        assert!(filtered_api.is_ok());
+        assert_json_snapshot!(filtered_api.unwrap());
+    }
+
+    #[test]
+    fn it_keeps_shared_responses_and_prunes_responses_of_dropped_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/shared-responses.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api =
+            extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
+                methods: Some(vec![String::from("get")]),
+                ..Default::default()
+            });
+        let components = filtered_api.unwrap().components.unwrap();
+        assert!(components.responses.contains_key("NotFound"));
+        assert!(!components.responses.contains_key("GoneResponse"));
+        assert!(components.schemas.contains_key("Error"));
+        assert!(components.schemas.contains_key("ErrorDetail"));
+        assert!(components.schemas.contains_key("Order"));
+    }
+
+    #[test]
+    fn it_filters_operations_by_parameter_location() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/user-reference.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api =
+            extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
+                parameter_locations: Some(vec![String::from("path")]),
+                ..Default::default()
+            });
+        let paths: Vec<&String> = filtered_api.as_ref().unwrap().paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/users/{userId}"]);
+
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api =
+            extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
+                parameter_locations: Some(vec![String::from("header")]),
+                ..Default::default()
+            });
+        let filtered_api = filtered_api.unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/pet/{petId}"]);
+        let pet_path = filtered_api.paths.paths["/pet/{petId}"].as_item().unwrap();
+        assert!(pet_path.delete.is_some());
+        assert!(pet_path.get.is_none());
+    }
+
+    #[test]
+    fn it_preserves_deprecated_operations() {
+        let filters = [
+            FilteringParameters {
+                paths: Some(vec![String::from("/pet")]),
+                ..Default::default()
+            },
+            FilteringParameters {
+                paths: Some(vec![String::from("/pet")]),
+                tags: Some(vec![String::from("pet")]),
+                security: Some(vec![String::from("petstore_auth")]),
+                ..Default::default()
+            },
+        ];
+        for filter in filters {
+            let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+                &String::from("tests/resources/petstore.yaml"),
+                &ParseOptions::default(),
+            );
+            let filtered_api = extract_content(openapi.unwrap())
+                .filter_by_parameters(filter)
+                .unwrap();
+            let pet_path = filtered_api.paths.paths["/pet"].as_item().unwrap();
+            assert!(pet_path.put.as_ref().unwrap().deprecated);
+            assert!(!pet_path.post.as_ref().unwrap().deprecated);
+        }
+    }
+
+    #[test]
+    fn it_prunes_examples_from_component_schemas() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api =
+            extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet/{petId}")]),
+                methods: Some(vec![String::from("get")]),
+                prune_schema_examples: true,
+                ..Default::default()
+            });
+        let components = filtered_api.unwrap().components.unwrap();
+        let schemas = serde_json::to_string(&components.schemas).unwrap();
+        assert!(components.schemas.contains_key("Pet"));
+        assert!(!schemas.contains("\"example\""));
+    }
+
+    #[test]
+    fn it_filters_petstore_with_security_globs() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filtered_api = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet/{petId}")]),
+                methods: Some(vec![String::from("get")]),
+                security_globs: Some(vec![String::from("petstore_*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let operation = filtered_api.paths.paths["/pet/{petId}"]
+            .as_item()
+            .unwrap()
+            .get
+            .clone()
+            .unwrap();
+        let schemes = filtered_api.components.unwrap().security_schemes;
+        assert_eq!(operation.security.unwrap().len(), 1);
+        assert_eq!(schemes.keys().collect::<Vec<_>>(), vec!["petstore_auth"]);
+
+        let filtered_api = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet/{petId}")]),
+                methods: Some(vec![String::from("get")]),
+                security: Some(vec![String::from("api_key")]),
+                security_globs: Some(vec![String::from("petstore_*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let schemes = filtered_api.components.unwrap().security_schemes;
+        assert_eq!(schemes.len(), 2);
+    }
+
+    #[test]
+    fn it_keeps_security_requirement_order_when_one_is_filtered_out() {
+        let operation: Operation = serde_json::from_value(json!({
+            "responses": {},
+            "security": [
+                {"api_key": []},
+                {"basic_auth": []},
+                {"oauth": ["read"], "basic_auth": []}
+            ]
+        }))
+        .unwrap();
+        let security_matcher = NameMatcher::new(
+            Some(vec![String::from("api_key"), String::from("oauth")]),
+            None,
+        );
+
+        let cloned = clone_operation(
+            "get",
+            &operation,
+            &HashSet::new(),
+            security_matcher.as_ref(),
+            false,
+        );
+
+        assert_eq!(
+            serde_json::to_value(cloned.security).unwrap(),
+            json!([{"api_key": []}, {"oauth": ["read"]}])
+        );
+    }
+
+    #[test]
+    fn it_matches_operations_inheriting_document_security() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/inherited-security.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filter = |security: &str| {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    security: Some(vec![String::from(security)]),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        let api_key = filter("api_key");
+        let paths: Vec<&String> = api_key.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/inherited", "/explicit"]);
+        let schemes: Vec<&String> = api_key
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .keys()
+            .collect();
+        assert_eq!(schemes, vec!["api_key"]);
+        let basic_auth = filter("basic_auth");
+        let paths: Vec<&String> = basic_auth.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/overridden"]);
+    }
+
+    #[test]
+    fn it_trims_document_security_to_matching_schemes() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/global-security.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filter = |security: &str| {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    security: Some(vec![String::from(security)]),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        let api_key = filter("api_key");
+        let paths: Vec<&String> = api_key.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/pets"]);
+        assert_eq!(
+            serde_json::to_value(&api_key.security).unwrap(),
+            json!([{"api_key": []}])
+        );
+        let schemes: Vec<&String> = api_key
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .keys()
+            .collect();
+        assert_eq!(schemes, vec!["api_key"]);
+        let oauth2 = filter("oauth2");
+        let paths: Vec<&String> = oauth2.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/pets", "/admin"]);
+        assert_eq!(
+            serde_json::to_value(&oauth2.security).unwrap(),
+            json!([{"oauth2": ["pets:read"]}])
+        );
+        let all_paths = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(all_paths.security, openapi.security);
+    }
+
+    #[test]
+    fn it_keeps_only_referenced_reusable_path_items() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/path-items-3.1.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                tags: Some(vec![String::from("pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/pets"]);
+        let components = filtered_api.components.unwrap();
+        let path_items = &components.extensions[openapi31_fields::PATH_ITEMS_EXTENSION];
+        assert!(path_items.get("PetItem").is_some());
+        assert!(path_items.get("OrderItem").is_none());
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Pet"]);
+    }
+
+    #[test]
+    fn it_keeps_the_tags_and_securities_of_all_operations_of_reusable_path_items() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/path-items-security-3.1.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                tags: Some(vec![String::from("pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let schemes: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .keys()
+            .collect();
+        assert_eq!(schemes, vec!["read_auth", "write_auth"]);
+        let tags: Vec<&String> = filtered_api.tags.iter().map(|tag| &tag.name).collect();
+        assert_eq!(tags, vec!["pets", "admin"]);
+    }
+
+    #[test]
+    fn it_keeps_webhooks_only_when_handled_as_openapi_3_1() {
+        let filter = |assume_version: Option<SpecVersion>| {
+            let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+                &String::from("tests/resources/webhooks-3.1.yaml"),
+                &ParseOptions {
+                    assume_version,
+                    ..Default::default()
+                },
+            );
+            extract_content(openapi.unwrap())
+                .filter_by_parameters(FilteringParameters {
+                    paths: Some(vec![String::from("/owners")]),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let schema_names = |filtered_api: &OpenAPI| -> Vec<String> {
+            filtered_api
+                .components
+                .iter()
+                .flat_map(|components| components.schemas.keys().cloned())
+                .collect()
+        };
+
+        let declared = filter(None);
+        assert_eq!(schema_names(&declared), vec!["Pet", "Owner", "PetEvent"]);
+        let document = openapi31_fields::to_document_value(&declared).unwrap();
+        assert!(document["webhooks"]["newPet"]["post"]["responses"]["200"].is_mapping());
+
+        let assumed_3_0 = filter(Some(SpecVersion::OpenApi3));
+        assert_eq!(schema_names(&assumed_3_0), vec!["Owner"]);
+        let document = openapi31_fields::to_document_value(&assumed_3_0).unwrap();
+        assert!(document.get("webhooks").is_none());
+    }
+
+    #[test]
+    fn it_keeps_the_tags_and_security_schemes_of_webhooks() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/webhooks-security-3.1.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let schemes: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .security_schemes
+            .keys()
+            .collect();
+        assert_eq!(schemes, vec!["api_key", "hook_auth"]);
+        let tags: Vec<&String> = filtered_api.tags.iter().map(|tag| &tag.name).collect();
+        assert_eq!(tags, vec!["pets", "events"]);
+    }
+
+    #[test]
+    fn it_keeps_components_with_dots_in_their_names() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/dotted-schema-names.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let schemas: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .keys()
+            .collect();
+        assert_eq!(schemas, vec!["com.example.Pet", "com.example.Owner"]);
+    }
+
+    #[test]
+    fn it_fails_when_document_is_nested_deeper_than_max_recursion_depth() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let result = extract_content(openapi.unwrap()).filter_by_parameters(FilteringParameters {
+            paths: Some(vec![String::from("/pet")]),
+            max_recursion_depth: Some(3),
+            ..Default::default()
+        });
+        assert!(matches!(
+            result.unwrap_err(),
+            FilterError::RecursionLimitExceeded(_)
+        ));
+    }
+
+    #[test]
+    fn it_filters_operations_by_jsonpath() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                jsonpaths: Some(vec![String::from(
+                    "$.requestBody.content['application/x-www-form-urlencoded']",
+                )]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(
+            paths,
+            vec!["/pet", "/store/order", "/user", "/user/{username}"]
+        );
+
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                jsonpaths: Some(vec![String::from(
+                    "$.responses.200.headers['X-Rate-Limit']",
+                )]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/user/login"]);
+    }
+
+    #[test]
+    fn it_filters_operations_by_regular_expression() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filtered_api = openapi
+            .filter_by_parameters(FilteringParameters {
+                grep: Some(String::from(r#""X-Rate-Limit|"X-Expires-After""#)),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(paths, vec!["/user/login"]);
+
+        let result = openapi.filter_by_parameters(FilteringParameters {
+            grep: Some(String::from("(ssn")),
+            ..Default::default()
+        });
+        assert!(matches!(
+            result.unwrap_err(),
+            FilterError::InvalidPattern {
+                kind: "regular expression",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn it_filters_operations_by_summary_glob() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                summary_globs: Some(vec![String::from("Finds *"), String::from("Logs * user*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(
+            paths,
+            vec!["/pet/findByStatus", "/pet/findByTags", "/user/logout"]
+        );
+    }
+
+    #[test]
+    fn it_keeps_server_variables() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/server-variables.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let variables = filtered_api.servers[0].variables.as_ref().unwrap();
+        assert_eq!(
+            variables.keys().collect::<Vec<_>>(),
+            vec!["region", "version"]
+        );
+        assert_eq!(variables["region"].default, "eu");
+        let operation_servers = filtered_api.paths.paths["/pets"]
+            .as_item()
+            .unwrap()
+            .get
+            .as_ref()
+            .unwrap()
+            .servers
+            .clone();
+        assert!(
+            operation_servers[0]
+                .variables
+                .as_ref()
+                .unwrap()
+                .contains_key("port")
+        );
+    }
+
+    #[test]
+    fn it_prunes_unused_server_variables() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/server-variables.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                prune_server_variables: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let variables = filtered_api.servers[0].variables.as_ref().unwrap();
+        assert_eq!(variables.keys().collect::<Vec<_>>(), vec!["region"]);
+        assert_eq!(variables["region"].enumeration, vec!["eu", "us"]);
+        let operation = filtered_api.paths.paths["/pets"]
+            .as_item()
+            .unwrap()
+            .get
+            .clone()
+            .unwrap();
+        assert!(operation.servers[0].variables.is_none());
+    }
+
+    #[test]
+    fn it_keeps_only_the_first_servers() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/multiple-servers.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                first_server_only: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let urls = |servers: &Vec<openapiv3::Server>| -> Vec<String> {
+            servers.iter().map(|server| server.url.clone()).collect()
+        };
+        assert_eq!(urls(&filtered_api.servers), vec!["https://api.example.com"]);
+        let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert_eq!(urls(&path.servers), vec!["https://pets.api.example.com"]);
+        assert_eq!(
+            urls(&path.get.as_ref().unwrap().servers),
+            vec!["https://read.pets.api.example.com"]
+        );
+        assert!(path.post.as_ref().unwrap().servers.is_empty());
+    }
+
+    #[test]
+    fn it_keeps_servers_by_description_ignoring_case() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/multiple-servers.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                server_descriptions: Some(vec![String::from("prod*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let urls: Vec<&str> = filtered_api
+            .servers
+            .iter()
+            .map(|server| server.url.as_str())
+            .collect();
+        assert_eq!(urls, vec!["https://api.example.com"]);
+    }
+
+    #[test]
+    fn it_resolves_relative_server_urls() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/relative-servers.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                server_base: Some(String::from("https://prod.example.com")),
+                ..Default::default()
+            })
+            .unwrap();
+        let urls = |servers: &Vec<openapiv3::Server>| -> Vec<String> {
+            servers.iter().map(|server| server.url.clone()).collect()
+        };
+        assert_eq!(
+            urls(&filtered_api.servers),
+            vec![
+                "https://prod.example.com/api",
+                "https://staging.example.com/api"
+            ]
+        );
+        let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert_eq!(
+            urls(&path.servers),
+            vec!["https://prod.example.com/pets-api"]
+        );
+        assert_eq!(
+            urls(&path.get.as_ref().unwrap().servers),
+            vec!["https://prod.example.com/read/v1"]
+        );
+    }
+
+    #[test]
+    fn it_keeps_schemas_referenced_by_example_components() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/example-references.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let components = filtered_api.components.unwrap();
+        let examples: Vec<&String> = components.examples.keys().collect();
+        assert_eq!(examples, vec!["PetListExample"]);
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Pet"]);
+    }
+
+    #[test]
+    fn it_filters_operations_by_scope_patterns() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/oauth-scopes.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                scopes: Some(vec![String::from("admin")]),
+                scope_globs: Some(vec![String::from("*:read")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let operations: Vec<(String, String)> = filtered_api
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_item()
+                    .unwrap()
+                    .iter()
+                    .map(|(method, _)| (path.clone(), method.to_string()))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                (String::from("/pets"), String::from("get")),
+                (String::from("/admin"), String::from("delete"))
+            ]
+        );
+    }
+
+    #[test]
+    fn it_prunes_unused_scopes_from_security_schemes() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/oauth-scopes.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                methods: Some(vec![String::from("get")]),
+                prune_scopes: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_json_snapshot!(filtered_api.components.unwrap().security_schemes);
+    }
+
+    #[test]
+    fn it_filters_selected_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                selection: Some(vec![
+                    (String::from("/pet/{petId}"), String::from("get")),
+                    (String::from("/store/order"), String::from("post")),
+                    (String::from("/pet/*"), String::from("get")),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+        let operations: Vec<(&String, Vec<&str>)> = filtered_api
+            .paths
+            .paths
+            .iter()
+            .map(|(path, item)| {
+                let methods = item.as_item().unwrap().iter().map(|(method, _)| method);
+                (path, methods.collect())
+            })
+            .collect();
+        assert_eq!(
+            operations,
+            vec![
+                (&String::from("/pet/{petId}"), vec!["get"]),
+                (&String::from("/store/order"), vec!["post"]),
+            ]
+        );
+        let schemas: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .keys()
+            .collect();
+        assert_eq!(schemas, vec!["Order", "Category", "Tag", "Pet"]);
+    }
+
+    #[test]
+    fn it_removes_request_bodies_from_methods_without_body_semantics() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/request-bodies.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                normalize_bodies: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert!(path.get.as_ref().unwrap().request_body.is_none());
+        assert!(path.delete.as_ref().unwrap().request_body.is_none());
+        assert!(path.post.as_ref().unwrap().request_body.is_some());
+        let components = filtered_api.components.unwrap();
+        let request_bodies: Vec<&String> = components.request_bodies.keys().collect();
+        assert_eq!(request_bodies, vec!["NewPet"]);
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Pet"]);
+    }
+
+    #[test]
+    fn it_disallows_additional_properties_in_strict_schemas() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/strict-schemas.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                strict_schemas: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let document = serde_json::to_value(&filtered_api).unwrap();
+        assert_eq!(
+            document.pointer(
+                "/paths/~1pets/post/requestBody/content/application~1json/schema/additionalProperties"
+            ),
+            Some(&json!(false))
+        );
+        let schemas = &document["components"]["schemas"];
+        // Pet is extended by Dog, so closing it would reject the breed of every dog
+        assert_eq!(schemas["Pet"].get("additionalProperties"), None);
+        assert_eq!(
+            schemas["Pet"]["properties"]["owner"]["additionalProperties"],
+            json!(false)
+        );
+        assert_eq!(schemas["Dog"].get("additionalProperties"), None);
+        assert_eq!(schemas["Dog"]["allOf"][1].get("additionalProperties"), None);
+    }
+
+    #[test]
+    fn it_removes_properties_of_the_other_schema_profile() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/schema-profiles.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let user = |schema_profile| {
+            let filtered_api = openapi
+                .filter_by_parameters(FilteringParameters {
+                    schema_profile: Some(schema_profile),
+                    ..Default::default()
+                })
+                .unwrap();
+            serde_json::to_value(&filtered_api).unwrap()["components"]["schemas"]["User"].clone()
+        };
+
+        let request = user(SchemaProfile::Request);
+        assert_eq!(request["required"], json!(["name", "password"]));
+        assert_eq!(request["properties"].get("id"), None);
+        let response = user(SchemaProfile::Response);
+        assert_eq!(response["required"], json!(["id", "name"]));
+        assert_eq!(response["properties"].get("password"), None);
+    }
+
+    #[test]
+    fn it_removes_links_to_filtered_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/links.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let link_names = |filtered_api: &OpenAPI| -> Vec<String> {
+            let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+            let response = path.post.as_ref().unwrap().responses.responses
+                [&openapiv3::StatusCode::Code(201)]
+                .as_item()
+                .unwrap();
+            response.links.keys().cloned().collect()
+        };
+
+        let all_methods = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/*")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            link_names(&all_methods),
+            vec!["GetPetById", "GetPetByRef", "DeletePet", "ExternalPet"]
+        );
+
+        let without_delete = openapi
+            .filter_by_parameters(FilteringParameters {
+                methods: Some(vec![String::from("post"), String::from("get")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            link_names(&without_delete),
+            vec!["GetPetById", "GetPetByRef", "ExternalPet"]
+        );
+        assert!(without_delete.components.unwrap().links.is_empty());
+
+        let only_post = openapi
+            .filter_by_parameters(FilteringParameters {
+                methods: Some(vec![String::from("post")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(link_names(&only_post), vec!["ExternalPet"]);
+    }
+
+    #[test]
+    fn it_keeps_extensions_of_paths_path_items_and_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/extensions.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                methods: Some(vec![String::from("get")]),
+                tags: Some(vec![String::from("pet")]),
+                security: Some(vec![String::from("api_key")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            filtered_api.paths.extensions["x-paths-owner"],
+            json!("platform")
+        );
+        let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert_eq!(path.extensions["x-path-owner"], json!("pets-team"));
+        assert!(path.post.is_none());
+        let operation = path.get.as_ref().unwrap();
+        assert_eq!(
+            operation.extensions["x-operation-owner"],
+            json!("pets-read")
+        );
+    }
+
+    #[test]
+    fn it_filters_operations_referencing_a_component_transitively() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filtered_api = openapi
+            .filter_by_parameters(FilteringParameters {
+                referenced_components: Some(vec![String::from("#/components/schemas/Category")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/pet",
+                "/pet/findByStatus",
+                "/pet/findByTags",
+                "/pet/{petId}"
+            ]
+        );
+        let pet_by_id = filtered_api.paths.paths["/pet/{petId}"].as_item().unwrap();
+        assert!(pet_by_id.get.is_some());
+        assert!(pet_by_id.delete.is_none());
+
+        // Address is only referenced by the Customer schema, which no operation uses
+        let filtered_api = openapi
+            .filter_by_parameters(FilteringParameters {
+                referenced_components: Some(vec![String::from("#/components/schemas/Address")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(filtered_api.paths.paths.is_empty());
+    }
+
+    #[test]
+    fn it_keeps_all_one_of_branches_without_discriminator() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/one-of.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let schemas: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .keys()
+            .collect();
+        assert_eq!(schemas, vec!["Cat", "Dog", "Lizard", "Collar"]);
+    }
+
+    #[test]
+    fn it_retains_components_matching_patterns() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/user/login")]),
+                retain_components: Some(vec![
+                    String::from("#/components/schemas/C*"),
+                    String::from("#/components/securitySchemes/api_key"),
+                ]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let components = filtered_api.components.as_ref().unwrap();
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Customer", "Address", "Category"]);
+        let schemes: Vec<&String> = components.security_schemes.keys().collect();
+        assert_eq!(schemes, vec!["api_key"]);
+    }
+
+    #[test]
+    fn it_reduces_kept_operations_to_stubs() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet/{petId}")]),
+                stub: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let document = serde_json::to_value(&filtered_api).unwrap();
+        assert_eq!(
+            document["paths"]["/pet/{petId}"]["get"],
+            json!({
+                "summary": "Find pet by ID",
+                "operationId": "getPetById",
+                "responses": {"default": {"description": ""}}
+            })
+        );
+        assert_eq!(document["components"], json!({}));
+        assert_eq!(document.get("tags"), None);
+    }
+
+    #[test]
+    fn it_stubs_reusable_path_items_and_drops_the_document_security() {
+        let stub = |file_name: &str| {
+            let openapi: Result<ParsedType<OpenAPI>, ParseError> =
+                parser::parse_document(&String::from(file_name), &ParseOptions::default());
+            let filtered_api = extract_content(openapi.unwrap())
+                .filter_by_parameters(FilteringParameters {
+                    paths: Some(vec![String::from("/pets"), String::from("/inherited")]),
+                    stub: true,
+                    ..Default::default()
+                })
+                .unwrap();
+            openapi31_fields::to_document_value(&filtered_api).unwrap()
+        };
+
+        let document = stub("tests/resources/inherited-security.yaml");
+        assert!(document.get("security").is_none());
+        assert!(document["components"].get("securitySchemes").is_none());
+
+        let document = stub("tests/resources/path-items-3.1.yaml");
+        assert_eq!(
+            serde_json::to_value(&document["paths"]["/pets"]).unwrap(),
+            json!({
+                "get": {
+                    "operationId": "listPets",
+                    "responses": {"default": {"description": ""}}
+                }
+            })
+        );
+        assert!(document["components"].get("pathItems").is_none());
+        assert!(document["components"].get("schemas").is_none());
+    }
+
+    #[test]
+    fn it_never_matches_operations_by_their_preserved_comments() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/comments.yaml"),
+            &ParseOptions {
+                preserve_comments: true,
+                ..Default::default()
+            },
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let grep = |pattern: &str| {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    grep: Some(String::from(pattern)),
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        assert!(grep("Lists pets").paths.paths.is_empty());
+        let filtered_api = grep("\"Pets\"");
+        let path = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert!(path.post.is_none());
+        assert_eq!(
+            path.get.as_ref().unwrap().extensions[yaml_comments::COMMENTS_EXTENSION],
+            json!(["# Lists pets"])
+        );
+    }
+
+    #[test]
+    fn it_excludes_operations_by_tag() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet"), String::from("/store/*")]),
+                exclude_tags: Some(vec![String::from("pet"), String::from("user")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let paths: Vec<&String> = filtered_api.paths.paths.keys().collect();
+        assert_eq!(
+            paths,
+            vec!["/store/inventory", "/store/order", "/store/order/{orderId}"]
+        );
+        let tags: Vec<&String> = filtered_api.tags.iter().map(|tag| &tag.name).collect();
+        assert_eq!(tags, vec!["store"]);
+
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/inherited-security.yaml"),
+            &ParseOptions::default(),
+        );
+        let untagged_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                exclude_tags: Some(vec![String::from("internal")]),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(untagged_api.paths.paths.len(), 4);
+    }
+
+    #[test]
+    fn it_filters_operations_by_request_body() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let operations = |has_request_body: bool| -> Vec<String> {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    paths: Some(vec![String::from("/pet*")]),
+                    has_request_body: Some(has_request_body),
+                    ..Default::default()
+                })
+                .unwrap()
+                .paths
+                .iter()
+                .flat_map(|(path, path_item)| {
+                    path_item
+                        .as_item()
+                        .unwrap()
+                        .iter()
+                        .map(move |(method, _)| format!("{} {}", method, path))
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            operations(true),
+            vec!["put /pet", "post /pet", "post /pet/{petId}/uploadImage"]
+        );
+        assert_eq!(
+            operations(false),
+            vec![
+                "get /pet/findByStatus",
+                "get /pet/findByTags",
+                "get /pet/{petId}",
+                "post /pet/{petId}",
+                "delete /pet/{petId}"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_dedupes_identical_schemas() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/duplicate-schemas.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                dedupe_schemas: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let schemas = &filtered.components.as_ref().unwrap().schemas;
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), vec!["Pet", "Error"]);
+
+        let document = serde_json::to_value(&filtered).unwrap();
+        assert_eq!(
+            document["paths"]["/animals"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["items"]["$ref"],
+            "#/components/schemas/Pet"
+        );
+    }
+
+    #[test]
+    fn it_inlines_schemas_referenced_once() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filtered = openapi
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pet")]),
+                inline_single_use_schemas: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let schemas = &filtered.components.as_ref().unwrap().schemas;
+        assert_eq!(schemas.keys().collect::<Vec<_>>(), vec!["Pet"]);
+
+        let pet = serde_json::to_value(&schemas["Pet"]).unwrap();
+        assert_eq!(
+            pet["properties"]["category"]["properties"]["name"]["example"],
+            "Dogs"
+        );
+        assert_eq!(pet["properties"]["tags"]["items"]["type"], "object");
+    }
+
+    #[test]
+    fn it_keeps_only_listed_responses_and_their_components() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/referenced-responses.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                keep_responses: Some(vec![String::from("DEFAULT")]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let responses = &filtered_api.paths.paths["/pets"]
+            .as_item()
+            .unwrap()
+            .post
+            .as_ref()
+            .unwrap()
+            .responses;
+        assert!(responses.responses.is_empty());
+        assert!(responses.default.is_some());
+        let components = filtered_api.components.as_ref().unwrap();
+        let response_names: Vec<&String> = components.responses.keys().collect();
+        assert_eq!(response_names, vec!["Error"]);
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Pet", "Error"]);
+        assert!(components.headers.is_empty());
+    }
+
+    #[test]
+    fn it_drops_path_parameters_unused_after_filtering() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/path-parameters.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filter = |drop_unused_path_parameters: bool, method: &str| {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    methods: Some(vec![String::from(method)]),
+                    drop_unused_path_parameters,
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let parameter_count = |filtered_api: &OpenAPI| {
+            filtered_api.paths.paths["/pets/{petId}"]
+                .as_item()
+                .unwrap()
+                .parameters
+                .len()
+        };
+
+        assert_eq!(parameter_count(&filter(false, "get")), 4);
+        let only_get = filter(true, "get");
+        let parameters = serde_json::to_value(
+            &only_get.paths.paths["/pets/{petId}"]
+                .as_item()
+                .unwrap()
+                .parameters,
+        )
+        .unwrap();
+        assert_eq!(
+            parameters,
+            json!([
+                {"$ref": "#/components/parameters/PetId"},
+                {"name": "verbose", "in": "query", "schema": {"type": "boolean"}, "style": "form"}
+            ])
+        );
+        let components = only_get.components.as_ref().unwrap();
+        assert!(!components.parameters.contains_key("Trace"));
+        assert!(components.schemas.is_empty());
+        assert_eq!(parameter_count(&filter(true, "delete")), 3);
+    }
+
+    #[test]
+    fn it_keeps_callbacks_referenced_by_kept_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/callbacks.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let components = filtered_api.components.as_ref().unwrap();
+        assert_eq!(
+            components.callbacks.keys().collect::<Vec<_>>(),
+            vec!["PetCreated"]
+        );
+        assert_eq!(
+            components.schemas.keys().collect::<Vec<_>>(),
+            vec!["Pet", "PetEvent", "ValidationEvent"]
+        );
+        assert!(components.responses.contains_key("Ack"));
+        let document = openapi31_fields::to_document_value(&filtered_api).unwrap();
+        let callbacks = &document["paths"]["/pets"]["post"]["callbacks"];
+        assert_eq!(
+            callbacks["petCreated"]["$ref"],
+            "#/components/callbacks/PetCreated"
+        );
+        assert!(callbacks["petValidated"].is_mapping());
+    }
+
+    #[test]
+    fn it_keeps_components_of_referenced_request_bodies_and_responses() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/referenced-responses.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let operation = filtered_api.paths.paths["/pets"]
+            .as_item()
+            .unwrap()
+            .post
+            .as_ref()
+            .unwrap();
+        assert!(matches!(
+            operation.request_body,
+            Some(ReferenceOr::Reference { .. })
+        ));
+        let components = filtered_api.components.unwrap();
+        let responses: Vec<&String> = components.responses.keys().collect();
+        assert_eq!(responses, vec!["Ok", "Error"]);
+        let request_bodies: Vec<&String> = components.request_bodies.keys().collect();
+        assert_eq!(request_bodies, vec!["NewPet"]);
+        let headers: Vec<&String> = components.headers.keys().collect();
+        assert_eq!(headers, vec!["RateLimit"]);
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Pet", "Error"]);
+    }
+
+    #[test]
+    fn it_keeps_header_components_referenced_from_responses() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/response-headers.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let components = filtered_api.components.unwrap();
+        let headers: Vec<&String> = components.headers.keys().collect();
+        assert_eq!(headers, vec!["RateLimit"]);
+        let schemas: Vec<&String> = components.schemas.keys().collect();
+        assert_eq!(schemas, vec!["Count"]);
+    }
+
+    #[test]
+    fn it_keeps_request_body_components_with_their_transitive_schemas() {
+        let openapi: OpenAPI = extract_content(
+            parser::parse_document(
+                &String::from("tests/resources/request-body-components.yaml"),
+                &ParseOptions::default(),
+            )
+            .unwrap(),
+        );
+        let kept_components = |method: &str| {
+            let components = openapi
+                .filter_by_parameters(FilteringParameters {
+                    methods: Some(vec![String::from(method)]),
+                    ..Default::default()
+                })
+                .unwrap()
+                .components
+                .unwrap();
+            let request_bodies: Vec<String> = components.request_bodies.into_keys().collect();
+            let schemas: Vec<String> = components.schemas.into_keys().collect();
+            (request_bodies, schemas)
+        };
+
+        assert_eq!(
+            kept_components("post"),
+            (
+                vec![String::from("CreatePet")],
+                vec![
+                    String::from("NewPet"),
+                    String::from("Pet"),
+                    String::from("Owner"),
+                    String::from("Address")
+                ]
+            )
+        );
+        // A request body component referencing another one keeps the referenced one and its schemas
+        assert_eq!(
+            kept_components("patch"),
+            (
+                vec![String::from("UpdatePet"), String::from("PatchPet")],
+                vec![String::from("PetUpdate")]
+            )
+        );
+    }
+
+    #[test]
+    fn it_matches_paths_without_trailing_slashes() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/trailing-slashes.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets/")]),
+                normalize_trailing_slash: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            filtered_api.paths.paths.keys().collect::<Vec<_>>(),
+            vec!["/pets"]
+        );
+        let pets = filtered_api.paths.paths["/pets"].as_item().unwrap();
+        assert!(pets.get.is_some() && pets.post.is_some());
+        let schemas: Vec<&String> = filtered_api
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .keys()
+            .collect();
+        assert_eq!(schemas, vec!["Pet", "NewPet"]);
+    }
+
+    #[test]
+    fn it_keeps_a_seeded_sample_of_the_matched_operations() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let sample = |seed: u64| {
+            openapi
+                .filter_by_parameters(FilteringParameters {
+                    tags: Some(vec![String::from("store")]),
+                    sample: Some(2),
+                    seed,
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+
+        let filtered_api = sample(3);
+        let operations: Vec<(&String, &str)> = filtered_api
+            .paths
+            .iter()
+            .flat_map(|(path_name, path)| {
+                path.as_item()
+                    .unwrap()
+                    .iter()
+                    .map(move |(method, _)| (path_name, method))
+            })
+            .collect();
+        assert_eq!(operations.len(), 2);
+        assert!(
+            operations
+                .iter()
+                .all(|(path_name, _)| path_name.starts_with("/store"))
+        );
+        assert_eq!(filtered_api, sample(3));
+        let document = serde_json::to_value(&filtered_api).unwrap();
+        assert!(
+            reference_collector::locate_dangling_refs(&document)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn it_keeps_components_referenced_with_escaped_names() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/escaped-component-names.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let schema_names = |path: &str| {
+            let filtered_api = openapi
+                .filter_by_parameters(FilteringParameters {
+                    paths: Some(vec![String::from(path)]),
+                    ..Default::default()
+                })
+                .unwrap();
+            let schemas: Vec<String> = filtered_api
+                .components
+                .unwrap()
+                .schemas
+                .into_keys()
+                .collect();
+            schemas
+        };
+
+        assert_eq!(schema_names("/orders"), vec!["Order/Detail", "Amount~EUR"]);
+        assert_eq!(schema_names("/customers"), vec!["Customer Record"]);
+    }
+
+    #[test]
+    fn it_returns_the_document_as_it_is_without_filters() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let parameters = FilteringParameters {
+            tag_match: TagMatch::All,
+            seed: 42,
+            strict_method_path: true,
+            allow_empty_responses: true,
+            ..Default::default()
+        };
+        assert!(parameters.is_empty());
+        assert_eq!(openapi.filter_by_parameters(parameters).unwrap(), openapi);
+        assert!(
+            !FilteringParameters {
+                first_server_only: true,
+                ..Default::default()
+            }
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn it_drops_matching_paths_without_the_methods() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let openapi = extract_content(openapi.unwrap());
+        let filter = |path: &str, strict_method_path: bool| {
+            openapi.filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from(path), String::from("/store/*")]),
+                methods: Some(vec![String::from("get")]),
+                strict_method_path,
+                ..Default::default()
+            })
+        };
+
+        let filtered_api = filter("/pet", false).unwrap();
+        assert!(!filtered_api.paths.paths.contains_key("/pet"));
+        assert!(filtered_api.paths.paths.contains_key("/store/inventory"));
+        assert!(filter("/pet/findByStatus", true).is_ok());
+        assert_eq!(
+            filter("/pet", true).unwrap_err().to_string(),
+            "Path '/pet' has no get operation"
+        );
+        assert_eq!(
+            filter("/pets", true).unwrap_err().to_string(),
+            "Path '/pets' has no get operation"
+        );
+    }
+
+    #[test]
+    fn it_keeps_examples_with_external_values() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/external-examples.yaml"),
+            &ParseOptions::default(),
+        );
+        let filtered_api = extract_content(openapi.unwrap())
+            .filter_by_parameters(FilteringParameters {
+                paths: Some(vec![String::from("/pets")]),
+                ..Default::default()
+            })
+            .unwrap();
+        let components = filtered_api.components.unwrap();
+        let examples: Vec<&String> = components.examples.keys().collect();
+        assert_eq!(examples, vec!["Pets"]);
+        let pets = components.examples["Pets"].as_item().unwrap();
+        assert_eq!(
+            pets.external_value.as_deref(),
+            Some("external-examples/pets.json")
+        );
+        assert!(pets.value.is_none());
     }
 
     fn extract_content<T>(parsed: ParsedType<T>) -> T {
@@ -0,0 +1,79 @@
+use openapiv3::OpenAPI;
+use std::fmt;
+
+/// Counts of the content kept in a filtered document
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FilterReport {
+    ///number of kept paths
+    pub paths: usize,
+    ///number of kept security schemes
+    pub security_schemes: usize,
+    ///number of kept component schemas
+    pub schemas: usize,
+}
+
+impl FilterReport {
+    /// Counts the content of a filtered document
+    ///
+    /// # Arguments
+    ///
+    /// * `openapi` - A reference to the filtered document.
+    ///
+    /// # Returns
+    ///
+    /// * `FilterReport` - The counts of the kept paths, security schemes and schemas.
+    pub fn from_document(openapi: &OpenAPI) -> Self {
+        let components = openapi.components.as_ref();
+        FilterReport {
+            paths: openapi.paths.paths.len(),
+            security_schemes: components.map_or(0, |components| components.security_schemes.len()),
+            schemas: components.map_or(0, |components| components.schemas.len()),
+        }
+    }
+}
+
+impl fmt::Display for FilterReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "kept {}, {}, {}",
+            count(self.paths, "path"),
+            count(self.security_schemes, "scheme"),
+            count(self.schemas, "schema")
+        )
+    }
+}
+
+/// Writes the count with the noun in singular or plural
+fn count(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::parser::{self, ParseOptions, ParsedType};
+
+    #[test]
+    fn it_counts_kept_content() {
+        let openapi: Result<ParsedType<OpenAPI>, ParseError> = parser::parse_document(
+            &String::from("tests/resources/petstore.yaml"),
+            &ParseOptions::default(),
+        );
+        let report = FilterReport::from_document(openapi.unwrap().content());
+
+        assert_eq!(report.to_string(), "kept 13 paths, 2 schemes, 8 schemas");
+        assert_eq!(
+            FilterReport {
+                paths: 1,
+                ..Default::default()
+            }
+            .to_string(),
+            "kept 1 path, 0 schemes, 0 schemas"
+        );
+    }
+}
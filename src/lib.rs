@@ -0,0 +1,56 @@
+//! Filters OpenAPI v3 documents, keeping only the content that matches the filters and the components it depends on.
+//!
+//! Documents are read with `parser::parse_document` and filtered with the `OpenAPIFilter` trait
+//! implemented for `openapiv3::OpenAPI`. Documents held in memory can be filtered with `filter_str`.
+//! Failures are reported as `ParseError` and `FilterError`, whose variants tell the kind of the failure.
+
+pub mod diagnostics;
+pub mod error;
+pub mod filter;
+pub mod parser;
+
+pub use error::{FilterError, ParseError};
+use filter::content::openapi31_fields;
+use filter::openapi::{FilteringParameters, OpenAPIFilter};
+use openapiv3::OpenAPI;
+use parser::{ParseOptions, ParsedType};
+
+/// Format of a document filtered with `filter_str`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+/// Filters a document held in a string without touching the filesystem or stdin.
+///
+/// The document is parsed like `parser::parse_document` parses files, filtered with the parameters and written back
+/// in the given format, or in the format detected from the input when no format is given.
+/// JSON is written compact on a single line, the same way as the command line tool writes it by default.
+///
+/// # Arguments
+///
+/// * `input` - A string slice holding the YAML or JSON document.
+/// * `format` - Format of the output, or None to use the format of the input.
+/// * `params` - The filters applied to the document.
+///
+/// # Returns
+///
+/// * `Result<String, FilterError>` - The filtered document, or an error telling why parsing, filtering or writing failed.
+pub fn filter_str(
+    input: &str,
+    format: Option<Format>,
+    params: FilteringParameters,
+) -> Result<String, FilterError> {
+    let parsed: ParsedType<OpenAPI> = parser::parse_str(input, &ParseOptions::default())?;
+    let format = format.unwrap_or(match parsed {
+        ParsedType::Json(_) => Format::Json,
+        ParsedType::Yaml(_) => Format::Yaml,
+    });
+    let filtered = parsed.content().filter_by_parameters(params)?;
+    let document = openapi31_fields::to_document_value(&filtered)?;
+    Ok(match format {
+        Format::Json => serde_json::to_string(&document)?,
+        Format::Yaml => serde_yaml::to_string(&document)?,
+    })
+}
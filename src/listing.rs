@@ -0,0 +1,401 @@
+use clap::ValueEnum;
+use indexmap::{IndexMap, IndexSet};
+use openapiv3::{OpenAPI, ReferenceOr, SecurityRequirement};
+use openapiv3_filter::filter::content::openapi31_fields::resolve_path_item;
+use openapiv3_filter::filter::content::reference_collector::{
+    DEFAULT_MAX_RECURSION_DEPTH, RecursionLimitExceeded, count_path_refs, escape_segment,
+};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// Dimension of the document whose distinct values are listed with `--list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListDimension {
+    /// Tags declared in the document root or used by the operations
+    Tags,
+    /// Path names
+    Paths,
+    /// Lowercase HTTP methods of the operations
+    Methods,
+    /// Security schemes declared in the components or required by the document root or the operations
+    Security,
+    /// Operation ids of the operations
+    OperationIds,
+}
+
+/// Lists the distinct values of a dimension of the document without filtering it.
+///
+/// Values are returned in the order they are first found in the document, so paths keep their input order.
+/// Operations of path items referenced from the components are included.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the parsed document.
+/// * `dimension` - The dimension whose values are listed.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The distinct values of the dimension.
+pub fn list_values(openapi: &OpenAPI, dimension: ListDimension) -> Vec<String> {
+    let mut values: IndexSet<String> = IndexSet::new();
+    match dimension {
+        ListDimension::Tags => {
+            values.extend(openapi.tags.iter().map(|tag| tag.name.clone()));
+        }
+        ListDimension::Paths => {
+            values.extend(openapi.paths.paths.keys().cloned());
+        }
+        ListDimension::Security => {
+            values.extend(security_names(&openapi.security));
+        }
+        ListDimension::Methods | ListDimension::OperationIds => {}
+    }
+    for path_item in openapi.paths.paths.values() {
+        let Some(path_item) = resolve_path_item(path_item, openapi.components.as_ref()) else {
+            continue;
+        };
+        for (method, operation) in path_item.iter() {
+            match dimension {
+                ListDimension::Tags => values.extend(operation.tags.iter().cloned()),
+                ListDimension::Methods => {
+                    values.insert(method.to_lowercase());
+                }
+                ListDimension::Security => values.extend(security_names(&operation.security)),
+                ListDimension::OperationIds => values.extend(operation.operation_id.clone()),
+                ListDimension::Paths => {}
+            }
+        }
+    }
+    if dimension == ListDimension::Security
+        && let Some(components) = &openapi.components
+    {
+        values.extend(components.security_schemes.keys().cloned());
+    }
+    values.into_iter().collect()
+}
+
+/// Counts the operations of each tag of the document.
+///
+/// Tags are listed in the order of the tags of the document root followed by the tags only used by operations, so
+/// declared tags without operations have the count `0`. Operations of path items referenced from the components are
+/// counted, while operations without tags are not.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the filtered document.
+///
+/// # Returns
+///
+/// * `IndexMap<String, usize>` - The number of operations by tag name.
+pub fn tag_histogram(openapi: &OpenAPI) -> IndexMap<String, usize> {
+    let mut counts: IndexMap<String, usize> = openapi
+        .tags
+        .iter()
+        .map(|tag| (tag.name.clone(), 0))
+        .collect();
+    for path_item in openapi.paths.paths.values() {
+        let Some(path_item) = resolve_path_item(path_item, openapi.components.as_ref()) else {
+            continue;
+        };
+        for (_, operation) in path_item.iter() {
+            // A tag listed twice on the same operation counts the operation once
+            let tags: IndexSet<&String> = operation.tags.iter().collect();
+            for tag in tags {
+                *counts.entry(tag.clone()).or_default() += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Lists the operations of the document as JSON Pointers, e.g. `/paths/~1pet~1{petId}/get`.
+///
+/// Operations of path items referenced from the components are addressed at the referenced path item,
+/// e.g. `/components/pathItems/Pets/get`, since JSON Pointers do not follow references. Operations of path items
+/// referenced from other documents, e.g. `other.yaml#/paths/~1pets`, are left out.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the filtered document.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The pointers of the operations in document order.
+pub fn operation_pointers(openapi: &OpenAPI) -> Vec<String> {
+    let mut pointers = Vec::new();
+    for (path_name, path_item) in &openapi.paths.paths {
+        let path_pointer = match path_item {
+            ReferenceOr::Reference { reference } => match reference.strip_prefix('#') {
+                Some(pointer) => pointer.to_string(),
+                // Path items of other documents have no pointer into this one
+                None => continue,
+            },
+            ReferenceOr::Item(_) => {
+                format!("/paths/{}", escape_segment(path_name))
+            }
+        };
+        let Some(path_item) = resolve_path_item(path_item, openapi.components.as_ref()) else {
+            continue;
+        };
+        for (method, _) in path_item.iter() {
+            pointers.push(format!("{}/{}", path_pointer, method));
+        }
+    }
+    pointers
+}
+
+/// Writes each component of the document as a JSON object with its JSON Pointer and definition, one object per line.
+///
+/// The objects are written as `{"pointer":"/components/schemas/Pet","definition":{...}}` in document order, so that
+/// a consumer can load the components one at a time. Component extensions such as `x-` keys are not components and are skipped.
+///
+/// # Arguments
+///
+/// * `document` - A reference to the JSON value of the filtered document.
+///
+/// # Returns
+///
+/// * `Vec<String>` - The newline-delimited JSON lines of the components.
+pub fn component_lines(document: &Value) -> Vec<String> {
+    let Some(Value::Object(components)) = document.get("components") else {
+        return Vec::new();
+    };
+    components
+        .iter()
+        .filter(|(category, _)| !category.starts_with("x-"))
+        .filter_map(|(category, definitions)| {
+            definitions
+                .as_object()
+                .map(|definitions| (category, definitions))
+        })
+        .flat_map(|(category, definitions)| {
+            definitions.iter().map(move |(name, definition)| {
+                json!({
+                    "pointer": format!("/components/{}/{}", category, escape_segment(name)),
+                    "definition": definition,
+                })
+                .to_string()
+            })
+        })
+        .collect()
+}
+
+/// Counts how many times each component of the document is referenced within the document.
+///
+/// Every component is listed as `{"#/components/schemas/Pet": 4, ...}` in document order, including the components
+/// that are not referenced at all. References from other components are counted as well. Security schemes are used by
+/// name instead of `$ref`, so their count is the number of security requirements naming them in the document root and
+/// the operations.
+///
+/// # Arguments
+///
+/// * `openapi` - A reference to the filtered document, used for the security requirements.
+/// * `document` - A reference to the JSON value of the filtered document.
+///
+/// # Returns
+///
+/// * `Result<String, RecursionLimitExceeded>` - The counts as a pretty printed JSON object, or an error if the document is nested too deep.
+pub fn component_ref_counts(
+    openapi: &OpenAPI,
+    document: &Value,
+) -> Result<String, RecursionLimitExceeded> {
+    let mut counts = HashMap::new();
+    count_path_refs(document, &mut counts, DEFAULT_MAX_RECURSION_DEPTH)?;
+    let components = openapi.components.as_ref();
+    let operation_security = openapi
+        .paths
+        .iter()
+        .filter_map(|(_, path_item)| resolve_path_item(path_item, components))
+        .flat_map(|path_item| {
+            path_item
+                .iter()
+                .flat_map(|(_, operation)| security_names(&operation.security))
+                .collect::<Vec<_>>()
+        });
+    for name in security_names(&openapi.security)
+        .into_iter()
+        .chain(operation_security)
+    {
+        *counts
+            .entry(format!(
+                "#/components/securitySchemes/{}",
+                escape_segment(&name)
+            ))
+            .or_default() += 1;
+    }
+    let mut ref_counts = Map::new();
+    for (category, definitions) in document
+        .get("components")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(category, _)| !category.starts_with("x-"))
+    {
+        for name in definitions.as_object().into_iter().flat_map(Map::keys) {
+            let pointer = format!("#/components/{}/{}", category, escape_segment(name));
+            let count = counts.get(&pointer).copied().unwrap_or_default();
+            ref_counts.insert(pointer, json!(count));
+        }
+    }
+    Ok(serde_json::to_string_pretty(&Value::Object(ref_counts))
+        .expect("Reference counts are always serializable"))
+}
+
+/// Returns the names of the security schemes of the security requirements
+fn security_names(security: &Option<Vec<SecurityRequirement>>) -> Vec<String> {
+    security
+        .iter()
+        .flatten()
+        .flat_map(|requirement| requirement.keys().cloned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openapiv3_filter::parser::{self, ParseOptions};
+
+    #[test]
+    fn it_lists_distinct_values_in_document_order() {
+        let openapi = parser::parse_document::<OpenAPI>(
+            "tests/resources/petstore.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap()
+        .content()
+        .clone();
+
+        assert_eq!(
+            list_values(&openapi, ListDimension::Tags),
+            vec!["pet", "store", "user"]
+        );
+        assert_eq!(
+            list_values(&openapi, ListDimension::Methods),
+            vec!["put", "post", "get", "delete"]
+        );
+        assert_eq!(
+            list_values(&openapi, ListDimension::Security),
+            vec!["petstore_auth", "api_key"]
+        );
+        assert_eq!(
+            list_values(&openapi, ListDimension::OperationIds)[..3],
+            ["updatePet", "addPet", "findPetsByStatus"]
+        );
+    }
+    #[test]
+    fn it_lists_operations_as_json_pointers() {
+        let mut openapi = parser::parse_document::<OpenAPI>(
+            "tests/resources/path-items-3.1.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap()
+        .content()
+        .clone();
+        openapi.paths.paths.insert(
+            String::from("/remote"),
+            ReferenceOr::Reference {
+                reference: String::from("other.yaml#/paths/~1pets"),
+            },
+        );
+
+        assert_eq!(
+            operation_pointers(&openapi),
+            vec![
+                "/components/pathItems/PetItem/get",
+                "/components/pathItems/OrderItem/get",
+                "/paths/~1users/get"
+            ]
+        );
+    }
+
+    #[test]
+    fn it_writes_components_as_json_lines() {
+        let document = json!({
+            "components": {
+                "schemas": {"Pet": {"type": "object"}, "pets/v1": {"type": "array"}},
+                "responses": {"NotFound": {"description": "Not found"}},
+                "x-internal": {"owner": "pets"}
+            }
+        });
+
+        assert_eq!(
+            component_lines(&document),
+            vec![
+                r#"{"pointer":"/components/schemas/Pet","definition":{"type":"object"}}"#,
+                r#"{"pointer":"/components/schemas/pets~1v1","definition":{"type":"array"}}"#,
+                r#"{"pointer":"/components/responses/NotFound","definition":{"description":"Not found"}}"#
+            ]
+        );
+        assert!(component_lines(&json!({"paths": {}})).is_empty());
+    }
+
+    #[test]
+    fn it_counts_references_to_each_component() {
+        let openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0.0"},
+            "security": [{"api_key": []}],
+            "paths": {"/pets": {
+                "get": {"responses": {"200": {"description": "Pets", "content": {"application/json": {
+                    "schema": {"type": "array", "items": {"$ref": "#/components/schemas/Pet"}}
+                }}}}},
+                "post": {
+                    "security": [{"api_key": []}],
+                    "requestBody": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}},
+                    "responses": {"201": {"description": "Created"}}
+                }
+            }},
+            "components": {
+                "schemas": {
+                    "Pet": {"type": "object", "properties": {"owner": {"$ref": "#/components/schemas/Owner"}}},
+                    "Owner": {"type": "object"},
+                    "Unused": {"type": "string"}
+                },
+                "securitySchemes": {"api_key": {"type": "apiKey", "in": "header", "name": "X-Key"}}
+            }
+        }))
+        .unwrap();
+        let document = serde_json::to_value(&openapi).unwrap();
+
+        let counts: Value =
+            serde_json::from_str(&component_ref_counts(&openapi, &document).unwrap()).unwrap();
+
+        assert_eq!(
+            counts,
+            json!({
+                "#/components/schemas/Pet": 2,
+                "#/components/schemas/Owner": 1,
+                "#/components/schemas/Unused": 0,
+                "#/components/securitySchemes/api_key": 2
+            })
+        );
+    }
+
+    #[test]
+    fn it_counts_operations_by_tag() {
+        let openapi: OpenAPI = serde_json::from_value(json!({
+            "openapi": "3.0.3",
+            "info": {"title": "Pets", "version": "1.0.0"},
+            "tags": [{"name": "store"}, {"name": "pet"}],
+            "paths": {
+                "/pets": {
+                    "get": {"tags": ["pet", "pet"], "responses": {}},
+                    "post": {"tags": ["pet", "admin"], "responses": {}}
+                },
+                "/health": {"get": {"responses": {}}}
+            }
+        }))
+        .unwrap();
+
+        let histogram = tag_histogram(&openapi);
+
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![
+                (String::from("store"), 0),
+                (String::from("pet"), 2),
+                (String::from("admin"), 1)
+            ]
+        );
+    }
+}
@@ -1,13 +1,104 @@
-mod filter;
-mod parser;
+mod config;
+mod deprecation;
+mod dialect;
+mod examples;
+mod exit_status;
+mod listing;
+mod selection;
+mod serializer;
 
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{Parser, ValueEnum};
 use openapiv3::OpenAPI;
 
-use crate::filter::openapi::{FilteringParameters, OpenAPIFilter};
-use parser::ParsedType;
+use crate::config::Config;
+use crate::deprecation::ReportFormat;
+use crate::dialect::SchemaDialect;
+use crate::exit_status::{EmptyResultError, ExitStatus, InvalidExamplesError};
+use crate::listing::ListDimension;
+use openapiv3_filter::FilterError;
+use openapiv3_filter::diagnostics;
+use openapiv3_filter::filter::content::example_validator::check_examples;
+use openapiv3_filter::filter::content::openapi31_fields;
+use openapiv3_filter::filter::content::reference_collector::{
+    find_dangling_refs, locate_dangling_refs,
+};
+use openapiv3_filter::filter::content::tag_group_resolver::resolve_tag_groups;
+use openapiv3_filter::filter::content::yaml_comments;
+use openapiv3_filter::filter::openapi::{
+    FilteringParameters, OpenAPIFilter, SchemaProfile, TagMatch,
+};
+use openapiv3_filter::filter::report::FilterReport;
+use openapiv3_filter::parser::{self, ParseOptions, ParsedType, SpecVersion};
+use serializer::{OutputFormat, YamlStyle};
+use std::fs;
 use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Methods kept by --read-only
+const READ_ONLY_METHODS: [&str; 3] = ["get", "head", "options"];
+
+/// Methods kept by --mutations
+const MUTATION_METHODS: [&str; 4] = ["post", "put", "patch", "delete"];
+
+/// Values of --tag-match, see `TagMatch`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum TagMatchArg {
+    /// Operations having at least one of the tags are kept
+    #[default]
+    Any,
+    /// Operations having every one of the tags are kept
+    All,
+}
+
+impl From<TagMatchArg> for TagMatch {
+    fn from(tag_match: TagMatchArg) -> Self {
+        match tag_match {
+            TagMatchArg::Any => TagMatch::Any,
+            TagMatchArg::All => TagMatch::All,
+        }
+    }
+}
+
+/// Values of --assume, see `SpecVersion`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SpecVersionArg {
+    /// OpenAPI 3.0, fields added in 3.1 are dropped when parsing
+    #[value(name = "openapi3")]
+    OpenApi3,
+    /// OpenAPI 3.1, reusable path items and webhooks are kept
+    #[value(name = "openapi3.1")]
+    OpenApi31,
+}
+
+impl From<SpecVersionArg> for SpecVersion {
+    fn from(version: SpecVersionArg) -> Self {
+        match version {
+            SpecVersionArg::OpenApi3 => SpecVersion::OpenApi3,
+            SpecVersionArg::OpenApi31 => SpecVersion::OpenApi31,
+        }
+    }
+}
+
+/// Values of --schema-profile, see `SchemaProfile`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SchemaProfileArg {
+    /// Properties marked `readOnly` are removed, leaving the request models
+    Request,
+    /// Properties marked `writeOnly` are removed, leaving the response models
+    Response,
+}
+
+impl From<SchemaProfileArg> for SchemaProfile {
+    fn from(profile: SchemaProfileArg) -> Self {
+        match profile {
+            SchemaProfileArg::Request => SchemaProfile::Request,
+            SchemaProfileArg::Response => SchemaProfile::Response,
+        }
+    }
+}
 
 #[derive(Parser, Default)]
 #[command(version,
@@ -29,7 +120,7 @@ use std::process::ExitCode;
 )]
 /// Filters document by matching specification paths
 struct Opts {
-    #[arg(help = "Input file or - for stdin", default_value = "-")]
+    #[arg(help = "Input file, http(s) URL or - for stdin", default_value = "-")]
     api_document: Option<String>,
     ///Matches the path name. Allows * wildcards in matching
     #[arg(
@@ -42,6 +133,22 @@ struct Opts {
             --path '*/pets' - Match all paths ending with /pets"
     )]
     path_names: Option<Vec<String>>,
+    #[arg(
+        long = "paths-file",
+        help = "file listing the paths to match one per line, with the same wildcards as --path. Lines starting with # are comments.\n\
+            The paths are combined with the --path arguments\n \
+            Examples:\n \
+            --paths-file 'paths.txt' - matches all the paths listed in paths.txt"
+    )]
+    paths_file: Option<String>,
+    #[arg(
+        long = "preview",
+        alias = "expand-path-globs-against-input",
+        help = "writes the path keys of the input document matched by each --path pattern to stderr before the output\n \
+            Examples:\n \
+            --path '*user*' --preview - shows which paths the pattern matches"
+    )]
+    preview: bool,
     #[arg(
         short = 'm',
         long = "method",
@@ -51,6 +158,35 @@ struct Opts {
             --method 'post' ----method 'get' - Matches both post and get methods in document"
     )]
     http_methods: Option<Vec<String>>,
+    #[arg(
+        long = "strict-method-path",
+        help = "fails when a path given with --path without wildcards has none of the methods given with --method, instead of silently dropping the path\n \
+            Examples:\n \
+            --path '/pet' --method 'get' --strict-method-path - fails because /pet has only put and post operations"
+    )]
+    strict_method_path: bool,
+    #[arg(
+        long = "read-only",
+        help = "keeps the read-only operations, i.e. get, head and options. Combined with --method and --mutations the methods of all are kept\n \
+            Examples:\n \
+            --read-only - keeps only the operations that do not modify data\n \
+            --read-only --method 'post' - keeps the read-only operations and post operations"
+    )]
+    read_only: bool,
+    #[arg(
+        long = "mutations",
+        help = "keeps the modifying operations, i.e. post, put, patch and delete. Combined with --method and --read-only the methods of all are kept\n \
+            Examples:\n \
+            --mutations - keeps only the operations that modify data"
+    )]
+    mutations: bool,
+    #[arg(
+        long = "has-request-body",
+        help = "keeps only the operations that declare a request body (true) or that do not declare one (false), regardless of the http method\n \
+            Examples:\n \
+            --has-request-body false --method 'post' - matches post operations without a request body"
+    )]
+    has_request_body: Option<bool>,
     #[arg(
         short,
         long = "tag",
@@ -60,6 +196,36 @@ struct Opts {
             --tag 'user_info' ----method 'collection' - Matches both user_info and collection tags in document"
     )]
     tags: Option<Vec<String>>,
+    #[arg(
+        long = "tag-group",
+        help = "tag group name from the x-tagGroups extension. Expands to all the tags listed in the group\n \
+            Examples:\n \
+            --tag-group 'payments' - matches all the tags grouped under payments in document\n \
+            --tag-group 'payments' --tag 'user_info' - Matches payments group tags and user_info tag in document"
+    )]
+    tag_groups: Option<Vec<String>>,
+    #[arg(
+        long = "exclude-tag",
+        help = "tag name whose operations are removed. Operations without tags are kept\n \
+            Examples:\n \
+            --exclude-tag 'internal' --exclude-tag 'beta' - matches all operations except the ones tagged internal or beta"
+    )]
+    exclude_tags: Option<Vec<String>>,
+    #[arg(
+        long = "tag-match",
+        value_enum,
+        default_value_t = TagMatchArg::Any,
+        help = "whether the kept operations need any or all of the tags given with --tag and --tag-group. Paths, methods and security are still matched independently\n \
+            Examples:\n \
+            --tag 'pet' --tag 'store' --tag-match 'all' - matches operations tagged with both pet and store"
+    )]
+    tag_match: TagMatchArg,
+    #[arg(
+        long = "require-all-tags",
+        conflicts_with = "tag_match",
+        help = "shorthand for --tag-match all"
+    )]
+    require_all_tags: bool,
     #[arg(
         short,
         long = "security",
@@ -69,6 +235,505 @@ struct Opts {
             --security 'api_key' ----security 'basic_auth' - Matches both api_key and basic_auth security definitions in document"
     )]
     security: Option<Vec<String>>,
+    #[arg(
+        long = "security-glob",
+        help = "security name pattern with * wildcards. Can be combined with exact --security names\n \
+            Examples:\n \
+            --security-glob 'oauth2_*' - matches API document content that uses oauth2_v1 or oauth2_v2 security definitions\n \
+            --security-glob 'oauth2_*' --security 'api_key' - Matches oauth2_ prefixed and api_key security definitions in document"
+    )]
+    security_globs: Option<Vec<String>>,
+    #[arg(
+        long = "scope",
+        help = "security requirement scope that is matched. Matches operations whose own or inherited security requirements list the scope for any scheme\n \
+            Examples:\n \
+            --scope 'pets:read' - matches operations that require the pets:read scope"
+    )]
+    scopes: Option<Vec<String>>,
+    #[arg(
+        long = "scope-glob",
+        help = "security requirement scope pattern with * wildcards. Can be combined with exact --scope names\n \
+            Examples:\n \
+            --scope-glob 'read:*' - matches operations that require any read: prefixed scope\n \
+            --scope-glob 'read:*' --scope 'admin' - Matches operations that require a read: prefixed or the admin scope"
+    )]
+    scope_globs: Option<Vec<String>>,
+    #[arg(
+        long = "param-in",
+        value_parser = ["query", "path", "header", "cookie"],
+        help = "parameter location. Matches operations that declare at least one parameter in the location\n \
+            Examples:\n \
+            --param-in 'query' - matches operations with query parameters in document\n \
+            --param-in 'path' --param-in 'header' - Matches operations with either path or header parameters in document"
+    )]
+    parameter_locations: Option<Vec<String>>,
+    #[arg(
+        long = "jsonpath",
+        alias = "select-by-jsonpath",
+        help = "JSONPath expression evaluated against each operation. Matches operations for which the expression yields a non-empty result\n \
+            Examples:\n \
+            --jsonpath \"$.responses.200.content['application/json']\" - matches operations with a JSON success response in document\n \
+            --jsonpath \"$.parameters[?@.in == 'query']\" --jsonpath '$.requestBody' - Matches operations with either query parameters or a request body in document"
+    )]
+    jsonpaths: Option<Vec<String>>,
+    #[arg(
+        long = "summary-glob",
+        alias = "operation-summary-glob",
+        help = "operation summary pattern with * wildcards. Matches operations whose summary matches any of the patterns\n \
+            Examples:\n \
+            --summary-glob '*internal*' - matches operations with internal in their summary"
+    )]
+    summary_globs: Option<Vec<String>>,
+    #[arg(
+        long = "grep",
+        alias = "keep-path-items-matching",
+        help = "keeps only the operations whose JSON serialization matches the regular expression anywhere, as a last-resort filter.\n\
+            References are not followed, so only the operation itself is searched\n \
+            Examples:\n \
+            --grep '\"ssn\"' - matches the operations mentioning a property or parameter named ssn"
+    )]
+    grep: Option<String>,
+    #[arg(
+        long = "references-component",
+        alias = "include-operations-referencing",
+        help = "keeps only the operations that reference the component directly or through other components\n \
+            Examples:\n \
+            --references-component '#/components/schemas/Address' - keeps every operation whose schemas use the Address schema"
+    )]
+    referenced_components: Option<Vec<String>>,
+    #[arg(
+        long = "retain-components",
+        help = "always keeps the components whose #/components/... pointer matches the wildcard pattern, along with the components they reference\n \
+            Examples:\n \
+            --retain-components '#/components/schemas/Common*' - keeps every schema whose name starts with Common in each filtered document"
+    )]
+    retain_components: Option<Vec<String>>,
+    #[arg(
+        long = "selection-file",
+        help = "JSON file listing the exact operations to keep as an array of {\"path\": ..., \"method\": ...} objects.\n\
+            The selection bypasses pattern matching, so it cannot be combined with options such as --path, --tag or --method\n \
+            Examples:\n \
+            --selection-file 'selection.json' - keeps only the operations listed in selection.json"
+    )]
+    selection_file: Option<String>,
+    #[arg(
+        long = "sample",
+        alias = "sample-output",
+        help = "keeps only a random sample of this many of the matched operations, together with the components they use. Useful for creating small test fixtures from large documents\n \
+            Examples:\n \
+            --tag 'pet' --sample 3 --seed 42 - keeps the same 3 random pet operations on every run"
+    )]
+    sample: Option<usize>,
+    #[arg(
+        long = "seed",
+        requires = "sample",
+        help = "seed of the random --sample, the same seed always gives the same sample. Without a seed the sample differs between runs"
+    )]
+    seed: Option<u64>,
+    #[arg(
+        long = "prune-schema-examples",
+        help = "removes example and examples keywords from the schemas in components. Examples in paths are kept"
+    )]
+    prune_schema_examples: bool,
+    #[arg(
+        long = "ensure-operation-ids",
+        help = "generates an operationId such as get_pet_petId from the method and path for the kept operations without one.\n\
+            Generated ids colliding with other ids get a numeric suffix. Existing operationIds are kept"
+    )]
+    ensure_operation_ids: bool,
+    #[arg(
+        long = "ensure-default-response",
+        alias = "assume-default-response",
+        help = "adds a default response with the description 'Unexpected error' to the kept operations without any response.\n\
+            Operations that have responses are kept as they are"
+    )]
+    ensure_default_response: bool,
+    #[arg(
+        long = "normalize-keys",
+        alias = "trim-whitespace-keys",
+        help = "trims leading and trailing whitespace from path keys, tag names and security scheme names before filtering.\n\
+            The trimmed names are also written to the output"
+    )]
+    normalize_keys: bool,
+    #[arg(
+        long = "normalize-trailing-slash",
+        help = "removes trailing slashes from the path keys before matching and in the output, merging e.g. /pets/ into /pets.\n\
+            Fails when the merged paths declare the same operation. Without it the path keys are kept verbatim\n \
+            Examples:\n \
+            --normalize-trailing-slash --path '/pets' - matches both /pets and /pets/ and writes them as /pets"
+    )]
+    normalize_trailing_slash: bool,
+    #[arg(
+        long = "tag-from-path",
+        help = "tags the operations without tags by the first segment of their path before filtering and adds the missing root tags.\n \
+            Examples:\n \
+            --tag-from-path --tag 'pets' - matches the untagged operations of /pets/... paths and the operations tagged pets"
+    )]
+    tag_from_path: bool,
+    #[arg(
+        long = "simplify-schemas",
+        alias = "flatten-single-allof",
+        help = "unwraps allOf compositions that have a single inline subschema in the kept schemas.\n\
+            Compositions with several subschemas or a $ref subschema are left as they are"
+    )]
+    simplify_schemas: bool,
+    #[arg(
+        long = "merge-allof",
+        help = "flattens single-level allOf compositions of the kept schemas in components into single object schemas,\n\
+            combining their properties, required lists and types. The last of conflicting property definitions is kept with a warning"
+    )]
+    merge_allof: bool,
+    #[arg(
+        long = "rename-extension",
+        alias = "output-extension-map",
+        value_parser = parse_extension_rename,
+        help = "renames an extension key throughout the filtered document, given as FROM=TO. Both keys need the x- prefix\n \
+            Examples:\n \
+            --rename-extension 'x-enum=x-ms-enum' - renames x-enum keys of the root, operations and schemas to x-ms-enum\n \
+            --rename-extension 'x-enum=x-ms-enum' --rename-extension 'x-nullable=x-ms-nullable' - applies both renames"
+    )]
+    extension_renames: Vec<(String, String)>,
+    #[arg(
+        long = "keep-responses",
+        alias = "keep-response-only",
+        value_delimiter = ',',
+        help = "keeps only the responses with the listed status codes in the kept operations and removes the components used only by the other responses\n \
+            Examples:\n \
+            --keep-responses '200,default' - keeps the 200 and default responses of every kept operation"
+    )]
+    keep_responses: Option<Vec<String>>,
+    #[arg(
+        long = "allow-empty-responses",
+        help = "keeps the operations with an empty responses object without warning about them.\n\
+            Without it each kept operation with responses: {} is reported as a warning, since OpenAPI requires at least one response"
+    )]
+    allow_empty_responses: bool,
+    #[arg(
+        long = "stub",
+        help = "reduces the kept operations to their operationId, summary and an empty default response for mocking scaffolds.\n\
+            Parameters, request bodies, responses, tags and security requirements are removed, so no components are kept"
+    )]
+    stub: bool,
+    #[arg(
+        long = "drop-path-parameters-unused-after-filter",
+        help = "removes the path level parameters that none of the kept operations of the path uses.\n\
+            Path parameters are kept when the path template contains them, other parameters when a kept operation does not override them"
+    )]
+    drop_unused_path_parameters: bool,
+    #[arg(
+        long = "dedupe-schemas",
+        alias = "dedupe-identical-schemas",
+        help = "replaces the kept component schemas that are identical to an earlier kept schema, apart from their descriptions, with that schema.\n\
+            The references to the removed schemas are rewritten and each replacement is reported on stderr"
+    )]
+    dedupe_schemas: bool,
+    #[arg(
+        long = "inline-single-use-schemas",
+        alias = "collapse-enums",
+        help = "inlines the kept component schemas that are referenced exactly once into their usage site and removes them from components.\n\
+            Schemas referenced more than once, recursive schemas and schemas named in discriminator mappings stay as components"
+    )]
+    inline_single_use_schemas: bool,
+    #[arg(
+        long = "schema-name-prefix",
+        help = "prefixes the names of the kept schemas in components and rewrites the references to them throughout the document\n \
+            Examples:\n \
+            --schema-name-prefix 'Petstore' - renames the Error schema to PetstoreError and its references to #/components/schemas/PetstoreError"
+    )]
+    schema_name_prefix: Option<String>,
+    #[arg(
+        long = "schema-profile",
+        value_enum,
+        help = "removes the properties of the kept component schemas that only belong to the other side of the API, along with their required entries.\n\
+            request removes readOnly properties and response removes writeOnly properties\n \
+            Examples:\n \
+            --schema-profile 'request' - keeps the schemas as request models without server generated properties such as id"
+    )]
+    schema_profile: Option<SchemaProfileArg>,
+    #[arg(
+        long = "strict-schemas",
+        help = "adds additionalProperties: false to the kept object schemas that do not specify additionalProperties.\n\
+            Schemas using allOf, oneOf or anyOf and their direct subschemas, including referenced component schemas, are left open"
+    )]
+    strict_schemas: bool,
+    #[arg(
+        long = "schema-dialect",
+        value_enum,
+        help = "rewrites the kept component and inline schemas to the JSON Schema dialect, for OpenAPI 3.1 consumers.\n\
+            2020-12 turns nullable: true into a null type, example into examples and boolean exclusiveMinimum and exclusiveMaximum into numbers\n \
+            Examples:\n \
+            --schema-dialect '2020-12' - turns type: string with nullable: true into type: [string, 'null']"
+    )]
+    schema_dialect: Option<SchemaDialect>,
+    #[arg(
+        long = "keep-server-description",
+        alias = "select-servers-by-description",
+        help = "keeps only the servers whose description matches the wildcard pattern, ignoring case, at the document, path and operation levels.\n\
+            Servers without a description are removed\n \
+            Examples:\n \
+            --keep-server-description 'prod*' - keeps the servers described as production and removes e.g. staging servers"
+    )]
+    server_descriptions: Option<Vec<String>>,
+    #[arg(
+        long = "first-server-only",
+        alias = "keep-first-server-only",
+        help = "keeps only the first server of the document root and of each path item and operation declaring servers.\n\
+            Useful when generating a client for a single environment"
+    )]
+    first_server_only: bool,
+    #[arg(
+        long = "server-base",
+        alias = "resolve-relative-server-urls",
+        help = "rewrites relative server URLs of the document root, path items and operations to absolute ones by prefixing them with the base URL.\n\
+            Absolute URLs are kept as they are\n \
+            Examples:\n \
+            --server-base https://prod.example.com - turns the server /api into https://prod.example.com/api"
+    )]
+    server_base: Option<String>,
+    #[arg(
+        long = "embed-base-path",
+        alias = "base-path-from-server",
+        help = "moves the base path of the server URLs to the start of the kept path keys, e.g. https://api.example.com/v2 and /pets become https://api.example.com and /v2/pets.\n\
+            Fails when the servers have different base paths"
+    )]
+    embed_base_path: bool,
+    #[arg(
+        long = "prune-server-variables",
+        help = "removes server variables that are not used as {name} placeholders in the URL of their server"
+    )]
+    prune_server_variables: bool,
+    #[arg(
+        long = "prune-scopes",
+        alias = "strip-unused-security-scopes",
+        help = "removes the OAuth2 scopes that no kept operation or the document root requires from the kept security schemes"
+    )]
+    prune_scopes: bool,
+    #[arg(
+        long = "normalize-bodies",
+        help = "removes requestBody from get, head, delete and trace operations, which have no request body semantics"
+    )]
+    normalize_bodies: bool,
+    #[arg(
+        long = "env-substitute",
+        help = "replaces ${VAR} placeholders in the input document with the values of the environment variables before parsing it.\n\
+            Fails when a referenced variable is not defined\n \
+            Examples:\n \
+            --env-substitute - reads 'url: https://${API_HOST}' as 'url: https://api.example.com' when API_HOST=api.example.com"
+    )]
+    env_substitute: bool,
+    #[arg(
+        long = "allow-missing-env",
+        requires = "env_substitute",
+        help = "leaves the placeholders of undefined environment variables as they are instead of failing with --env-substitute"
+    )]
+    allow_missing_env: bool,
+    #[arg(
+        long = "assume",
+        value_enum,
+        help = "handles the input document as this OpenAPI version instead of the version in its openapi field.\n\
+            OpenAPI 3.1 webhooks and reusable path items are kept only when the document is handled as openapi3.1\n \
+            Examples:\n \
+            --assume 'openapi3.1' - keeps the webhooks and pathItems of a piped document declaring another version"
+    )]
+    assume_version: Option<SpecVersionArg>,
+    #[arg(
+        long = "format",
+        value_enum,
+        help = "format of the output document, defaults to the format of the input document.\n\
+            toml is experimental and fails on documents with null values, which TOML cannot represent\n \
+            Examples:\n \
+            --format 'json' - writes a YAML input document as JSON"
+    )]
+    format: Option<OutputFormat>,
+    #[arg(
+        long = "preserve-comments",
+        help = "keeps the comments written above the path and operation keys of a YAML input document in the YAML output.\n\
+            Best effort: only full-line comments directly above the keys of the kept paths and operations are written back"
+    )]
+    preserve_comments: bool,
+    #[arg(
+        long = "yaml-style",
+        value_enum,
+        default_value_t = YamlStyle::Block,
+        help = "collection style used when the output is written as YAML\n \
+            Examples:\n \
+            --yaml-style 'block' - writes indented block collections (default)\n \
+            --yaml-style 'flow' - writes top-level keys on their own lines with inline flow collections as values"
+    )]
+    yaml_style: YamlStyle,
+    #[arg(
+        long = "indent",
+        value_parser = clap::value_parser!(u8).range(1..=16),
+        help = "number of spaces per nesting level of the output. JSON output is pretty printed instead of written on a single line\n \
+            Examples:\n \
+            --indent 4 - indents nested YAML collections and JSON objects by four spaces"
+    )]
+    indent: Option<u8>,
+    #[arg(
+        long = "oneline",
+        conflicts_with = "indent",
+        help = "writes the output document on a single line, as compact JSON or as a YAML flow mapping, for pasting into chats and tickets.\n\
+            Readability suffers and large documents make very long lines, so this suits small filtered results. Comments are not kept\n \
+            Examples:\n \
+            --path '/pet/{petId}' --method 'get' --oneline - writes the single operation document on one line"
+    )]
+    oneline: bool,
+    #[arg(
+        long = "filter-file",
+        help = "TOML, plain text (.txt) or YAML file containing default filtering parameters\n\
+            Parameters given as arguments take precedence over the OPENAPIV3_FILTER_PATH, _METHOD, _TAG and _SECURITY\n\
+            environment variables, which take precedence over the filter file, which takes precedence over\n\
+            an openapiv3-filter.toml or openapiv3-filter.yaml file discovered from the working directory\n \
+            Examples:\n \
+            --filter-file 'filters.toml' - reads paths, methods, tags, tag-groups and security lists from filters.toml\n \
+            --filter-file 'filters.txt' - reads path:/pets/* and tag:users lines from filters.txt, ignoring # comment lines"
+    )]
+    filter_file: Option<String>,
+    #[arg(
+        long = "fail-on-empty",
+        help = "exits with status 4 instead of writing the document when filtering removes all the paths"
+    )]
+    fail_on_empty: bool,
+    #[arg(
+        long = "check-input",
+        alias = "fail-on-dangling-input-refs",
+        help = "checks before filtering that every local $ref of the input document resolves, and exits with status 2 listing the first dangling references and their locations otherwise"
+    )]
+    check_input: bool,
+    #[arg(
+        long = "check-examples",
+        alias = "validate-examples-against-schema",
+        help = "checks after filtering that the kept examples conform to their schemas and writes the mismatches to stderr.\n\
+            Media type, parameter and header examples are checked against their schema and schema examples against the schema itself"
+    )]
+    check_examples: bool,
+    #[arg(
+        long = "strict",
+        requires = "check_examples",
+        help = "exits with status 1 listing the first mismatching examples instead of writing the document when --check-examples finds mismatches"
+    )]
+    strict: bool,
+    #[arg(
+        short,
+        long = "quiet",
+        help = "suppresses the warnings and the summary of the kept content written to stderr. Errors are still printed"
+    )]
+    quiet: bool,
+    #[arg(
+        long = "list",
+        value_enum,
+        conflicts_with = "input_glob",
+        help = "prints the distinct values of the dimension found in the input document, one per line, instead of filtering it\n \
+            Examples:\n \
+            --list 'tags' - prints the tags that can be used with --tag\n \
+            --list 'paths' - prints the path names that can be used with --path"
+    )]
+    list: Option<ListDimension>,
+    #[arg(
+        long = "deprecation-report",
+        alias = "only-deprecated-report",
+        value_enum,
+        help = "writes a report of the deprecated operations and schemas of the filtered document instead of the document.\n\
+            The report lists paths, methods, operationIds, schema names and x-sunset extension values\n \
+            Examples:\n \
+            --deprecation-report 'table' --tag 'pet' - prints a table of the deprecated pet operations and the deprecated schemas they use"
+    )]
+    deprecation_report: Option<ReportFormat>,
+    #[arg(
+        long = "json-pointer-paths",
+        conflicts_with = "deprecation_report",
+        help = "writes the kept operations as JSON Pointers, one per line, instead of the document\n \
+            Examples:\n \
+            --json-pointer-paths --tag 'pet' - prints pointers such as /paths/~1pet~1{petId}/get for the pet operations"
+    )]
+    json_pointer_paths: bool,
+    #[arg(
+        long = "fetch-external-examples",
+        help = "fetches the examples given as an externalValue URL or file and inlines them as the value of the example.\n\
+            Relative locations are resolved against the input document. Without it externalValue examples are kept as they are\n \
+            Examples:\n \
+            --fetch-external-examples --extract-examples - includes the external examples in the extracted response examples"
+    )]
+    fetch_external_examples: bool,
+    #[arg(
+        long = "timeout",
+        value_name = "SECONDS",
+        default_value_t = 30,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "maximum number of seconds a fetch of the input document or an external example from a URL may take, including reading the response, at least 1.\n\
+            A fetch taking longer fails with an I/O error and exit code 3, so that a hung server does not stall e.g. a CI pipeline\n \
+            Examples:\n \
+            --timeout 5 https://api.example.com/openapi.yaml - fails when the document is not fetched in 5 seconds"
+    )]
+    timeout: u64,
+    #[arg(
+        long = "extract-examples",
+        alias = "select-examples",
+        conflicts_with_all = ["deprecation_report", "json_pointer_paths"],
+        help = "writes the response examples of the kept operations as a JSON object keyed by path, method, status code and media type instead of the document.\n\
+            References to the examples and responses of the components are resolved\n \
+            Examples:\n \
+            --extract-examples --tag 'pet' - prints the response examples of the pet operations for a mock server"
+    )]
+    extract_examples: bool,
+    #[arg(
+        long = "ndjson-components",
+        conflicts_with_all = ["deprecation_report", "json_pointer_paths", "extract_examples"],
+        help = "writes each kept component as a JSON object with its JSON Pointer and definition, one per line, instead of the document\n \
+            Examples:\n \
+            --ndjson-components --tag 'pet' - prints lines such as {\"pointer\":\"/components/schemas/Pet\",\"definition\":{...}}"
+    )]
+    ndjson_components: bool,
+    #[arg(
+        long = "count-refs",
+        conflicts_with_all = ["deprecation_report", "json_pointer_paths", "extract_examples", "ndjson_components"],
+        help = "writes how many times each kept component is referenced within the filtered document as a JSON object instead of the document\n \
+            Examples:\n \
+            --count-refs --tag 'pet' - prints counts such as {\"#/components/schemas/Pet\": 4} for the components of the pet operations"
+    )]
+    count_refs: bool,
+    #[arg(
+        long = "tag-histogram",
+        alias = "operation-count-threshold-per-tag",
+        conflicts_with_all = ["deprecation_report", "json_pointer_paths", "extract_examples", "ndjson_components", "count_refs"],
+        help = "writes the number of kept operations of each tag as a JSON object instead of the document\n \
+            Examples:\n \
+            --tag-histogram - prints counts such as {\"pet\": 8, \"store\": 4}"
+    )]
+    tag_histogram: bool,
+    #[arg(
+        long = "min-tag-operations",
+        requires = "tag_histogram",
+        help = "warns about the tags of the --tag-histogram with fewer operations than this"
+    )]
+    min_tag_operations: Option<usize>,
+    #[arg(
+        long = "max-tag-operations",
+        requires = "tag_histogram",
+        help = "warns about the tags of the --tag-histogram with more operations than this"
+    )]
+    max_tag_operations: Option<usize>,
+    #[arg(
+        long = "input-glob",
+        requires = "output_dir",
+        conflicts_with = "api_document",
+        help = "filters every file matching the glob pattern with the same parameters instead of a single input document\n \
+            Examples:\n \
+            --input-glob 'specs/*.yaml' --output-dir 'filtered' - writes the filtered specs/*.yaml files to the filtered directory"
+    )]
+    input_glob: Option<String>,
+    #[arg(
+        long = "output-dir",
+        requires = "input_glob",
+        help = "directory the documents filtered with --input-glob are written to. File names of the input documents are kept"
+    )]
+    output_dir: Option<String>,
+    #[arg(
+        long = "fail-fast",
+        requires = "input_glob",
+        help = "stops filtering the --input-glob documents at the first document that fails"
+    )]
+    fail_fast: bool,
 }
 
 impl Opts {
@@ -76,64 +741,606 @@ impl Opts {
         // Check if stdin has data
         let has_stdin_data = !io::stdin().is_terminal();
         // If no stdin data, use parse() which shows help on no args
-        // If there is stdin data, use try_parse() which doesn't show help
+        // If there is stdin data, running without any arguments passes the piped document through
+        // unchanged instead of showing help, while invalid arguments are still reported
         let opts = if has_stdin_data {
             match Self::try_parse() {
                 Ok(opts) => opts,
-                Err(_) => Self {
-                    api_document: Some(String::from("-")),
-                    ..Default::default()
-                },
+                Err(error)
+                    if error.kind() == ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand =>
+                {
+                    Self::parse_from([env!("CARGO_PKG_NAME"), "-"])
+                }
+                Err(error) => exit_with_usage_error(error),
             }
         } else {
-            Self::parse()
+            Self::try_parse().unwrap_or_else(|error| exit_with_usage_error(error))
         };
 
-        Ok(opts)
+        opts.with_method_presets().apply_config()
+    }
+
+    /// Adds the methods of the --read-only and --mutations presets to the methods given with --method
+    fn with_method_presets(self) -> Self {
+        let presets = [
+            (self.read_only, READ_ONLY_METHODS.as_slice()),
+            (self.mutations, MUTATION_METHODS.as_slice()),
+        ];
+        if presets.iter().all(|(enabled, _)| !enabled) {
+            return self;
+        }
+        let mut methods = self.http_methods.clone().unwrap_or_default();
+        for method in presets
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .flat_map(|(_, methods)| methods.iter())
+        {
+            if !methods.iter().any(|existing| existing == method) {
+                methods.push(method.to_string());
+            }
+        }
+        Self {
+            http_methods: Some(methods),
+            ..self
+        }
+    }
+
+    /// Options applied to the raw input documents before parsing them
+    fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            substitute_env: self.env_substitute,
+            allow_missing_env: self.allow_missing_env,
+            assume_version: self.assume_version.map(SpecVersion::from),
+            preserve_comments: self.preserve_comments,
+            timeout: Some(Duration::from_secs(self.timeout)),
+        }
+    }
+
+    /// Fills the filtering parameters missing from the arguments from the environment variables, the explicit filter file and the discovered configuration file
+    fn apply_config(self) -> Result<Self, Box<dyn std::error::Error>> {
+        let explicit_config = match &self.filter_file {
+            Some(filter_file) => config::load_config(Path::new(filter_file))?,
+            None => Config::default(),
+        };
+        let discovered_config = match config::discover_config(&std::env::current_dir()?) {
+            Some(config_file) => config::load_config(&config_file)?,
+            None => Config::default(),
+        };
+        let config = Config {
+            paths: self.path_names,
+            methods: self.http_methods,
+            tags: self.tags,
+            tag_groups: self.tag_groups,
+            security: self.security,
+        }
+        .or(config::env_config(|name| std::env::var(name).ok()))
+        .or(explicit_config)
+        .or(discovered_config);
+
+        Ok(Self {
+            path_names: config.paths,
+            http_methods: config.methods,
+            tags: config.tags,
+            tag_groups: config.tag_groups,
+            security: config.security,
+            ..self
+        })
     }
 }
 
 fn main() -> ExitCode {
     // Use our custom parse_args instead of the default parse()
-    let opts = Opts::parse_args().expect("Argument parsing failed");
-
-    let Opts {
-        api_document,
-        path_names,
-        http_methods,
-        tags,
-        security,
-    } = opts;
-    let document: Result<ParsedType<OpenAPI>, Box<dyn (std::error::Error)>> =
-        parser::parse_document(&api_document.expect("Could not parse input document paremeter"));
-    match document {
-        Ok(openapi) => match openapi {
-            ParsedType::Json(val) => {
-                let res = val.filter_by_parameters(FilteringParameters {
-                    paths: (path_names).clone(),
-                    methods: (http_methods).clone(),
-                    tags: (tags).clone(),
-                    security: (security),
-                });
-                let text_res = serde_json::to_string(&res.unwrap()).unwrap();
-                println!("{}", text_res);
-                ExitCode::SUCCESS
+    let opts = match Opts::parse_args() {
+        Ok(opts) => opts,
+        Err(error) => return report_error(error.as_ref()),
+    };
+    diagnostics::set_quiet(opts.quiet);
+
+    if let Some(input_glob) = &opts.input_glob {
+        return filter_documents(input_glob, &opts);
+    }
+    let api_document = opts
+        .api_document
+        .as_deref()
+        .expect("Could not parse input document paremeter");
+    if let Some(dimension) = opts.list {
+        return match parser::parse_document::<OpenAPI>(api_document, &opts.parse_options()) {
+            Ok(openapi) => {
+                for value in listing::list_values(openapi.content(), dimension) {
+                    println!("{}", value);
+                }
+                ExitStatus::Success.into()
             }
-            ParsedType::Yaml(val) => {
-                let res = val.filter_by_parameters(FilteringParameters {
-                    paths: (path_names).clone(),
-                    methods: (http_methods).clone(),
-                    tags: (tags).clone(),
-                    security: (security),
-                });
-                let text_res = serde_yaml::to_string(&res.unwrap()).unwrap();
-                println!("{}", text_res);
-                ExitCode::SUCCESS
+            Err(error) => report_error(&error),
+        };
+    }
+    match filter_document(api_document, &opts) {
+        Ok(text_res) => {
+            println!("{}", text_res);
+            ExitStatus::Success.into()
+        }
+        Err(error) => report_error(error.as_ref()),
+    }
+}
+
+/// Returns a seed for --sample derived from the current time, used when no --seed is given
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+/// Parses, filters and serializes a single document with the filtering parameters of the arguments
+///
+/// # Arguments
+///
+/// * `api_document` - Input file, URL or - for stdin
+/// * `opts` - Parsed arguments
+///
+/// # Returns
+///
+/// * `Result<String, Box<dyn std::error::Error>>` - The filtered document in the format of the input document, or the error that stopped the processing
+fn filter_document(api_document: &str, opts: &Opts) -> Result<String, Box<dyn std::error::Error>> {
+    let openapi: ParsedType<OpenAPI> = parser::parse_document(api_document, &opts.parse_options())?;
+    if opts.check_input {
+        let input = openapi31_fields::to_document_value(openapi.content())?;
+        let references = locate_dangling_refs(&serde_json::to_value(&input)?)?;
+        if !references.is_empty() {
+            return Err(Box::new(FilterError::DanglingRefs { references }));
+        }
+    }
+    let paths = merge_paths_file(opts.path_names.clone(), opts.paths_file.as_deref())?;
+    let tags = merge_tag_groups(
+        openapi.content(),
+        opts.tags.clone(),
+        opts.tag_groups.clone(),
+    )?;
+    let selection = opts
+        .selection_file
+        .as_deref()
+        .map(|selection_file| selection::load_selection(Path::new(selection_file)))
+        .transpose()?;
+    if selection.is_some()
+        && let Some(option) = matching_option(opts)
+    {
+        return Err(format!(
+            "--selection-file selects the exact operations and cannot be combined with {}",
+            option
+        )
+        .into());
+    }
+    let res = openapi
+        .content()
+        .filter_by_parameters(FilteringParameters {
+            paths,
+            preview_path_matches: opts.preview,
+            methods: opts.http_methods.clone(),
+            strict_method_path: opts.strict_method_path,
+            has_request_body: opts.has_request_body,
+            tags,
+            exclude_tags: opts.exclude_tags.clone(),
+            tag_match: if opts.require_all_tags {
+                TagMatch::All
+            } else {
+                opts.tag_match.into()
+            },
+            security: opts.security.clone(),
+            security_globs: opts.security_globs.clone(),
+            scopes: opts.scopes.clone(),
+            scope_globs: opts.scope_globs.clone(),
+            parameter_locations: opts.parameter_locations.clone(),
+            jsonpaths: opts.jsonpaths.clone(),
+            summary_globs: opts.summary_globs.clone(),
+            grep: opts.grep.clone(),
+            referenced_components: opts.referenced_components.clone(),
+            retain_components: opts.retain_components.clone(),
+            selection,
+            prune_schema_examples: opts.prune_schema_examples,
+            ensure_operation_ids: opts.ensure_operation_ids,
+            ensure_default_responses: opts.ensure_default_response,
+            simplify_schemas: opts.simplify_schemas,
+            normalize_keys: opts.normalize_keys,
+            normalize_trailing_slash: opts.normalize_trailing_slash,
+            tag_from_path: opts.tag_from_path,
+            merge_all_of: opts.merge_allof,
+            schema_profile: opts.schema_profile.map(SchemaProfile::from),
+            strict_schemas: opts.strict_schemas,
+            dedupe_schemas: opts.dedupe_schemas,
+            inline_single_use_schemas: opts.inline_single_use_schemas,
+            schema_name_prefix: opts.schema_name_prefix.clone(),
+            keep_responses: opts.keep_responses.clone(),
+            allow_empty_responses: opts.allow_empty_responses,
+            stub: opts.stub,
+            drop_unused_path_parameters: opts.drop_unused_path_parameters,
+            extension_renames: opts.extension_renames.clone(),
+            server_descriptions: opts.server_descriptions.clone(),
+            first_server_only: opts.first_server_only,
+            server_base: opts.server_base.clone(),
+            embed_base_path: opts.embed_base_path,
+            prune_server_variables: opts.prune_server_variables,
+            prune_scopes: opts.prune_scopes,
+            normalize_bodies: opts.normalize_bodies,
+            sample: opts.sample,
+            seed: opts.seed.unwrap_or_else(random_seed),
+            ..Default::default()
+        })?;
+    if opts.fail_on_empty && res.paths.paths.is_empty() {
+        return Err(Box::new(EmptyResultError));
+    }
+    let res = if opts.fetch_external_examples {
+        examples::inline_external_values(
+            &res,
+            api_document,
+            Some(Duration::from_secs(opts.timeout)),
+        )?
+    } else {
+        res
+    };
+    if let Some(format) = opts.deprecation_report {
+        return Ok(deprecation::deprecation_report(&res, format));
+    }
+    if opts.json_pointer_paths {
+        return Ok(listing::operation_pointers(&res).join("\n"));
+    }
+    if opts.tag_histogram {
+        let histogram = listing::tag_histogram(&res);
+        for (tag, count) in &histogram {
+            if let Some(minimum) = opts.min_tag_operations
+                && *count < minimum
+            {
+                diagnostics::warn(format!(
+                    "tag '{}' has {} operation(s), fewer than the minimum {}",
+                    tag, count, minimum
+                ));
             }
-        },
-        Err(error) => {
-            println!("{}", error);
-            ExitCode::FAILURE
+            if let Some(maximum) = opts.max_tag_operations
+                && *count > maximum
+            {
+                diagnostics::warn(format!(
+                    "tag '{}' has {} operation(s), more than the maximum {}",
+                    tag, count, maximum
+                ));
+            }
+        }
+        return Ok(serializer::to_json_string(&histogram, Some(2))?);
+    }
+    if opts.extract_examples {
+        return Ok(examples::extract_examples(&res));
+    }
+    let mut document = openapi31_fields::to_document_value(&res).unwrap();
+    let comments = yaml_comments::take_comments(&mut document);
+    if let Some(dialect) = opts.schema_dialect {
+        let mut document_value = serde_json::to_value(&document)?;
+        dialect::convert_schemas(&mut document_value, dialect);
+        document = serde_yaml::to_value(&document_value)?;
+    }
+    if opts.ndjson_components {
+        return Ok(listing::component_lines(&serde_json::to_value(&document)?).join("\n"));
+    }
+    if opts.count_refs {
+        return Ok(listing::component_ref_counts(
+            &res,
+            &serde_json::to_value(&document)?,
+        )?);
+    }
+    for reference in find_dangling_refs(&serde_json::to_value(&document).unwrap())? {
+        diagnostics::warn(format!(
+            "reference '{}' does not resolve to any content in the filtered document",
+            reference
+        ));
+    }
+    if opts.check_examples {
+        let mismatches = check_examples(&serde_json::to_value(&document)?);
+        if opts.strict && !mismatches.is_empty() {
+            return Err(Box::new(InvalidExamplesError { mismatches }));
+        }
+        for (location, reason) in mismatches {
+            diagnostics::warn(format!(
+                "example at {} does not match its schema: {}",
+                location, reason
+            ));
+        }
+    }
+    let report = FilterReport::from_document(&res);
+    match opts.input_glob {
+        Some(_) => diagnostics::info(format!("{}: {}", api_document, report)),
+        None => diagnostics::info(report),
+    }
+    let indent = opts.indent.map(usize::from);
+    let format = opts.format.unwrap_or(match openapi {
+        ParsedType::Json(_) => OutputFormat::Json,
+        ParsedType::Yaml(_) => OutputFormat::Yaml,
+    });
+    let text_res = match format {
+        OutputFormat::Json => serializer::to_json_string(&document, indent).unwrap(),
+        OutputFormat::Yaml if opts.oneline => serializer::to_oneline_yaml_string(&document)?,
+        OutputFormat::Yaml => yaml_comments::write_comments(
+            &serializer::to_yaml_string(&document, opts.yaml_style, indent).unwrap(),
+            &comments,
+        ),
+        OutputFormat::Toml => serializer::to_toml_string(&serde_json::to_value(&document)?)
+            .map_err(|error| {
+                FilterError::Serialization(
+                    format!(
+                        "TOML cannot represent the document ({}), e.g. because it has null values",
+                        error
+                    )
+                    .into(),
+                )
+            })?,
+    };
+    Ok(text_res)
+}
+
+/// Filters every document matching the glob pattern and writes them to the output directory
+///
+/// Failures are reported with the name of the failed file and the remaining documents are still filtered unless `--fail-fast` is given.
+///
+/// # Arguments
+///
+/// * `input_glob` - Glob pattern matching the input documents
+/// * `opts` - Parsed arguments
+///
+/// # Returns
+///
+/// * `ExitCode` - Success when all the documents were filtered, otherwise the exit code of the first failure
+fn filter_documents(input_glob: &str, opts: &Opts) -> ExitCode {
+    let output_dir = Path::new(
+        opts.output_dir
+            .as_deref()
+            .expect("--output-dir is required with --input-glob"),
+    );
+    let input_files = match glob::glob(input_glob) {
+        Ok(input_files) => input_files,
+        Err(error) => return report_error(&error),
+    };
+    if let Err(error) = fs::create_dir_all(output_dir) {
+        return report_error(&error);
+    }
+    let base_dir = glob_base_dir(input_glob);
+    let mut status: Option<ExitStatus> = None;
+    for input_file in input_files {
+        let result = match input_file {
+            Ok(input_file) => filter_to_output_dir(&input_file, &base_dir, output_dir, opts)
+                .map_err(|error| (input_file.display().to_string(), error)),
+            Err(error) => Err((
+                error.path().display().to_string(),
+                Box::new(io::Error::from(error)) as Box<dyn std::error::Error>,
+            )),
+        };
+        match result {
+            Ok(()) => {
+                status.get_or_insert(ExitStatus::Success);
+            }
+            Err((input_file, error)) => {
+                println!("{}: {}", input_file, error);
+                let failure = ExitStatus::from_error(error.as_ref());
+                if opts.fail_fast {
+                    return failure.into();
+                }
+                status = match status {
+                    None | Some(ExitStatus::Success) => Some(failure),
+                    first_failure => first_failure,
+                };
+            }
+        }
+    }
+    match status {
+        Some(status) => status.into(),
+        None => {
+            println!("No files matched the input glob '{}'", input_glob);
+            ExitStatus::Error.into()
+        }
+    }
+}
+
+/// Returns the leading directories of the glob pattern without wildcards, e.g. `specs` for `specs/**/openapi.yaml`
+fn glob_base_dir(input_glob: &str) -> PathBuf {
+    Path::new(input_glob)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        })
+        .collect()
+}
+
+/// Filters a single document and writes it to the output directory
+///
+/// The path of the input document relative to the base directory of the glob is kept under the output directory, so that
+/// documents with the same file name in different directories do not overwrite each other.
+fn filter_to_output_dir(
+    input_file: &Path,
+    base_dir: &Path,
+    output_dir: &Path,
+    opts: &Opts,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let text_res = filter_document(&input_file.to_string_lossy(), opts)?;
+    let relative_path = input_file
+        .strip_prefix(base_dir)
+        .ok()
+        .filter(|relative_path| relative_path.file_name().is_some())
+        .unwrap_or(Path::new(
+            input_file.file_name().unwrap_or(input_file.as_os_str()),
+        ));
+    let output_file = output_dir.join(relative_path);
+    if let Some(parent) = output_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output_file, format!("{}\n", text_res))?;
+    Ok(())
+}
+
+/// Prints the error and resolves the exit code matching the class of the error
+fn report_error(error: &(dyn std::error::Error + 'static)) -> ExitCode {
+    println!("{}", error);
+    ExitStatus::from_error(error).into()
+}
+
+/// Prints the error of parsing the arguments and exits, like `clap::Error::exit` but with the exit statuses of the program
+///
+/// clap exits with 2 for invalid arguments, which is the status of input documents that cannot be parsed, so invalid
+/// arguments exit with the general error status instead. `--help` and `--version` still exit successfully.
+///
+/// # Arguments
+///
+/// * `error` - The error returned by clap, also for `--help` and `--version`
+fn exit_with_usage_error(error: clap::Error) -> ! {
+    let status = if error.use_stderr() {
+        ExitStatus::Error
+    } else {
+        ExitStatus::Success
+    };
+    // Printing fails only when the output is closed, and the exit status is all that is left to report then
+    let _ = error.print();
+    std::process::exit(status as i32)
+}
+
+/// Parses an extension rename given as `FROM=TO`
+///
+/// # Arguments
+///
+/// * `value` - The rename given as an argument
+///
+/// # Returns
+///
+/// * `Result<(String, String), String>` - The current and the new extension key, or an error if either is not an extension key
+fn parse_extension_rename(value: &str) -> Result<(String, String), String> {
+    let (from, to) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected FROM=TO but found '{}'", value))?;
+    match [from, to].into_iter().find(|key| !key.starts_with("x-")) {
+        Some(key) => Err(format!(
+            "'{}' is not an extension key starting with x-",
+            key
+        )),
+        None => Ok((from.to_string(), to.to_string())),
+    }
+}
+
+/// Returns the first given option that matches operations by a pattern, which a selection file bypasses
+///
+/// Options given in a filter file are included, since they are merged into the options before filtering.
+///
+/// # Arguments
+///
+/// * `opts` - The command line options
+///
+/// # Returns
+///
+/// * `Option<&'static str>` - The name of the option, or None when no matching option is given
+fn matching_option(opts: &Opts) -> Option<&'static str> {
+    [
+        (
+            "--path",
+            opts.path_names.is_some() || opts.paths_file.is_some(),
+        ),
+        ("--method", opts.http_methods.is_some()),
+        ("--has-request-body", opts.has_request_body.is_some()),
+        ("--tag", opts.tags.is_some() || opts.tag_groups.is_some()),
+        ("--exclude-tag", opts.exclude_tags.is_some()),
+        (
+            "--security",
+            opts.security.is_some() || opts.security_globs.is_some(),
+        ),
+        (
+            "--scope",
+            opts.scopes.is_some() || opts.scope_globs.is_some(),
+        ),
+        ("--param-in", opts.parameter_locations.is_some()),
+        ("--jsonpath", opts.jsonpaths.is_some()),
+        ("--summary-glob", opts.summary_globs.is_some()),
+        ("--grep", opts.grep.is_some()),
+        (
+            "--references-component",
+            opts.referenced_components.is_some(),
+        ),
+    ]
+    .into_iter()
+    .find(|(_, given)| *given)
+    .map(|(option, _)| option)
+}
+
+/// Appends the paths listed in the paths file to the path filters
+///
+/// The file lists one path or path pattern per line. Blank lines and lines starting with `#` are skipped.
+///
+/// # Arguments
+///
+/// * `paths` - Paths given as filtering parameters
+/// * `paths_file` - Optional path of the paths file
+///
+/// # Returns
+///
+/// * `Result<Option<Vec<String>>, Box<dyn std::error::Error>>` - Combined path filters, or an error naming the file if it cannot be read,
+///   has a line with whitespace inside the path or lists no paths
+fn merge_paths_file(
+    paths: Option<Vec<String>>,
+    paths_file: Option<&str>,
+) -> Result<Option<Vec<String>>, Box<dyn std::error::Error>> {
+    let Some(paths_file) = paths_file else {
+        return Ok(paths);
+    };
+    let contents = fs::read_to_string(paths_file)
+        .map_err(|error| format!("Could not read paths file '{}': {}", paths_file, error))?;
+    let mut file_paths: Vec<String> = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.contains(char::is_whitespace) {
+            return Err(format!(
+                "Paths file '{}' line {}: path '{}' contains whitespace",
+                paths_file,
+                index + 1,
+                line
+            )
+            .into());
+        }
+        file_paths.push(line.to_string());
+    }
+    if file_paths.is_empty() {
+        return Err(format!(
+            "Paths file '{}' lists no paths on its {} line(s)",
+            paths_file,
+            contents.lines().count()
+        )
+        .into());
+    }
+    let mut merged_paths = paths.unwrap_or_default();
+    merged_paths.extend(file_paths);
+    Ok(Some(merged_paths))
+}
+
+/// Unions the tags of the requested tag groups into the tag filters
+///
+/// # Arguments
+///
+/// * `openapi` - The OpenAPI document containing the `x-tagGroups` extension
+/// * `tags` - Tag names given as filtering parameters
+/// * `tag_groups` - Tag group names given as filtering parameters
+///
+/// # Returns
+///
+/// * `Result<Option<Vec<String>>, Box<dyn std::error::Error>>` - Combined tag filters, or an error if a tag group was not found
+fn merge_tag_groups(
+    openapi: &OpenAPI,
+    tags: Option<Vec<String>>,
+    tag_groups: Option<Vec<String>>,
+) -> Result<Option<Vec<String>>, Box<dyn std::error::Error>> {
+    let Some(tag_groups) = tag_groups else {
+        return Ok(tags);
+    };
+    let mut merged_tags = tags.unwrap_or_default();
+    for tag in resolve_tag_groups(&openapi.extensions, &tag_groups)? {
+        if !merged_tags.contains(&tag) {
+            merged_tags.push(tag);
         }
     }
+    Ok(Some(merged_tags))
 }
@@ -1,8 +1,95 @@
+use crate::diagnostics;
+use crate::error::ParseError;
+use crate::filter::content::openapi31_fields;
+use crate::filter::content::yaml_comments::{self, mapping_key};
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use serde_yaml::Value as YamlValue;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
 use std::fs;
 use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+/// Error returned when the input is valid YAML or JSON but does not have the shape of an OpenAPI document
+#[derive(Debug, PartialEq)]
+pub struct InvalidDocumentError {
+    reason: String,
+}
+
+impl fmt::Display for InvalidDocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Input document is not an OpenAPI document: {}",
+            self.reason
+        )
+    }
+}
+
+impl Error for InvalidDocumentError {}
+
+/// Error returned when the input document references an environment variable that is not defined
+#[derive(Debug, PartialEq)]
+pub struct UndefinedEnvVariableError {
+    name: String,
+}
+
+impl fmt::Display for UndefinedEnvVariableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Environment variable '{}' referenced in the input document is not defined",
+            self.name
+        )
+    }
+}
+
+impl Error for UndefinedEnvVariableError {}
+
+/// Error returned when the input document is not valid UTF-8, e.g. when it is saved with a Latin-1 encoding
+#[derive(Debug, PartialEq)]
+pub struct InvalidEncodingError {
+    offset: usize,
+}
+
+impl fmt::Display for InvalidEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Input document is not valid UTF-8: invalid byte at offset {}",
+            self.offset
+        )
+    }
+}
+
+impl Error for InvalidEncodingError {}
+
+/// OpenAPI version the input document is handled as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecVersion {
+    /// OpenAPI 3.0, fields added in 3.1 are dropped when parsing
+    OpenApi3,
+    /// OpenAPI 3.1, reusable path items and webhooks are kept
+    OpenApi31,
+}
+
+/// Options controlling how the raw input document is read before parsing
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ParseOptions {
+    ///when true replaces `${VAR}` placeholders with the values of the environment variables
+    pub substitute_env: bool,
+    ///when true leaves placeholders of undefined environment variables as they are instead of failing
+    pub allow_missing_env: bool,
+    ///when provided handles the document as this version instead of the version in its `openapi` field
+    pub assume_version: Option<SpecVersion>,
+    ///when true keeps the comments above the path and operation keys of YAML documents, see `yaml_comments`
+    pub preserve_comments: bool,
+    ///when provided bounds the time of fetching the document from a URL, the fetch fails with a timed out I/O error after it
+    pub timeout: Option<Duration>,
+}
 
 #[derive(Debug)]
 pub enum ParsedType<T> {
@@ -10,7 +97,16 @@ pub enum ParsedType<T> {
     Yaml(T),
 }
 
-/// Reads the contents of a file into a String.
+impl<T> ParsedType<T> {
+    /// Returns the parsed content regardless of the format it was parsed from
+    pub fn content(&self) -> &T {
+        match self {
+            ParsedType::Json(content) | ParsedType::Yaml(content) => content,
+        }
+    }
+}
+
+/// Reads the contents of a file as bytes.
 ///
 /// # Arguments
 ///
@@ -18,14 +114,74 @@ pub enum ParsedType<T> {
 ///
 /// # Returns
 ///
-/// * `io::Result<String>` - A Result containing the file contents as a String, or an io::Error if an error occurs.
-fn read_file(file_name: &str) -> io::Result<String> {
+/// * `io::Result<Vec<u8>>` - A Result containing the file contents, or an io::Error if an error occurs.
+fn read_file(file_name: &str) -> io::Result<Vec<u8>> {
     let mut file = fs::File::open(file_name)?; // Open the file
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?; // Read the contents into a String
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?; // Read the contents into a buffer
     Ok(contents)
 }
 
+/// Decodes the raw contents of the input document as UTF-8.
+///
+/// # Arguments
+///
+/// * `contents` - The raw bytes of the input document.
+///
+/// # Returns
+///
+/// * `Result<String, InvalidEncodingError>` - The decoded contents, or an error naming the offset of the first invalid byte.
+fn decode_contents(contents: Vec<u8>) -> Result<String, InvalidEncodingError> {
+    String::from_utf8(contents).map_err(|err| InvalidEncodingError {
+        offset: err.utf8_error().valid_up_to(),
+    })
+}
+
+/// Fetches the contents of a http or https URL into a String.
+///
+/// Network errors and error statuses are returned as I/O errors, the same way as failures to read a file.
+/// A fetch taking longer than the timeout, including reading the body, fails with an error of the `TimedOut` kind.
+///
+/// # Arguments
+///
+/// * `url` - A string slice representing the URL to fetch.
+/// * `timeout` - The maximum duration of the whole fetch, or None for no limit.
+///
+/// # Returns
+///
+/// * `io::Result<(Vec<u8>, Option<&'static str>)>` - A Result containing the response body and the format resolved from the `Content-Type` header, or an io::Error if an error occurs.
+fn fetch_url(url: &str, timeout: Option<Duration>) -> io::Result<(Vec<u8>, Option<&'static str>)> {
+    let into_io_error = |error: ureq::Error| match error {
+        ureq::Error::Timeout(_) => io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!(
+                "Fetching {} timed out after {} seconds",
+                url,
+                timeout.unwrap_or_default().as_secs_f64()
+            ),
+        ),
+        error => io::Error::other(error),
+    };
+    let mut response = ureq::get(url)
+        .config()
+        .timeout_global(timeout)
+        .build()
+        .call()
+        .map_err(into_io_error)?;
+    let media_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(format_from_content_type);
+    let contents = response.body_mut().read_to_vec().map_err(into_io_error)?;
+    Ok((contents, media_type))
+}
+
+/// Returns true when the input document is given as a http or https URL
+fn is_url(file_name: &str) -> bool {
+    file_name.starts_with("http://") || file_name.starts_with("https://")
+}
+
 /// Parses a JSON string into a struct.
 ///
 /// # Arguments
@@ -44,57 +200,403 @@ where
 
 /// Parses a YAML string into a struct.
 ///
+/// Fields not modelled by the `openapiv3` crate are moved under extension keys before deserializing, see `openapi31_fields`.
+/// OpenAPI 3.1 fields are kept only when the document is handled as OpenAPI 3.1.
+///
 /// # Arguments
 ///
 /// * `contents` - A string slice representing the YAML string to parse.
+/// * `options` - Options giving the version the document is handled as and whether comments are kept.
 ///
 /// # Returns
 ///
-/// * `Result<T, serde_yaml::Error>` - A Result containing the parsed struct, or a serde_yaml::Error if an error occurs.
-fn parse_yaml<T>(contents: &str) -> Result<T, serde_yaml::Error>
+/// * `Result<T, ParseError>` - A Result containing the parsed struct, or an error if the YAML is invalid or declares an unsupported version.
+fn parse_yaml<T>(contents: &str, options: &ParseOptions) -> Result<T, ParseError>
 where
     T: for<'de> Deserialize<'de>,
 {
-    serde_yaml::from_str(contents)
+    let mut document: YamlValue = serde_yaml::from_str(contents)?;
+    check_version(&document)?;
+    let version = options
+        .assume_version
+        .unwrap_or_else(|| declared_version(&document));
+    openapi31_fields::stash_fields(&mut document, version == SpecVersion::OpenApi31);
+    if options.preserve_comments {
+        yaml_comments::stash_comments(contents, &mut document);
+    }
+    Ok(serde_yaml::from_value(document)?)
+}
+
+/// Returns the version declared in the `openapi` field, defaulting to OpenAPI 3.0
+fn declared_version(document: &YamlValue) -> SpecVersion {
+    match document.get("openapi").and_then(YamlValue::as_str) {
+        Some(version) if version.starts_with("3.1") => SpecVersion::OpenApi31,
+        _ => SpecVersion::OpenApi3,
+    }
+}
+
+/// Checks that the document declares an OpenAPI 3 version.
+///
+/// Swagger 2.0 documents declaring their version in the `swagger` field are rejected as well.
+/// Documents without a version string pass the check, so that the missing field is reported by `check_document_shape`.
+///
+/// # Arguments
+///
+/// * `document` - The YAML value of the whole document
+///
+/// # Returns
+///
+/// * `Result<(), ParseError>` - An error naming the unsupported version
+fn check_version(document: &YamlValue) -> Result<(), ParseError> {
+    let version = document
+        .get("openapi")
+        .or_else(|| document.get("swagger"))
+        .and_then(YamlValue::as_str);
+    match version {
+        Some(version) if !version.starts_with("3.") => {
+            Err(ParseError::UnsupportedVersion(version.to_string()))
+        }
+        _ => Ok(()),
+    }
 }
 
-/// Parses a document from a file or stdin, attempting to parse it as YAML first, then as JSON.
+/// Parses a document from a file, URL or stdin, attempting to parse it as YAML first, then as JSON.
+///
+/// The raw contents are preprocessed as requested by the options before parsing.
 ///
 /// # Arguments
 ///
-/// * `file_name` - A string slice representing the name of the file to read, or "-" for stdin.
+/// * `file_name` - A string slice representing the name of the file or URL to read, or "-" for stdin.
+/// * `options` - A reference to the options applied to the raw contents before parsing.
 ///
 /// # Returns
 ///
-/// * `Result<ParsedType<T>, Box<dyn std::error::Error>>` - A Result containing the parsed struct, or an error if parsing fails.
-pub fn parse_document<T>(file_name: &str) -> Result<ParsedType<T>, Box<dyn (std::error::Error)>>
+/// * `Result<ParsedType<T>, ParseError>` - A Result containing the parsed struct, or an error if reading, preprocessing or parsing fails.
+pub fn parse_document<T>(
+    file_name: &str,
+    options: &ParseOptions,
+) -> Result<ParsedType<T>, ParseError>
 where
     T: for<'de> Deserialize<'de>,
 {
-    let data = match file_name {
-        "-" => std::io::read_to_string(std::io::stdin()),
-        _ => read_file(file_name),
+    let (contents, media_type) = match file_name {
+        "-" => {
+            let mut contents = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut contents)
+                .map(|_| (contents, None))
+        }
+        url if is_url(url) => fetch_url(url, options.timeout),
+        _ => read_file(file_name).map(|contents| (contents, None)),
+    }?;
+    let contents = preprocess_contents(decode_contents(contents)?, options)?;
+    let (result, default_type) = parse_contents(&contents, options)?;
+    Ok(wrap_response_type(
+        result,
+        file_name,
+        default_type,
+        &contents,
+        media_type,
+    ))
+}
+
+/// Reads the literal example of an `externalValue` as a value that can be inlined as the `value` of the example.
+///
+/// A relative location is resolved against the location of the document, which is the working directory for stdin.
+/// For a document read from a URL the location is resolved as a URL reference and must resolve to a http or https URL,
+/// so such documents never read examples from the local filesystem.
+/// Locations with a `.json`, `.yaml` or `.yml` extension are parsed in that format. Other contents are parsed as JSON
+/// when possible and otherwise kept as a string, e.g. for plain text or XML examples.
+///
+/// # Arguments
+///
+/// * `location` - The `externalValue` of the example, a URL or a file path.
+/// * `document` - The file name or URL of the document declaring the example, or "-" for stdin.
+/// * `timeout` - The maximum duration of fetching the example from a URL, or None for no limit.
+///
+/// # Returns
+///
+/// * `Result<JsonValue, ParseError>` - The example value, or an error if reading or parsing fails.
+pub fn read_external_value(
+    location: &str,
+    document: &str,
+    timeout: Option<Duration>,
+) -> Result<JsonValue, ParseError> {
+    let location = match document {
+        url if is_url(url) => {
+            let resolved = resolve_url_reference(url, location);
+            if !is_url(&resolved) {
+                return Err(ParseError::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "External value '{}' of {} does not resolve to a http or https URL",
+                        location, url
+                    ),
+                )));
+            }
+            resolved
+        }
+        _ if is_url(location) || Path::new(location).is_absolute() => location.to_string(),
+        "-" => location.to_string(),
+        file_name => Path::new(file_name)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(location)
+            .to_string_lossy()
+            .into_owned(),
     };
-    match data {
-        Ok(contents) => match parse_yaml(&contents) {
-            Ok(result) => Ok(wrap_response_type(result, file_name, "yaml", &contents)),
-            Err(_) => match parse_json(&contents) {
-                Ok(result) => Ok(wrap_response_type(result, file_name, "json", &contents)),
-                Err(err) => Err(Box::new(err)),
-            },
+    let (contents, media_type) = if is_url(&location) {
+        fetch_url(&location, timeout)?
+    } else {
+        (read_file(&location)?, None)
+    };
+    let contents = decode_contents(contents)?;
+    let format = if is_url(&location) {
+        url_extension(&location).or(media_type)
+    } else {
+        file_extension(&location)
+    };
+    Ok(match format {
+        Some("json") => serde_json::from_str(&contents)?,
+        Some(_) => serde_yaml::from_str(&contents)?,
+        None => serde_json::from_str(&contents).unwrap_or_else(|_| JsonValue::String(contents)),
+    })
+}
+
+/// Resolves a URL reference against the URL of a base document, like a browser resolves a link.
+///
+/// References with a scheme are returned as they are, network-path references (`//host/path`) take the scheme of the base,
+/// root-relative references (`/path`) its scheme and authority, and other references are resolved against the directory
+/// of the base path. The query and fragment of the base are not carried over to path references.
+///
+/// # Arguments
+///
+/// * `base` - The http or https URL of the document, e.g. `https://example.com/specs/api.yaml?version=2`.
+/// * `reference` - The reference to resolve, e.g. `../examples/pet.json`.
+///
+/// # Returns
+///
+/// * `String` - The resolved URL, e.g. `https://example.com/examples/pet.json`.
+fn resolve_url_reference(base: &str, reference: &str) -> String {
+    let has_scheme = reference.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if has_scheme {
+        return reference.to_string();
+    }
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    if reference.starts_with("//") {
+        return format!("{}:{}", scheme, reference);
+    }
+    let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, rest) = rest.split_at(authority_end);
+    let base_without_fragment = &rest[..rest.find('#').unwrap_or(rest.len())];
+    let base_path = &rest[..rest.find(['?', '#']).unwrap_or(rest.len())];
+    let base_path = if base_path.is_empty() { "/" } else { base_path };
+    let (reference_path, suffix) =
+        reference.split_at(reference.find(['?', '#']).unwrap_or(reference.len()));
+    let path = match reference_path {
+        "" if suffix.starts_with('?') => base_path.to_string(),
+        "" => {
+            return format!(
+                "{}://{}{}{}",
+                scheme, authority, base_without_fragment, suffix
+            );
+        }
+        path if path.starts_with('/') => remove_dot_segments(path),
+        path => {
+            let directory = &base_path[..=base_path.rfind('/').unwrap_or_default()];
+            remove_dot_segments(&format!("{}{}", directory, path))
+        }
+    };
+    format!("{}://{}{}{}", scheme, authority, path, suffix)
+}
+
+/// Removes the `.` and `..` segments of an absolute URL path, never climbing above the root
+///
+/// # Arguments
+///
+/// * `path` - The URL path starting with `/`, e.g. `/specs/../examples/./pet.json`.
+///
+/// # Returns
+///
+/// * `String` - The path without dot segments, e.g. `/examples/pet.json`.
+fn remove_dot_segments(path: &str) -> String {
+    let input: Vec<&str> = path.split('/').skip(1).collect();
+    let mut output: Vec<&str> = Vec::with_capacity(input.len());
+    for (index, segment) in input.iter().enumerate() {
+        let is_last = index == input.len() - 1;
+        match *segment {
+            "." => {}
+            ".." => {
+                output.pop();
+            }
+            segment => output.push(segment),
+        }
+        // A path ending with a dot segment refers to a directory
+        if is_last && matches!(*segment, "." | "..") {
+            output.push("");
+        }
+    }
+    format!("/{}", output.join("/"))
+}
+
+/// Parses a document from a string without reading any file, attempting to parse it as YAML first, then as JSON.
+///
+/// The format of the parsed document is detected from the contents, so JSON documents are parsed as `ParsedType::Json`.
+///
+/// # Arguments
+///
+/// * `contents` - A string slice holding the document.
+/// * `options` - A reference to the options applied to the raw contents before parsing.
+///
+/// # Returns
+///
+/// * `Result<ParsedType<T>, ParseError>` - A Result containing the parsed struct, or an error if preprocessing or parsing fails.
+pub fn parse_str<T>(contents: &str, options: &ParseOptions) -> Result<ParsedType<T>, ParseError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let contents = preprocess_contents(contents.to_string(), options)?;
+    let (result, _) = parse_contents(&contents, options)?;
+    Ok(match detect_format(&contents) {
+        Some("json") => ParsedType::Json(result),
+        _ => ParsedType::Yaml(result),
+    })
+}
+
+/// Strips a leading byte order mark and applies the preprocessing requested by the options to the raw contents
+fn preprocess_contents(
+    contents: String,
+    options: &ParseOptions,
+) -> Result<String, UndefinedEnvVariableError> {
+    let contents = match contents.strip_prefix('\u{feff}') {
+        Some(contents) => contents.to_string(),
+        None => contents,
+    };
+    if options.substitute_env {
+        substitute_env_variables(&contents, options.allow_missing_env, |name| {
+            std::env::var(name).ok()
+        })
+    } else {
+        Ok(contents)
+    }
+}
+
+/// Parses the contents as YAML first, then as JSON, returning the parsed struct with the name of the format that parsed it
+///
+/// Documents declaring a version other than OpenAPI 3 are rejected without trying to parse them as JSON.
+fn parse_contents<T>(
+    contents: &str,
+    options: &ParseOptions,
+) -> Result<(T, &'static str), ParseError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let contents = remove_duplicate_path_keys(contents);
+    let contents = contents.as_ref();
+    match parse_yaml(contents, options) {
+        Ok(result) => Ok((result, "yaml")),
+        Err(ParseError::UnsupportedVersion(version)) => {
+            Err(ParseError::UnsupportedVersion(version))
+        }
+        Err(_) => match parse_json(contents) {
+            Ok(result) => Ok((result, "json")),
+            Err(err) => {
+                check_document_shape(contents)?;
+                Err(ParseError::JsonParse(err))
+            }
         },
-        Err(e) => Err(Box::new(e)),
     }
 }
 
+/// Removes the earlier declarations of path keys declared more than once in the block style `paths` mapping, warning about each.
+///
+/// YAML parsing rejects duplicate mapping keys, so a path copied without renaming it would fail the whole document.
+/// The last declaration of the path is kept, as with JSON documents. Only the top-level `paths` mapping written in
+/// block style is scanned; keys of flow style mappings are left to the parser.
+///
+/// # Arguments
+///
+/// * `contents` - The raw contents of the document
+///
+/// # Returns
+///
+/// * `Cow<str>` - The contents without the overwritten declarations, borrowed when there are no duplicates
+fn remove_duplicate_path_keys(contents: &str) -> Cow<'_, str> {
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let is_content = |line: &str| !line.trim().is_empty() && !line.trim_start().starts_with('#');
+    let indent = |line: &str| line.len() - line.trim_start_matches(' ').len();
+    let Some(paths_line) = lines.iter().position(|line| {
+        let line = line.trim_end();
+        line == "paths:"
+            || line
+                .strip_prefix("paths:")
+                .is_some_and(|rest| rest.trim_start().starts_with('#'))
+    }) else {
+        return Cow::Borrowed(contents);
+    };
+    let block_end = (paths_line + 1..lines.len())
+        .find(|&index| is_content(lines[index]) && indent(lines[index]) == 0)
+        .unwrap_or(lines.len());
+    let Some(key_indent) = (paths_line + 1..block_end)
+        .find(|&index| is_content(lines[index]))
+        .map(|index| indent(lines[index]))
+    else {
+        return Cow::Borrowed(contents);
+    };
+    let key_lines: Vec<(usize, String)> = (paths_line + 1..block_end)
+        .filter(|&index| is_content(lines[index]) && indent(lines[index]) == key_indent)
+        .filter_map(|index| mapping_key(lines[index].trim()).map(|key| (index, key)))
+        .collect();
+    let mut removed = vec![false; lines.len()];
+    for (position, (index, key)) in key_lines.iter().enumerate() {
+        let Some((overwriting_index, _)) = key_lines[position + 1..]
+            .iter()
+            .find(|(_, other_key)| other_key == key)
+        else {
+            continue;
+        };
+        diagnostics::warn(format!(
+            "path '{}' is declared more than once, the declaration on line {} is overwritten by the one on line {}",
+            key,
+            index + 1,
+            overwriting_index + 1
+        ));
+        let entry_end = key_lines
+            .get(position + 1)
+            .map_or(block_end, |(next_index, _)| *next_index);
+        removed[*index..entry_end].fill(true);
+    }
+    if !removed.contains(&true) {
+        return Cow::Borrowed(contents);
+    }
+    Cow::Owned(
+        lines
+            .iter()
+            .zip(removed)
+            .filter(|(_, removed)| !removed)
+            .map(|(line, _)| *line)
+            .collect(),
+    )
+}
+
 fn wrap_response_type<T>(
     response: T,
     file_name: &str,
     default_type: &str,
     input: &str,
+    media_type: Option<&'static str>,
 ) -> ParsedType<T> {
-    let file_postfix = if file_name != "-" {
-        file_name.split(".").last().or(Some(default_type))
+    let file_postfix = if is_url(file_name) {
+        media_type
+            .or_else(|| url_extension(file_name))
+            .or_else(|| detect_format(input))
+    } else if file_name != "-" {
+        file_extension(file_name).or(Some(default_type))
     } else {
         detect_format(input)
     };
@@ -124,3 +626,415 @@ fn detect_format(input: &str) -> Option<&'static str> {
     }
     None
 }
+
+/// Checks that a document that could not be parsed as OpenAPI is at least shaped like one.
+///
+/// Documents such as `null` or a bare string are valid YAML, so the error from deserializing them would not tell what is wrong.
+/// Contents that are not valid YAML pass the check, so that their syntax error is reported instead.
+///
+/// # Arguments
+///
+/// * `contents` - Contents of the input document
+///
+/// # Returns
+///
+/// * `Result<(), ParseError>` - An error naming the missing part of the document, or telling that the document is empty
+fn check_document_shape(contents: &str) -> Result<(), ParseError> {
+    let Ok(document) = serde_yaml::from_str::<YamlValue>(contents) else {
+        return Ok(());
+    };
+    let reason = match &document {
+        YamlValue::Mapping(_) => {
+            if !document.get("openapi").is_some_and(YamlValue::is_string) {
+                "missing the 'openapi' version string".to_string()
+            } else if !document.get("info").is_some_and(YamlValue::is_mapping) {
+                "missing the 'info' object".to_string()
+            } else {
+                return Ok(());
+            }
+        }
+        YamlValue::Null => return Err(ParseError::EmptyDocument),
+        YamlValue::Bool(_) | YamlValue::Number(_) | YamlValue::String(_) => {
+            "document is a single scalar value instead of an object".to_string()
+        }
+        YamlValue::Sequence(_) => "document is a list instead of an object".to_string(),
+        YamlValue::Tagged(_) => "document is a tagged value instead of an object".to_string(),
+    };
+    Err(ParseError::InvalidDocument(InvalidDocumentError { reason }))
+}
+
+/// Replaces the `${VAR}` placeholders of the raw document with the values of the variables.
+///
+/// Placeholders whose name is not a valid environment variable name, such as `${}` or `${a-b}`, are left as they are.
+///
+/// # Arguments
+///
+/// * `contents` - Raw contents of the input document
+/// * `allow_missing` - Whether placeholders of undefined variables are left as they are instead of failing
+/// * `lookup` - Function returning the value of a variable, or None when it is not defined
+///
+/// # Returns
+///
+/// * `Result<String, UndefinedEnvVariableError>` - The substituted contents, or an error naming the first undefined variable
+fn substitute_env_variables(
+    contents: &str,
+    allow_missing: bool,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, UndefinedEnvVariableError> {
+    let mut substituted = String::with_capacity(contents.len());
+    let mut rest = contents;
+    while let Some(start) = rest.find("${") {
+        substituted.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let name = placeholder[2..]
+            .split_once('}')
+            .map(|(name, _)| name)
+            .filter(|name| is_env_variable_name(name));
+        match name {
+            Some(name) => {
+                match lookup(name) {
+                    Some(value) => substituted.push_str(&value),
+                    None if allow_missing => substituted.push_str(&placeholder[..name.len() + 3]),
+                    None => {
+                        return Err(UndefinedEnvVariableError {
+                            name: name.to_string(),
+                        });
+                    }
+                }
+                rest = &placeholder[name.len() + 3..];
+            }
+            None => {
+                substituted.push_str("${");
+                rest = &placeholder[2..];
+            }
+        }
+    }
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+/// Returns true when the name consists of ASCII letters, digits and underscores and does not start with a digit
+fn is_env_variable_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+/// Resolves the document format from the media type of a `Content-Type` header
+///
+/// # Arguments
+///
+/// * `content_type` - Value of the `Content-Type` header, e.g. `application/json; charset=utf-8`
+///
+/// # Returns
+///
+/// * `Option<&'static str>` - Either json or yaml, or None when the media type does not tell the format
+fn format_from_content_type(content_type: &str) -> Option<&'static str> {
+    let media_type = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if media_type.ends_with("json") {
+        Some("json")
+    } else if media_type.ends_with("yaml") || media_type.ends_with("yml") {
+        Some("yaml")
+    } else {
+        None
+    }
+}
+
+/// Resolves the document format from the file extension in the path of a URL
+///
+/// # Arguments
+///
+/// * `url` - The URL of the document
+///
+/// # Returns
+///
+/// * `Option<&'static str>` - Either json or yaml, or None when the path has no known extension
+fn url_extension(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    file_extension(path.rsplit('/').next().unwrap_or_default())
+}
+
+/// Resolves the document format from the extension of a file name, ignoring the case of the extension
+///
+/// # Arguments
+///
+/// * `file_name` - The file name, possibly with several dots, e.g. `api.v1.YAML`
+///
+/// # Returns
+///
+/// * `Option<&'static str>` - Either json or yaml, or None when the file name has no known extension
+fn file_extension(file_name: &str) -> Option<&'static str> {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("json") => Some("json"),
+        Some("yaml") | Some("yml") => Some("yaml"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_documents_not_shaped_like_openapi() {
+        assert_eq!(
+            check_document_shape("null").unwrap_err().to_string(),
+            "Input document is empty or null"
+        );
+        assert_eq!(
+            check_document_shape("just a string")
+                .unwrap_err()
+                .to_string(),
+            "Input document is not an OpenAPI document: document is a single scalar value instead of an object"
+        );
+        assert_eq!(
+            check_document_shape("openapi: 3.0.3\npaths: {}")
+                .unwrap_err()
+                .to_string(),
+            "Input document is not an OpenAPI document: missing the 'info' object"
+        );
+        assert!(check_document_shape("openapi: 3.0.3\ninfo: {}").is_ok());
+        assert!(check_document_shape("{ not: valid: yaml").is_ok());
+    }
+
+    #[test]
+    fn it_strips_byte_order_marks_and_rejects_invalid_utf8() {
+        let parsed = parse_document::<openapiv3::OpenAPI>(
+            "tests/resources/bom.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(parsed.content().info.title, "Byte order mark");
+
+        let error = parse_document::<openapiv3::OpenAPI>(
+            "tests/resources/latin1.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::InvalidEncoding(InvalidEncodingError { offset: 56 })
+        ));
+    }
+
+    #[test]
+    fn it_reports_null_and_scalar_documents() {
+        for contents in ["null", "\"just a string\""] {
+            let file = std::env::temp_dir()
+                .join(format!("openapiv3-filter-scalar-{}.json", contents.len()));
+            fs::write(&file, contents).unwrap();
+            let error = parse_document::<openapiv3::OpenAPI>(
+                &file.to_string_lossy(),
+                &ParseOptions::default(),
+            )
+            .unwrap_err();
+            assert!(matches!(
+                error,
+                ParseError::EmptyDocument | ParseError::InvalidDocument(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_last_declaration_of_duplicate_path_keys() {
+        let contents = "openapi: 3.0.3\npaths:\n  /pets:\n    get: {}\n\n  '/users': {}\n  /pets:\n    post: {}\ncomponents: {}\n";
+
+        assert_eq!(
+            remove_duplicate_path_keys(contents),
+            "openapi: 3.0.3\npaths:\n  '/users': {}\n  /pets:\n    post: {}\ncomponents: {}\n"
+        );
+        assert!(matches!(
+            remove_duplicate_path_keys("paths:\n  /pets: {}\n  /users: {}\n"),
+            Cow::Borrowed(_)
+        ));
+
+        let parsed = parse_document::<openapiv3::OpenAPI>(
+            "tests/resources/duplicate-paths.yaml",
+            &ParseOptions::default(),
+        )
+        .unwrap();
+        let pets = parsed.content().paths.paths["/pets"].as_item().unwrap();
+        assert!(pets.get.is_none());
+        assert!(pets.post.is_some());
+    }
+
+    #[test]
+    fn it_rejects_documents_of_other_versions() {
+        for (contents, version) in [
+            ("swagger: '2.0'\ninfo: {}\npaths: {}", "2.0"),
+            ("openapi: 4.0.0\ninfo: {}\npaths: {}", "4.0.0"),
+        ] {
+            let error =
+                parse_str::<openapiv3::OpenAPI>(contents, &ParseOptions::default()).unwrap_err();
+            assert!(matches!(error, ParseError::UnsupportedVersion(found) if found == version));
+        }
+    }
+
+    #[test]
+    fn it_substitutes_env_variables() {
+        let lookup = |name: &str| (name == "API_HOST").then(|| String::from("api.example.com"));
+        assert_eq!(
+            substitute_env_variables("url: https://${API_HOST}/v1 ${} ${a-b}", false, lookup),
+            Ok(String::from("url: https://api.example.com/v1 ${} ${a-b}"))
+        );
+        assert_eq!(
+            substitute_env_variables("url: https://${API_HOST}:${PORT}", true, lookup),
+            Ok(String::from("url: https://api.example.com:${PORT}"))
+        );
+        assert_eq!(
+            substitute_env_variables("url: https://${API_HOST}:${PORT}", false, lookup)
+                .unwrap_err()
+                .to_string(),
+            "Environment variable 'PORT' referenced in the input document is not defined"
+        );
+    }
+
+    #[test]
+    fn it_resolves_format_from_content_type() {
+        assert_eq!(
+            format_from_content_type("application/json; charset=utf-8"),
+            Some("json")
+        );
+        assert_eq!(
+            format_from_content_type("application/vnd.oai.openapi+json"),
+            Some("json")
+        );
+        assert_eq!(format_from_content_type("application/x-yaml"), Some("yaml"));
+        assert_eq!(format_from_content_type("text/plain"), None);
+    }
+
+    #[test]
+    fn it_resolves_format_from_url_extension() {
+        assert_eq!(
+            url_extension("https://example.com/specs/api.json?version=2"),
+            Some("json")
+        );
+        assert_eq!(
+            url_extension("https://example.com/api.yml#top"),
+            Some("yaml")
+        );
+        assert_eq!(url_extension("https://example.com/api"), None);
+        assert_eq!(url_extension("https://example.com/API.JSON"), Some("json"));
+    }
+
+    #[test]
+    fn it_resolves_format_from_file_extension_case_insensitively() {
+        assert_eq!(file_extension("API.JSON"), Some("json"));
+        assert_eq!(file_extension("api.v1.Json"), Some("json"));
+        assert_eq!(file_extension("api.v1.YAML"), Some("yaml"));
+        assert_eq!(file_extension("specs/api.Yml"), Some("yaml"));
+        assert_eq!(file_extension("api.v1"), None);
+        assert!(matches!(
+            wrap_response_type((), "API.JSON", "yaml", "", None),
+            ParsedType::Json(())
+        ));
+    }
+
+    #[test]
+    fn it_reads_external_values_relative_to_the_document() {
+        let document = "tests/resources/external-examples.yaml";
+
+        assert_eq!(
+            read_external_value("external-examples/pets.json", document, None).unwrap(),
+            serde_json::json!([{"name": "Rex"}, {"name": "Tom"}])
+        );
+        assert_eq!(
+            read_external_value("external-examples/notes.txt", document, None).unwrap(),
+            "Pets are listed in the order they were added\n"
+        );
+        assert!(matches!(
+            read_external_value("external-examples/missing.json", document, None),
+            Err(ParseError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn it_resolves_external_values_of_url_documents_as_url_references() {
+        let base = "https://example.com/specs/v1/api.yaml?version=2#/paths";
+        for (reference, resolved) in [
+            ("pet.json", "https://example.com/specs/v1/pet.json"),
+            (
+                "../examples/pet.json?raw=1",
+                "https://example.com/specs/examples/pet.json?raw=1",
+            ),
+            ("/etc/passwd", "https://example.com/etc/passwd"),
+            ("/../../etc/passwd", "https://example.com/etc/passwd"),
+            (
+                "//cdn.example.com/pet.json",
+                "https://cdn.example.com/pet.json",
+            ),
+            (
+                "http://other.example.com/pet.json",
+                "http://other.example.com/pet.json",
+            ),
+            (
+                "?version=3",
+                "https://example.com/specs/v1/api.yaml?version=3",
+            ),
+            ("file:///etc/passwd", "file:///etc/passwd"),
+        ] {
+            assert_eq!(resolve_url_reference(base, reference), resolved);
+        }
+        assert_eq!(
+            resolve_url_reference("https://example.com", "pet.json"),
+            "https://example.com/pet.json"
+        );
+        assert_eq!(
+            resolve_url_reference("https://example.com?version=2", "pet.json"),
+            "https://example.com/pet.json"
+        );
+    }
+
+    #[test]
+    fn it_never_reads_external_values_of_url_documents_from_disk() {
+        // The connection is accepted by the operating system but never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let document = format!("http://{}/specs/api.yaml", listener.local_addr().unwrap());
+        let local_file = fs::canonicalize("tests/resources/external-examples/pets.json").unwrap();
+        let timeout = Some(Duration::from_millis(100));
+
+        match read_external_value(local_file.to_str().unwrap(), &document, timeout) {
+            Err(ParseError::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::TimedOut),
+            result => panic!(
+                "expected the example to be fetched from the server, got {:?}",
+                result
+            ),
+        }
+        match read_external_value("file:///etc/hostname", &document, timeout) {
+            Err(ParseError::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::InvalidInput),
+            result => panic!("expected a non-http URL to be rejected, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_times_out_fetching_from_an_unresponsive_server() {
+        // The connection is accepted by the operating system but never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api.yaml", listener.local_addr().unwrap());
+
+        let error = parse_document::<JsonValue>(
+            &url,
+            &ParseOptions {
+                timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        match error {
+            ParseError::Io(error) => assert_eq!(error.kind(), io::ErrorKind::TimedOut),
+            error => panic!("expected a timed out I/O error, got {:?}", error),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// Operation listed in a selection file
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct SelectedOperation {
+    ///exact path name of the operation
+    path: String,
+    ///http method of the operation
+    method: String,
+}
+
+/// Reads the operations to select from a JSON file.
+///
+/// The file contains an array of `{"path": "/pets", "method": "get"}` objects. Paths are matched exactly and methods case-insensitively.
+///
+/// # Arguments
+///
+/// * `path` - A reference to the path of the selection file.
+///
+/// # Returns
+///
+/// * `Result<Vec<(String, String)>, Box<dyn std::error::Error>>` - The selected path and lowercase method pairs, or an error naming the file if it cannot be read or parsed.
+pub fn load_selection(path: &Path) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let contents = fs::read_to_string(path).map_err(|error| {
+        format!(
+            "Could not read selection file {}: {}",
+            path.display(),
+            error
+        )
+    })?;
+    let selected_operations: Vec<SelectedOperation> =
+        serde_json::from_str(&contents).map_err(|error| {
+            format!(
+                "Could not parse selection file {}: {}",
+                path.display(),
+                error
+            )
+        })?;
+    Ok(selected_operations
+        .into_iter()
+        .map(|operation| (operation.path, operation.method.to_lowercase()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_loads_selection_with_lowercase_methods() {
+        let selection = load_selection(Path::new("tests/resources/selection.json")).unwrap();
+
+        assert_eq!(
+            selection,
+            vec![
+                (String::from("/pet/{petId}"), String::from("get")),
+                (String::from("/store/order"), String::from("post")),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_names_the_selection_file_that_cannot_be_read_or_parsed() {
+        let error =
+            load_selection(Path::new("tests/resources/missing-selection.json")).unwrap_err();
+        assert!(
+            error.to_string().starts_with(
+                "Could not read selection file tests/resources/missing-selection.json: "
+            )
+        );
+        let error = load_selection(Path::new("tests/resources/petstore.yaml")).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Could not parse selection file tests/resources/petstore.yaml: ")
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_yaml::Value as YamlValue;
+
+/// Collection style used when the output document is written as YAML
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum YamlStyle {
+    /// Indented block collections, one entry per line
+    #[default]
+    Block,
+    /// Top-level keys one per line with their values written as inline flow collections
+    Flow,
+}
+
+/// Format the output document is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// JSON, compact unless an indentation is given
+    Json,
+    /// YAML in the collection style given with `--yaml-style`
+    Yaml,
+    /// TOML, experimental since documents with `null` values cannot be written
+    Toml,
+}
+
+/// Serializes a value into a TOML string.
+///
+/// TOML has no `null`, so values containing `null`, e.g. `example: null`, cannot be written.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the value to serialize, which must serialize as a map.
+///
+/// # Returns
+///
+/// * `Result<String, toml::ser::Error>` - A Result containing the TOML string, or a toml::ser::Error if the value cannot be represented in TOML.
+pub fn to_toml_string<T>(value: &T) -> Result<String, toml::ser::Error>
+where
+    T: Serialize,
+{
+    toml::to_string_pretty(value)
+}
+
+/// Serializes a value into a JSON string, pretty printed with the given indentation width.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the value to serialize.
+/// * `indent` - Number of spaces per nesting level, or None for compact output on a single line.
+///
+/// # Returns
+///
+/// * `Result<String, serde_json::Error>` - A Result containing the JSON string, or a serde_json::Error if serialization fails.
+pub fn to_json_string<T>(value: &T, indent: Option<usize>) -> Result<String, serde_json::Error>
+where
+    T: Serialize,
+{
+    let Some(indent) = indent else {
+        return serde_json::to_string(value);
+    };
+    let indent = " ".repeat(indent);
+    let mut writer = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut writer, formatter);
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(writer).expect("JSON output is always valid UTF-8"))
+}
+
+/// Serializes a value into a YAML string using the requested collection style.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the value to serialize.
+/// * `style` - Collection style of the output.
+/// * `indent` - Number of spaces per nesting level of block collections, or None for the default of two spaces. Ignored with the flow style.
+///
+/// # Returns
+///
+/// * `Result<String, serde_yaml::Error>` - A Result containing the YAML string, or a serde_yaml::Error if serialization fails.
+pub fn to_yaml_string<T>(
+    value: &T,
+    style: YamlStyle,
+    indent: Option<usize>,
+) -> Result<String, serde_yaml::Error>
+where
+    T: Serialize,
+{
+    match style {
+        YamlStyle::Block => {
+            let yaml = serde_yaml::to_string(value)?;
+            Ok(match indent {
+                Some(indent) => reindent_block_yaml(&yaml, indent),
+                None => yaml,
+            })
+        }
+        YamlStyle::Flow => match serde_yaml::to_value(value)? {
+            YamlValue::Mapping(map) => {
+                let mut result = String::new();
+                for (key, value) in map.iter() {
+                    result.push_str(&format!("{}: {}\n", flow_scalar(key)?, flow_value(value)?));
+                }
+                Ok(result)
+            }
+            value => Ok(format!("{}\n", flow_value(&value)?)),
+        },
+    }
+}
+
+/// Serializes a value into YAML written as a single flow collection on one line, e.g. for pasting into a ticket.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the value to serialize.
+///
+/// # Returns
+///
+/// * `Result<String, serde_yaml::Error>` - A Result containing the YAML line without a line break, or a serde_yaml::Error if serialization fails.
+pub fn to_oneline_yaml_string<T>(value: &T) -> Result<String, serde_yaml::Error>
+where
+    T: Serialize,
+{
+    flow_value(&serde_yaml::to_value(value)?)
+}
+
+/// Changes the indentation of block style YAML written with the two space indentation of `serde_yaml`.
+///
+/// Every nesting level is indented by `indent` spaces. Contents of sequence entries stay aligned after their `- ` indicator,
+/// and the lines of block scalars keep their indentation relative to the first line, so the document keeps its content.
+///
+/// # Arguments
+///
+/// * `yaml` - Block style YAML using two space indentation
+/// * `indent` - Number of spaces per nesting level
+///
+/// # Returns
+///
+/// * `String` - The reindented YAML
+fn reindent_block_yaml(yaml: &str, indent: usize) -> String {
+    // Columns where the content of the enclosing collections starts, as (original column, new column)
+    let mut columns: Vec<(usize, usize)> = vec![(0, 0)];
+    // Original column of the key introducing the block scalar being copied and how much its lines are shifted
+    let mut block_scalar: Option<(usize, isize)> = None;
+    let mut result = String::with_capacity(yaml.len());
+    for line in yaml.lines() {
+        let content = line.trim_start_matches(' ');
+        let column = line.len() - content.len();
+        if let Some((key_column, shift)) = block_scalar {
+            if content.is_empty() || column > key_column {
+                if !content.is_empty() {
+                    result.push_str(&" ".repeat(column.saturating_add_signed(shift)));
+                }
+                result.push_str(content);
+                result.push('\n');
+                continue;
+            }
+            block_scalar = None;
+        }
+        while columns
+            .last()
+            .is_some_and(|(original, _)| *original > column)
+        {
+            columns.pop();
+        }
+        let new_column = match columns.last().copied() {
+            Some((original, new)) if original == column => new,
+            Some((_, new)) => {
+                columns.push((column, new + indent));
+                new + indent
+            }
+            None => column,
+        };
+        // Contents of sequence entries start after their indicators
+        let mut entry = content;
+        let (mut original_column, mut new_entry_column) = (column, new_column);
+        while let Some(rest) = entry.strip_prefix("- ") {
+            entry = rest;
+            original_column += 2;
+            new_entry_column += 2;
+            columns.push((original_column, new_entry_column));
+        }
+        if ["|", "|-", "|+", ">", ">-", ">+"]
+            .iter()
+            .any(|header| entry == *header || entry.ends_with(&format!(" {}", header)))
+        {
+            let shift = (new_entry_column + indent) as isize - (original_column + 2) as isize;
+            block_scalar = Some((original_column, shift));
+        }
+        result.push_str(&" ".repeat(new_column));
+        result.push_str(content);
+        result.push('\n');
+    }
+    result
+}
+
+/// Writes a YAML value as a single line flow collection.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the YAML value to write.
+///
+/// # Returns
+///
+/// * `Result<String, serde_yaml::Error>` - A Result containing the flow styled value, or a serde_yaml::Error if a scalar cannot be serialized.
+fn flow_value(value: &YamlValue) -> Result<String, serde_yaml::Error> {
+    match value {
+        YamlValue::Mapping(map) => {
+            let entries = map
+                .iter()
+                .map(|(key, value)| Ok(format!("{}: {}", flow_scalar(key)?, flow_value(value)?)))
+                .collect::<Result<Vec<String>, serde_yaml::Error>>()?;
+            Ok(format!("{{{}}}", entries.join(", ")))
+        }
+        YamlValue::Sequence(items) => {
+            let entries = items
+                .iter()
+                .map(flow_value)
+                .collect::<Result<Vec<String>, serde_yaml::Error>>()?;
+            Ok(format!("[{}]", entries.join(", ")))
+        }
+        YamlValue::Tagged(tagged) => Ok(format!("{} {}", tagged.tag, flow_value(&tagged.value)?)),
+        scalar => flow_scalar(scalar),
+    }
+}
+
+/// Writes a YAML scalar so that it is safe to use inside flow collections.
+///
+/// Plain scalars can not contain flow indicators inside flow collections and block scalars can not be used at all,
+/// so such strings are written as double quoted scalars instead.
+///
+/// # Arguments
+///
+/// * `value` - A reference to the YAML scalar to write.
+///
+/// # Returns
+///
+/// * `Result<String, serde_yaml::Error>` - A Result containing the scalar, or a serde_yaml::Error if the scalar cannot be serialized.
+fn flow_scalar(value: &YamlValue) -> Result<String, serde_yaml::Error> {
+    let text = serde_yaml::to_string(value)?;
+    let text = text.trim_end_matches('\n');
+    match value {
+        YamlValue::String(string) => {
+            let quoted = text.starts_with('\'') || text.starts_with('"');
+            let unsafe_plain = text.contains([',', '[', ']', '{', '}', ':', '#']);
+            if text.contains('\n') || (!quoted && unsafe_plain) {
+                Ok(serde_json::to_string(string).expect("Strings are always serializable"))
+            } else {
+                Ok(text.to_string())
+            }
+        }
+        YamlValue::Mapping(_) | YamlValue::Sequence(_) | YamlValue::Tagged(_) => flow_value(value),
+        _ => Ok(text.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_reindents_block_style_yaml() {
+        let value = json!({
+            "paths": {"/pets": {"get": {
+                "description": "first line\n  indented line\nlast line",
+                "parameters": [{"name": "id", "schema": {"type": "string"}}],
+                "tags": ["pet"]
+            }}}
+        });
+
+        let yaml = to_yaml_string(&value, YamlStyle::Block, Some(4)).unwrap();
+        let parsed: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(parsed, value);
+        assert_eq!(
+            yaml,
+            "paths:\n    /pets:\n        get:\n            description: |-\n                first line\n                  indented line\n                last line\n            \
+            parameters:\n            - name: id\n              schema:\n                  type: string\n            tags:\n            - pet\n"
+        );
+    }
+
+    #[test]
+    fn it_writes_json_with_configured_indent() {
+        let value = json!({"paths": {"/pets": {}}});
+
+        assert_eq!(
+            to_json_string(&value, None).unwrap(),
+            "{\"paths\":{\"/pets\":{}}}"
+        );
+        assert_eq!(
+            to_json_string(&value, Some(4)).unwrap(),
+            "{\n    \"paths\": {\n        \"/pets\": {}\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn it_writes_nested_collections_in_flow_style() {
+        let value = json!({
+            "openapi": "3.0.0",
+            "paths": {"/pet/{petId}": {"get": {"tags": ["pet"], "summary": "Find pet, by id"}}}
+        });
+
+        let yaml = to_yaml_string(&value, YamlStyle::Flow, None).unwrap();
+
+        assert_eq!(
+            yaml,
+            "openapi: 3.0.0\npaths: {\"/pet/{petId}\": {get: {tags: [pet], summary: \"Find pet, by id\"}}}\n"
+        );
+    }
+
+    #[test]
+    fn it_writes_flow_style_that_parses_back_to_the_same_content() {
+        let value = json!({
+            "description": "multi\nline: text",
+            "items": [{"name": "a: b"}, {"name": "#hash"}, {"count": 1, "enabled": true, "empty": null}],
+            "quoted": "'single'"
+        });
+
+        let yaml = to_yaml_string(&value, YamlStyle::Flow, None).unwrap();
+        let parsed: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn it_writes_the_whole_document_on_one_line() {
+        let value = json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Pets", "description": "multi\nline"},
+            "paths": {}
+        });
+
+        let yaml = to_oneline_yaml_string(&value).unwrap();
+
+        assert_eq!(
+            yaml,
+            "{openapi: 3.0.0, info: {title: Pets, description: \"multi\\nline\"}, paths: {}}"
+        );
+        let parsed: serde_json::Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, value);
+    }
+}
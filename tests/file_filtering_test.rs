@@ -1,18 +1,20 @@
 use insta::assert_snapshot;
 use rexpect::session::spawn_command;
 use std::fs::read_to_string;
-use std::io::Write;
+use std::io::{Read, Write};
+use std::net::TcpListener;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::str::from_utf8;
+use std::thread;
 
 #[test]
-fn it_filters_yaml_files() -> Result<(), Box<dyn (std::error::Error)>> {
+fn it_filters_yaml_files() -> Result<(), Box<dyn std::error::Error>> {
     let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
 
     let cmd = define_command(
         bin_path,
-        "--path *createWithList tests/resources/petstore.yaml".into(),
+        "--quiet --path *createWithList tests/resources/petstore.yaml".into(),
     );
 
     let mut process = spawn_command(cmd, Some(30000))?;
@@ -24,12 +26,12 @@ fn it_filters_yaml_files() -> Result<(), Box<dyn (std::error::Error)>> {
 }
 
 #[test]
-fn it_filters_json_files() -> Result<(), Box<dyn (std::error::Error)>> {
+fn it_filters_json_files() -> Result<(), Box<dyn std::error::Error>> {
     let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
 
     let cmd = define_command(
         bin_path,
-        "--tag item tests/resources/user-reference.json".into(),
+        "--quiet --tag item tests/resources/user-reference.json".into(),
     );
 
     let mut process = spawn_command(cmd, Some(30000))?;
@@ -41,7 +43,96 @@ fn it_filters_json_files() -> Result<(), Box<dyn (std::error::Error)>> {
 }
 
 #[test]
-fn it_reports_parsing_errors() -> Result<(), Box<dyn (std::error::Error)>> {
+fn it_filters_reusable_path_items() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --path /pets tests/resources/path-items-3.1.yaml".into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_filters_yaml_files_with_block_style() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --path /pet/{petId} --method get --yaml-style block tests/resources/petstore.yaml"
+            .into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_filters_yaml_files_with_flow_style() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --path /pet/{petId} --method get --yaml-style flow tests/resources/petstore.yaml"
+            .into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_filters_yaml_files_on_one_line() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --path /store/inventory --oneline tests/resources/petstore.yaml".into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_eq!(result.trim_end().lines().count(), 1);
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_converts_schemas_to_json_schema_2020_12() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --path /pets --schema-dialect 2020-12 tests/resources/nullable-schemas.yaml"
+            .into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_reports_parsing_errors() -> Result<(), Box<dyn std::error::Error>> {
     let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
 
     let cmd = define_command(
@@ -58,7 +149,7 @@ fn it_reports_parsing_errors() -> Result<(), Box<dyn (std::error::Error)>> {
 }
 
 #[test]
-fn it_reports_io_errors() -> Result<(), Box<dyn (std::error::Error)>> {
+fn it_reports_io_errors() -> Result<(), Box<dyn std::error::Error>> {
     let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
 
     let cmd = define_command(bin_path, "--tag item tests/resources/not_found".into());
@@ -73,8 +164,42 @@ fn it_reports_io_errors() -> Result<(), Box<dyn (std::error::Error)>> {
 }
 
 #[test]
-fn it_handled_piped_input_with_explicit_pipe_marker_yaml()
--> Result<(), Box<dyn (std::error::Error)>> {
+fn it_filters_with_tag_groups() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--quiet --tag-group billing tests/resources/tag-groups.yaml".into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_reports_unknown_tag_groups() -> Result<(), Box<dyn std::error::Error>> {
+    let bin_path = assert_cmd::cargo::cargo_bin("openapiv3-filter");
+
+    let cmd = define_command(
+        bin_path,
+        "--tag-group payments tests/resources/tag-groups.yaml".into(),
+    );
+
+    let mut process = spawn_command(cmd, Some(30000))?;
+
+    let result = process.exp_eof()?;
+
+    assert_snapshot!(result.trim_end());
+    Ok(())
+}
+
+#[test]
+fn it_handled_piped_input_with_explicit_pipe_marker_yaml() -> Result<(), Box<dyn std::error::Error>>
+{
     // Read the test file
     let contents = read_to_string("tests/resources/petstore.yaml")?;
 
@@ -107,7 +232,7 @@ fn it_handled_piped_input_with_explicit_pipe_marker_yaml()
 
 #[test]
 fn it_handled_piped_input_without_explicit_pipe_marker_yaml()
--> Result<(), Box<dyn (std::error::Error)>> {
+-> Result<(), Box<dyn std::error::Error>> {
     // Read the test file
     let contents = read_to_string("tests/resources/petstore.yaml")?;
 
@@ -137,9 +262,66 @@ fn it_handled_piped_input_without_explicit_pipe_marker_yaml()
     Ok(())
 }
 
+#[test]
+fn it_converts_piped_input_without_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let contents = read_to_string("tests/resources/info-extensions.yaml")?;
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--format", "json"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(contents.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    assert!(output.status.success());
+    let converted: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let input: serde_json::Value = serde_yaml::from_str(&contents)?;
+    assert_eq!(converted, input);
+    Ok(())
+}
+
+#[test]
+fn it_rejects_invalid_arguments_with_piped_input() -> Result<(), Box<dyn std::error::Error>> {
+    let contents = read_to_string("tests/resources/petstore.yaml")?;
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--pth", "/user"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The document may not be read before the arguments are rejected
+        let _ = stdin.write_all(contents.as_bytes());
+    }
+    let output = child.wait_with_output()?;
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(from_utf8(&output.stderr)?.contains("unexpected argument '--pth'"));
+    Ok(())
+}
+
+#[test]
+fn it_shows_help_and_version_successfully_with_piped_input()
+-> Result<(), Box<dyn std::error::Error>> {
+    for argument in ["--help", "--version"] {
+        let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .arg(argument)
+            .stdin(Stdio::piped())
+            .output()?;
+
+        assert_eq!(output.status.code(), Some(0), "{}", argument);
+        assert!(!output.stdout.is_empty());
+    }
+    Ok(())
+}
+
 #[test]
 fn it_handled_piped_input_without_explicit_pipe_marker_without_filtering_yaml()
--> Result<(), Box<dyn (std::error::Error)>> {
+-> Result<(), Box<dyn std::error::Error>> {
     // Read the test file
     let contents = read_to_string("tests/resources/user-reference.yaml")?;
 
@@ -169,7 +351,7 @@ fn it_handled_piped_input_without_explicit_pipe_marker_without_filtering_yaml()
 
 #[test]
 fn it_handled_piped_input_without_explicit_pipe_marker_without_filtering_json()
--> Result<(), Box<dyn (std::error::Error)>> {
+-> Result<(), Box<dyn std::error::Error>> {
     // Read the test file
     let contents = read_to_string("tests/resources/user-reference.json")?;
 
@@ -197,8 +379,767 @@ fn it_handled_piped_input_without_explicit_pipe_marker_without_filtering_json()
     Ok(())
 }
 
+#[test]
+fn it_uses_discovered_config_file() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .current_dir("tests/resources/config")
+        .arg("../user-reference.yaml")
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(filtered_paths(&output.stdout)?, vec!["/users"]);
+    Ok(())
+}
+
+#[test]
+fn it_prefers_filter_file_over_discovered_config_file() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .current_dir("tests/resources/config")
+        .arg("--filter-file")
+        .arg("get-filter.toml")
+        .arg("../user-reference.yaml")
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(filtered_paths(&output.stdout)?, vec!["/users/{userId}"]);
+    Ok(())
+}
+
+#[test]
+fn it_prefers_arguments_over_filter_file() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .current_dir("tests/resources/config")
+        .arg("--filter-file")
+        .arg("get-filter.toml")
+        .arg("--method")
+        .arg("post")
+        .arg("../user-reference.yaml")
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(filtered_paths(&output.stdout)?, vec!["/users"]);
+    Ok(())
+}
+
+#[test]
+fn it_prefers_environment_variables_over_filter_file() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .current_dir("tests/resources/config")
+            .env("OPENAPIV3_FILTER_METHOD", "post,put")
+            .args(["--filter-file", "get-filter.toml"])
+            .args(args)
+            .arg("../user-reference.yaml")
+            .output()
+    };
+
+    let output = run(&[])?;
+    assert!(output.status.success());
+    assert_eq!(filtered_paths(&output.stdout)?, vec!["/users"]);
+
+    let output = run(&["--method", "get"])?;
+    assert!(output.status.success());
+    assert_eq!(filtered_paths(&output.stdout)?, vec!["/users/{userId}"]);
+    Ok(())
+}
+
+#[test]
+fn it_exits_with_distinct_codes_for_failure_classes() -> Result<(), Box<dyn std::error::Error>> {
+    let exit_code = |args: &[&str]| -> Result<Option<i32>, Box<dyn std::error::Error>> {
+        Ok(
+            Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+                .args(args)
+                .output()?
+                .status
+                .code(),
+        )
+    };
+
+    assert_eq!(
+        exit_code(&["--tag", "item", "tests/resources/user-reference.yaml"])?,
+        Some(0)
+    );
+    assert_eq!(
+        exit_code(&["--tag-group", "payments", "tests/resources/tag-groups.yaml"])?,
+        Some(1)
+    );
+    assert_eq!(
+        exit_code(&["--no-such-flag", "tests/resources/user-reference.yaml"])?,
+        Some(1)
+    );
+    assert_eq!(
+        exit_code(&["--tag", "item", "tests/resources/invalid-content"])?,
+        Some(2)
+    );
+    assert_eq!(
+        exit_code(&["--tag", "item", "tests/resources/not_found"])?,
+        Some(3)
+    );
+    assert_eq!(
+        exit_code(&[
+            "--path",
+            "non-matching-path",
+            "tests/resources/user-reference.yaml"
+        ])?,
+        Some(0)
+    );
+    assert_eq!(
+        exit_code(&[
+            "--path",
+            "non-matching-path",
+            "--fail-on-empty",
+            "tests/resources/user-reference.yaml"
+        ])?,
+        Some(4)
+    );
+    Ok(())
+}
+
+#[test]
+fn it_rejects_selection_files_mixed_with_matching_filters() -> Result<(), Box<dyn std::error::Error>>
+{
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .arg("tests/resources/petstore.yaml")
+            .output()
+    };
+
+    let output = run(&["--selection-file", "tests/resources/selection.json"])?;
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        filtered_paths(&output.stdout)?,
+        vec!["/pet/{petId}", "/store/order"]
+    );
+
+    let output = run(&[
+        "--selection-file",
+        "tests/resources/selection.json",
+        "--tag",
+        "store",
+    ])?;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        from_utf8(&output.stdout)?,
+        "--selection-file selects the exact operations and cannot be combined with --tag\n"
+    );
+
+    let output = run(&["--selection-file", "tests/resources/missing-selection.json"])?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        from_utf8(&output.stdout)?
+            .starts_with("Could not read selection file tests/resources/missing-selection.json: ")
+    );
+    Ok(())
+}
+
+#[test]
+fn it_keeps_paths_in_input_order() -> Result<(), Box<dyn std::error::Error>> {
+    let input: serde_yaml::Value =
+        serde_yaml::from_str(&read_to_string("tests/resources/petstore.yaml")?)?;
+    let expected_paths: Vec<String> = input["paths"]
+        .as_mapping()
+        .unwrap()
+        .iter()
+        .filter(|(_, path)| path.get("get").is_some())
+        .filter_map(|(key, _)| key.as_str().map(String::from))
+        .collect();
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--method", "get", "tests/resources/petstore.yaml"])
+        .output()?;
+
+    assert!(expected_paths.len() > 2);
+    assert_eq!(filtered_paths(&output.stdout)?, expected_paths);
+    Ok(())
+}
+
+#[test]
+fn it_lists_tags_without_filtering() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--list", "tags", "tests/resources/petstore.yaml"])
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?, "pet\nstore\nuser\n");
+    Ok(())
+}
+
+#[test]
+fn it_substitutes_env_variables_before_parsing() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--env-substitute", "tests/resources/env-servers.yaml"])
+        .env("API_HOST", "api.example.com")
+        .output()?;
+    let document: serde_yaml::Value = serde_yaml::from_slice(&output.stdout)?;
+    assert_eq!(
+        document["servers"][0]["url"].as_str(),
+        Some("https://api.example.com/v1")
+    );
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--env-substitute", "tests/resources/env-servers.yaml"])
+        .env_remove("API_HOST")
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    Ok(())
+}
+
+fn filtered_paths(stdout: &[u8]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let document: serde_yaml::Value = serde_yaml::from_slice(stdout)?;
+    Ok(document["paths"]
+        .as_mapping()
+        .map(|paths| {
+            paths
+                .keys()
+                .filter_map(|key| key.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
 fn define_command(bin_path: PathBuf, command: String) -> Command {
     let mut cmd = Command::new(bin_path);
     cmd.args(command.split(" "));
     cmd
 }
+
+#[test]
+fn it_filters_documents_fetched_from_url() -> Result<(), Box<dyn std::error::Error>> {
+    let document = read_to_string("tests/resources/user-reference.json")?;
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+    let server = thread::spawn(move || -> std::io::Result<()> {
+        let (mut stream, _) = listener.accept()?;
+        let mut request = [0; 4096];
+        let _ = stream.read(&mut request)?;
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            document.len(),
+            document
+        )
+    });
+
+    let remote_output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--tag", "item", &format!("http://{}/spec", address)])
+        .output()?;
+    server.join().unwrap()?;
+    let local_output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--tag", "item", "tests/resources/user-reference.json"])
+        .output()?;
+
+    assert_eq!(remote_output.status.code(), Some(0));
+    assert_eq!(
+        from_utf8(&remote_output.stdout)?,
+        from_utf8(&local_output.stdout)?
+    );
+    Ok(())
+}
+
+#[test]
+fn it_exits_with_io_error_code_when_url_cannot_be_fetched() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Binding and dropping a listener gives a local port that refuses connections
+    let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--tag", "item", &format!("http://{}/spec.yaml", address)])
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(3));
+    Ok(())
+}
+
+#[test]
+fn it_exits_with_io_error_code_when_fetching_times_out() -> Result<(), Box<dyn std::error::Error>> {
+    // The connection is accepted by the operating system but never answered
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}/spec.yaml", listener.local_addr()?);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--timeout", "1", &url])
+        .output()?;
+    let zero_output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["--timeout", "0", &url])
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(3));
+    assert!(from_utf8(&output.stdout)?.contains("timed out after 1 seconds"));
+    // A zero timeout is an invalid argument, not an input document that cannot be parsed
+    assert_eq!(zero_output.status.code(), Some(1));
+    assert!(from_utf8(&zero_output.stderr)?.contains("invalid value '0' for '--timeout"));
+    Ok(())
+}
+
+#[test]
+fn it_suppresses_warnings_with_quiet() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .output()
+    };
+
+    let output = run(&["--path", "/pets", "tests/resources/dangling-reference.yaml"])?;
+    let quiet_output = run(&[
+        "--path",
+        "/pets",
+        "--quiet",
+        "tests/resources/dangling-reference.yaml",
+    ])?;
+
+    assert_eq!(
+        from_utf8(&output.stderr)?.trim_end(),
+        "warning: reference '#/components/schemas/Missing' does not resolve to any content in the filtered document\nkept 1 path, 0 schemes, 0 schemas"
+    );
+    assert!(quiet_output.stderr.is_empty());
+    assert_eq!(output.stdout, quiet_output.stdout);
+    assert_eq!(quiet_output.status.code(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn it_rejects_dangling_input_refs_with_check_input() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .output()
+    };
+
+    let output = run(&["--check-input", "tests/resources/dangling-reference.yaml"])?;
+    let valid_output = run(&["--check-input", "tests/resources/petstore.yaml"])?;
+
+    assert_eq!(
+        from_utf8(&output.stdout)?,
+        "Input document has 1 reference(s) that do not resolve to any content:\n  \
+         '#/components/schemas/Missing' at #/paths/~1pets/get/responses/200/content/application~1json/schema\n"
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(valid_output.status.code(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn it_combines_paths_file_with_path_arguments() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .output()
+    };
+
+    let output = run(&[
+        "--paths-file",
+        "tests/resources/paths.txt",
+        "--path",
+        "/user/login",
+        "tests/resources/petstore.yaml",
+    ])?;
+    let empty_output = run(&[
+        "--paths-file",
+        "tests/resources/empty-paths.txt",
+        "tests/resources/petstore.yaml",
+    ])?;
+
+    let document: serde_yaml::Value = serde_yaml::from_slice(&output.stdout)?;
+    let paths: Vec<&str> = document["paths"]
+        .as_mapping()
+        .unwrap()
+        .keys()
+        .filter_map(|path| path.as_str())
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/pet/{petId}",
+            "/store/inventory",
+            "/store/order",
+            "/store/order/{orderId}",
+            "/user/login"
+        ]
+    );
+    assert_eq!(
+        from_utf8(&empty_output.stdout)?.trim_end(),
+        "Paths file 'tests/resources/empty-paths.txt' lists no paths on its 2 line(s)"
+    );
+    assert_eq!(empty_output.status.code(), Some(1));
+
+    // A missing paths file is a usage error, not an unreadable input document
+    let missing_output = run(&[
+        "--paths-file",
+        "tests/resources/missing-paths.txt",
+        "tests/resources/petstore.yaml",
+    ])?;
+    assert!(
+        from_utf8(&missing_output.stdout)?
+            .starts_with("Could not read paths file 'tests/resources/missing-paths.txt': ")
+    );
+    assert_eq!(missing_output.status.code(), Some(1));
+    Ok(())
+}
+
+#[test]
+fn it_writes_toml_output() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .output()
+    };
+
+    let output = run(&[
+        "--format",
+        "toml",
+        "--path",
+        "/user/login",
+        "tests/resources/petstore.yaml",
+    ])?;
+    let null_output = run(&["--format", "toml", "tests/resources/null-extension.yaml"])?;
+
+    let document: toml::Table = toml::from_str(from_utf8(&output.stdout)?)?;
+    assert_eq!(document["openapi"].as_str(), Some("3.0.3"));
+    let paths: Vec<&String> = document["paths"].as_table().unwrap().keys().collect();
+    assert_eq!(paths, vec!["/user/login"]);
+    assert_eq!(
+        from_utf8(&null_output.stdout)?.trim_end(),
+        "Failed to write the document: TOML cannot represent the document (unsupported unit type), e.g. because it has null values"
+    );
+    assert_eq!(null_output.status.code(), Some(1));
+    Ok(())
+}
+
+#[test]
+fn it_previews_paths_matched_by_patterns() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--preview",
+            "--path",
+            "/user/log*",
+            "--path",
+            "/users",
+            "tests/resources/petstore.yaml",
+        ])
+        .output()?;
+
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "path '/user/log*' matches 2 paths: /user/login, /user/logout\n\
+         path '/users' matches no paths\n\
+         kept 2 paths, 0 schemes, 0 schemas\n"
+    );
+    assert!(!from_utf8(&output.stdout)?.contains("matches"));
+    Ok(())
+}
+
+#[test]
+fn it_preserves_comments_of_kept_paths_and_operations() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--preserve-comments",
+            "--path",
+            "/pets",
+            "--method",
+            "post",
+            "--indent",
+            "4",
+            "tests/resources/comments.yaml",
+        ])
+        .output()?;
+
+    let stdout = from_utf8(&output.stdout)?;
+    assert!(stdout.contains(
+        "paths:\n    # Pet operations, owned by the pets team\n    /pets:\n        # Creates a pet\n        post:\n"
+    ));
+    assert!(!stdout.contains("# Lists pets"));
+    assert!(!stdout.contains("# Deprecated"));
+    assert!(!stdout.contains("x-openapiv3-filter-comments"));
+    Ok(())
+}
+
+#[test]
+fn it_never_matches_preserved_comments() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| {
+        Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(args)
+            .args(["--preserve-comments", "tests/resources/comments.yaml"])
+            .output()
+    };
+
+    let output = run(&["--grep", "Lists pets"])?;
+    assert!(output.status.success());
+    assert!(!from_utf8(&output.stdout)?.contains("/pets"));
+
+    let output = run(&["--jsonpath", "$['x-openapiv3-filter-comments']"])?;
+    assert!(output.status.success());
+    assert!(!from_utf8(&output.stdout)?.contains("/pets"));
+
+    let output = run(&["--grep", "\"Pets\""])?;
+    assert!(from_utf8(&output.stdout)?.contains("    # Lists pets\n    get:\n"));
+    Ok(())
+}
+
+#[test]
+fn it_keeps_info_with_its_extensions() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--path",
+            "/pets",
+            "--format",
+            "json",
+            "tests/resources/info-extensions.yaml",
+        ])
+        .output()?;
+
+    let filtered: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let input: serde_json::Value =
+        serde_yaml::from_str(&read_to_string("tests/resources/info-extensions.yaml")?)?;
+    assert_eq!(filtered["info"], input["info"]);
+    assert_eq!(filtered["info"]["contact"]["x-team"], "pets");
+    Ok(())
+}
+
+#[test]
+fn it_unions_method_presets_with_methods() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |args: &[&str]| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(["--path", "/pet/{petId}", "--format", "json"])
+            .args(args)
+            .arg("tests/resources/petstore.yaml")
+            .output()?;
+        let filtered: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        Ok(filtered["paths"]["/pet/{petId}"]
+            .as_object()
+            .map(|path_item| path_item.keys().cloned().collect())
+            .unwrap_or_default())
+    };
+
+    assert_eq!(run(&["--read-only"])?, vec!["get"]);
+    assert_eq!(run(&["--mutations"])?, vec!["post", "delete"]);
+    assert_eq!(
+        run(&["--read-only", "--method", "delete"])?,
+        vec!["get", "delete"]
+    );
+    Ok(())
+}
+
+#[test]
+fn it_warns_about_empty_responses_unless_allowed() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |allow_empty_responses: bool| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"));
+        cmd.args(["--path", "/pets", "tests/resources/empty-responses.yaml"]);
+        if allow_empty_responses {
+            cmd.arg("--allow-empty-responses");
+        }
+        cmd.output()
+    };
+
+    let output = run(false)?;
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "warning: operation 'get /pets' has no responses, which OpenAPI requires\n\
+         kept 1 path, 0 schemes, 0 schemas\n"
+    );
+    assert!(from_utf8(&output.stdout)?.contains("summary: Lists pets"));
+
+    let output = run(true)?;
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "kept 1 path, 0 schemes, 0 schemas\n"
+    );
+    assert!(from_utf8(&output.stdout)?.contains("summary: Lists pets"));
+
+    for option in ["--stub", "--ensure-default-response"] {
+        let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args([
+                "--path",
+                "/pets",
+                option,
+                "tests/resources/empty-responses.yaml",
+            ])
+            .output()?;
+        assert!(output.status.success());
+        assert_eq!(
+            from_utf8(&output.stderr)?,
+            "kept 1 path, 0 schemes, 0 schemas\n"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn it_warns_about_operations_left_without_responses() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |ensure_default_response: bool| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"));
+        cmd.args([
+            "--path",
+            "/pets",
+            "--keep-responses",
+            "200",
+            "tests/resources/empty-responses.yaml",
+        ]);
+        if ensure_default_response {
+            cmd.arg("--ensure-default-response");
+        }
+        cmd.output()
+    };
+
+    let output = run(false)?;
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "warning: operation 'get /pets' has no responses, which OpenAPI requires\n\
+         warning: operation 'post /pets' has no responses left after --keep-responses, which OpenAPI requires\n\
+         kept 1 path, 0 schemes, 0 schemas\n"
+    );
+
+    let output = run(true)?;
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "kept 1 path, 0 schemes, 0 schemas\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn it_warns_only_once_when_sampling() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--path",
+            "/pets",
+            "--sample",
+            "2",
+            "tests/resources/empty-responses.yaml",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "warning: operation 'get /pets' has no responses, which OpenAPI requires\n\
+         kept 1 path, 0 schemes, 0 schemas\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn it_checks_examples_against_their_schemas() -> Result<(), Box<dyn std::error::Error>> {
+    let run = |strict: bool| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"));
+        cmd.args(["--check-examples", "tests/resources/invalid-examples.yaml"]);
+        if strict {
+            cmd.arg("--strict");
+        }
+        cmd.output()
+    };
+
+    let output = run(false)?;
+    assert_eq!(output.status.code(), Some(0));
+    assert!(from_utf8(&output.stderr)?.starts_with(
+        "warning: example at #/paths/~1pets/get/responses/200/content/application~1json/examples/pets does not match its schema: $[1] is missing the required property 'name'\n\
+         warning: example at #/components/schemas/Pet/properties/age/example does not match its schema: $ is less than the minimum 0\n"
+    ));
+
+    let output = run(true)?;
+    assert_eq!(output.status.code(), Some(1));
+    assert!(from_utf8(&output.stdout)?.starts_with(
+        "Filtered document has 2 example(s) that do not match their schema:\n  #/paths/"
+    ));
+    Ok(())
+}
+
+#[test]
+fn it_writes_summary_to_stderr() -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args(["tests/resources/petstore.yaml"])
+        .output()?;
+
+    assert_eq!(
+        from_utf8(&output.stderr)?,
+        "kept 13 paths, 2 schemes, 8 schemas\n"
+    );
+    assert!(!from_utf8(&output.stdout)?.contains("kept 13 paths"));
+    assert!(from_utf8(&output.stdout)?.starts_with("openapi:"));
+    assert_eq!(output.status.code(), Some(0));
+    Ok(())
+}
+
+#[test]
+fn it_filters_documents_matching_input_glob() -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = std::env::temp_dir().join("openapiv3-filter-input-glob");
+    let _ = std::fs::remove_dir_all(&output_dir);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--tag",
+            "item",
+            "--input-glob",
+            "tests/resources/user-reference.*",
+        ])
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(0));
+    for file_name in ["user-reference.json", "user-reference.yaml"] {
+        let single_output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+            .args(["--tag", "item", &format!("tests/resources/{}", file_name)])
+            .output()?;
+        assert_eq!(
+            read_to_string(output_dir.join(file_name))?,
+            from_utf8(&single_output.stdout)?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn it_keeps_the_directories_of_input_glob_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = std::env::temp_dir().join("openapiv3-filter-nested-glob");
+    let _ = std::fs::remove_dir_all(&output_dir);
+
+    let output = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"))
+        .args([
+            "--input-glob",
+            "tests/resources/nested-glob/**/openapi.yaml",
+        ])
+        .arg("--output-dir")
+        .arg(&output_dir)
+        .output()?;
+
+    assert_eq!(output.status.code(), Some(0));
+    for (directory, title) in [("pets", "Pets"), ("users", "Users")] {
+        let filtered = read_to_string(output_dir.join(directory).join("openapi.yaml"))?;
+        assert!(filtered.contains(&format!("title: {}", title)));
+    }
+    Ok(())
+}
+
+#[test]
+fn it_continues_input_glob_after_failures_unless_failing_fast()
+-> Result<(), Box<dyn std::error::Error>> {
+    let run = |output_dir: &PathBuf, fail_fast: bool| {
+        let _ = std::fs::remove_dir_all(output_dir);
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("openapiv3-filter"));
+        cmd.args(["--input-glob", "tests/resources/batch/*.yaml"])
+            .arg("--output-dir")
+            .arg(output_dir);
+        if fail_fast {
+            cmd.arg("--fail-fast");
+        }
+        cmd.output()
+    };
+
+    let output_dir = std::env::temp_dir().join("openapiv3-filter-batch");
+    let output = run(&output_dir, false)?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(from_utf8(&output.stdout)?.starts_with("tests/resources/batch/a-invalid.yaml: "));
+    assert!(output_dir.join("b-valid.yaml").exists());
+    assert!(!output_dir.join("a-invalid.yaml").exists());
+
+    let output_dir = std::env::temp_dir().join("openapiv3-filter-batch-fail-fast");
+    let output = run(&output_dir, true)?;
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output_dir.join("b-valid.yaml").exists());
+    Ok(())
+}
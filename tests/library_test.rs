@@ -0,0 +1,143 @@
+use openapiv3::OpenAPI;
+use openapiv3_filter::filter::content::reference_collector::find_dangling_refs;
+use openapiv3_filter::filter::openapi::{FilteringParameters, OpenAPIFilter};
+use openapiv3_filter::parser::{self, ParseOptions};
+use openapiv3_filter::{FilterError, Format, filter_str};
+
+fn petstore() -> Result<OpenAPI, Box<dyn std::error::Error>> {
+    let parsed = parser::parse_document::<OpenAPI>(
+        "tests/resources/petstore.yaml",
+        &ParseOptions::default(),
+    )?;
+    Ok(parsed.content().clone())
+}
+
+fn schema_names(openapi: &OpenAPI) -> Vec<&str> {
+    let mut names: Vec<&str> = openapi
+        .components
+        .iter()
+        .flat_map(|components| components.schemas.keys().map(String::as_str))
+        .collect();
+    names.sort();
+    names
+}
+
+fn assert_references_resolve(openapi: &OpenAPI) -> Result<(), Box<dyn std::error::Error>> {
+    let document = serde_json::to_value(openapi)?;
+    assert_eq!(find_dangling_refs(&document)?, Vec::<String>::new());
+    Ok(())
+}
+
+#[test]
+fn it_keeps_schemas_referenced_by_filtered_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let result = petstore()?.filter_by_parameters(FilteringParameters {
+        paths: Some(vec![String::from("/pet/{petId}")]),
+        ..Default::default()
+    })?;
+
+    assert_eq!(result.paths.paths.len(), 1);
+    assert!(result.paths.paths.contains_key("/pet/{petId}"));
+    assert_eq!(schema_names(&result), vec!["Category", "Pet", "Tag"]);
+    assert_references_resolve(&result)
+}
+
+#[test]
+fn it_keeps_only_the_tags_and_security_schemes_in_use() -> Result<(), Box<dyn std::error::Error>> {
+    let result = petstore()?.filter_by_parameters(FilteringParameters {
+        tags: Some(vec![String::from("store")]),
+        ..Default::default()
+    })?;
+
+    let tags: Vec<&str> = result.tags.iter().map(|tag| tag.name.as_str()).collect();
+    assert_eq!(tags, vec!["store"]);
+    assert_eq!(schema_names(&result), vec!["Order"]);
+    let components = result.components.as_ref().unwrap();
+    assert!(components.request_bodies.is_empty());
+    assert!(components.security_schemes.contains_key("api_key"));
+    assert!(!components.security_schemes.contains_key("petstore_auth"));
+    assert_references_resolve(&result)
+}
+
+#[test]
+fn it_keeps_references_resolvable_for_every_method() -> Result<(), Box<dyn std::error::Error>> {
+    let openapi = petstore()?;
+    for method in ["get", "put", "post", "delete"] {
+        let result = openapi.filter_by_parameters(FilteringParameters {
+            methods: Some(vec![String::from(method)]),
+            ..Default::default()
+        })?;
+
+        assert!(!result.paths.paths.is_empty());
+        assert!(result.paths.iter().all(|(_, path)| {
+            path.as_item()
+                .is_some_and(|path| path.iter().all(|(name, _)| name == method))
+        }));
+        assert_references_resolve(&result)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn it_removes_all_paths_when_nothing_matches() -> Result<(), Box<dyn std::error::Error>> {
+    let result = petstore()?.filter_by_parameters(FilteringParameters {
+        paths: Some(vec![String::from("/missing")]),
+        ..Default::default()
+    })?;
+
+    assert!(result.paths.paths.is_empty());
+    assert!(schema_names(&result).is_empty());
+    assert!(result.tags.is_empty());
+    Ok(())
+}
+
+#[test]
+fn it_keeps_components_in_source_order() -> Result<(), Box<dyn std::error::Error>> {
+    let source: serde_yaml::Value =
+        serde_yaml::from_str(&std::fs::read_to_string("tests/resources/petstore.yaml")?)?;
+    let result = petstore()?.filter_by_parameters(FilteringParameters {
+        paths: Some(vec![String::from("/*")]),
+        ..Default::default()
+    })?;
+    let components = result.components.as_ref().unwrap();
+
+    for (category, kept_names) in [
+        ("schemas", components.schemas.keys().collect::<Vec<_>>()),
+        (
+            "securitySchemes",
+            components.security_schemes.keys().collect(),
+        ),
+    ] {
+        let source_names: Vec<&str> = source["components"][category]
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .filter_map(|name| name.as_str())
+            .filter(|name| kept_names.iter().any(|kept| kept == name))
+            .collect();
+        assert!(kept_names.len() > 1);
+        assert_eq!(kept_names, source_names, "order of {}", category);
+    }
+    Ok(())
+}
+
+#[test]
+fn it_filters_documents_held_in_strings() -> Result<(), Box<dyn std::error::Error>> {
+    let input = std::fs::read_to_string("tests/resources/petstore.yaml")?;
+    let parameters = || FilteringParameters {
+        paths: Some(vec![String::from("/store/order")]),
+        ..Default::default()
+    };
+
+    let yaml = filter_str(&input, None, parameters())?;
+    let from_yaml: OpenAPI = serde_yaml::from_str(&yaml)?;
+    assert_eq!(schema_names(&from_yaml), vec!["Order"]);
+
+    let json = filter_str(&input, Some(Format::Json), parameters())?;
+    let from_json: OpenAPI = serde_json::from_str(&json)?;
+    assert_eq!(from_json, from_yaml);
+    assert_eq!(filter_str(&json, None, parameters())?, json);
+
+    let error = filter_str("openapi: 3.0.3", None, parameters()).unwrap_err();
+    assert!(matches!(error, FilterError::Parse(_)));
+    Ok(())
+}